@@ -0,0 +1,274 @@
+/*
+ * Gõ Nhanh - Vietnamese IME Core
+ *
+ * C interface of libgonhanh_core (see core/src/lib.rs for full docs).
+ * Kept in sync with the Rust exports by core/tests/abi_test.rs.
+ *
+ * Hosts should check at startup:
+ *
+ *     if (ime_abi_version() != GONHANH_ABI_VERSION) { refuse to load }
+ *
+ * and use ime_capabilities() to enable optional features.
+ */
+
+#ifndef GONHANH_H
+#define GONHANH_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* ============================================================
+ * Version & Capabilities
+ * ============================================================ */
+
+#define GONHANH_ABI_VERSION 1
+
+#define IME_CAP_SHORTCUTS       0x0001 /* ime_add_shortcut */
+#define IME_CAP_AUTO_RESTORE    0x0002 /* ime_english_auto_restore */
+#define IME_CAP_ESC_RESTORE     0x0004 /* ime_esc_restore */
+#define IME_CAP_AUTO_CAPITALIZE 0x0008 /* ime_auto_capitalize */
+#define IME_CAP_PREEDIT         0x0010 /* ime_get_buffer */
+#define IME_CAP_RESTORE_WORD    0x0020 /* ime_restore_word */
+#define IME_CAP_ENGINE_HANDLES  0x0040 /* ime_engine_* */
+#define IME_CAP_PLATFORM_KEYS   0x0080 /* ime_key_platform */
+#define IME_CAP_CHAR_INPUT      0x0100 /* ime_char */
+#define IME_CAP_LAYOUTS         0x0200 /* ime_layout, ime_custom_layout */
+#define IME_CAP_CONFIG          0x0400 /* ime_get_config, ime_set_config */
+#define IME_CAP_OUTPUT_CHUNKS   0x0800 /* ime_result_next_chunk */
+#define IME_CAP_OUTPUT_ENCODING 0x1000 /* ime_result_utf8, ime_result_utf16 */
+#define IME_CAP_EVENTS          0x2000 /* ime_set_event_callback */
+#define IME_CAP_FOCUS_CONTEXTS  0x4000 /* ime_focus */
+#define IME_CAP_METHOD_TABLES   0x8000 /* ime_method_table */
+#define IME_CAP_BRACKET_VOWELS  0x10000 /* ime_bracket_vowels */
+#define IME_CAP_KEY_BINDINGS    0x20000 /* ime_key_bindings */
+#define IME_CAP_ORTHOGRAPHY     0x40000 /* ime_orthography */
+#define IME_CAP_CHARSETS        0x80000 /* ime_charset */
+#define IME_CAP_NFD             0x100000 /* IME_CHARSET_NFD */
+#define IME_CAP_CONVERT         0x200000 /* ime_convert */
+#define IME_CAP_VIQR_CHARSET    0x400000 /* IME_CHARSET_VIQR */
+#define IME_CAP_STRIP_DIACRITICS 0x800000 /* ime_strip_diacritics, ime_slugify */
+#define IME_CAP_CHANGE_CASE     0x1000000 /* ime_change_case */
+
+uint32_t ime_abi_version(void);
+uint32_t ime_capabilities(void);
+
+/* ============================================================
+ * Types
+ * ============================================================ */
+
+#define IME_RESULT_MAX_CHARS 64
+
+#define IME_ACTION_NONE    0 /* pass the key through */
+#define IME_ACTION_SEND    1 /* delete `backspace` chars, insert `chars` */
+#define IME_ACTION_RESTORE 2
+
+#define IME_FLAG_KEY_CONSUMED 0x01 /* don't pass the trigger key through */
+#define IME_FLAG_MORE_OUTPUT  0x02 /* fetch the rest with ime_result_next_chunk */
+
+typedef struct ImeResult {
+    uint32_t chars[IME_RESULT_MAX_CHARS]; /* UTF-32 codepoints */
+    uint8_t action;
+    uint8_t backspace;
+    uint8_t count;
+    uint8_t flags;
+} ImeResult;
+
+/* Opaque engine instance (ime_engine_new / ime_engine_free) */
+typedef struct ImeEngine ImeEngine;
+
+/* Keycode namespaces for ime_key_platform */
+#define IME_NS_MAC_VK     0
+#define IME_NS_WIN_VK     1
+#define IME_NS_X11_KEYSYM 2
+#define IME_NS_EVDEV      3
+
+/* Modifier bits for ime_key_platform */
+#define IME_MOD_CAPS  0x01
+#define IME_MOD_CTRL  0x02
+#define IME_MOD_SHIFT 0x04
+
+/* Input methods for ime_method */
+#define IME_METHOD_TELEX     0
+#define IME_METHOD_VNI       1
+#define IME_METHOD_VIQR      2
+#define IME_METHOD_CUSTOM    3 /* table from ime_method_table */
+#define IME_METHOD_TELEX_VNI 4 /* Telex letters and VNI digits together */
+#define IME_METHOD_DIRECT    5 /* Vietnamese keyboard layout (number row) */
+
+/* Orthography profiles for ime_orthography */
+#define IME_ORTHOGRAPHY_STANDARD  0
+#define IME_ORTHOGRAPHY_HIGHLANDS 1 /* Ê-đê, Jrai, Bahnar, M'nông: ĕ ŏ ŭ ơ̆ č ñ, finals h l r s */
+
+/* Output charsets for ime_charset (legacy ones: chars are bytes, backspace counts bytes) */
+#define IME_CHARSET_UNICODE 0
+#define IME_CHARSET_TCVN3   1 /* ABC, .VnTime fonts */
+#define IME_CHARSET_VNI_WIN 2 /* VNI-Times fonts: "á" is the two bytes "aù" */
+#define IME_CHARSET_VISCII  3
+#define IME_CHARSET_NFD     4 /* Decomposed Unicode: "ế" is "e" U+0302 U+0301 */
+#define IME_CHARSET_VIQR    5 /* ASCII diacritics: "ế" is "e^'" */
+
+/* Cases for ime_change_case */
+#define IME_CASE_LOWER    0
+#define IME_CASE_UPPER    1
+#define IME_CASE_TITLE    2 /* First letter of each word */
+#define IME_CASE_SENTENCE 3 /* First letter of each sentence */
+#define IME_CASE_TOGGLE   4
+
+/* Built-in layouts for ime_layout */
+#define IME_LAYOUT_QWERTY  0
+#define IME_LAYOUT_DVORAK  1
+#define IME_LAYOUT_COLEMAK 2
+#define IME_LAYOUT_AZERTY  3
+#define IME_LAYOUT_QWERTZ  4
+
+/* Encodings for ime_output_encoding */
+#define IME_ENCODING_UTF32 0
+#define IME_ENCODING_UTF8  1
+#define IME_ENCODING_UTF16 2
+
+/* Events for ime_set_event_callback (text / detail) */
+#define IME_EVENT_COMMIT          1 /* word / raw keystrokes */
+#define IME_EVENT_AUTO_RESTORE    2 /* restored English / replaced text */
+#define IME_EVENT_ESC_RESTORE     3 /* raw keystrokes / replaced text */
+#define IME_EVENT_SHORTCUT        4 /* replacement / trigger */
+#define IME_EVENT_AUTO_CAPITALIZE 5 /* uppercased letter / "" */
+
+/* Strings are UTF-8 and only valid during the callback */
+typedef struct ImeEvent {
+    uint8_t kind;
+    const char *text;
+    const char *detail;
+} ImeEvent;
+
+typedef void (*ImeEventCallback)(const ImeEvent *event, void *user_data);
+
+/* ============================================================
+ * Global Engine
+ * ============================================================ */
+
+void ime_init(void);
+
+/* Key input (free results with ime_free) */
+ImeResult *ime_key(uint16_t key, bool caps, bool ctrl);
+ImeResult *ime_key_ext(uint16_t key, bool caps, bool ctrl, bool shift);
+ImeResult *ime_key_platform(uint8_t ns, uint32_t code, uint8_t mods);
+ImeResult *ime_char(uint32_t codepoint);
+void ime_free(ImeResult *r);
+
+/* Result output */
+ImeResult *ime_result_next_chunk(void);
+int64_t ime_result_utf8(const ImeResult *result, char *out, int64_t max_len);
+int64_t ime_result_utf16(const ImeResult *result, uint16_t *out, int64_t max_len);
+bool ime_output_encoding(uint8_t encoding);
+int64_t ime_result_text(const ImeResult *result, void *out, int64_t max_len);
+
+/* Settings */
+void ime_method(uint8_t method);
+bool ime_method_table(const char *table);
+bool ime_key_bindings(const char *bindings);
+bool ime_layout(uint8_t layout);
+bool ime_custom_layout(const char *map);
+void ime_enabled(bool enabled);
+void ime_skip_w_shortcut(bool skip);
+void ime_esc_restore(bool enabled);
+void ime_free_tone(bool enabled);
+void ime_modern(bool modern);
+void ime_english_auto_restore(bool enabled);
+void ime_auto_capitalize(bool enabled);
+void ime_bracket_vowels(bool enabled);
+bool ime_orthography(uint8_t orthography);
+bool ime_charset(uint8_t charset);
+int64_t ime_get_config(char *out, int64_t max_len);
+bool ime_set_config(const char *text);
+
+/* State */
+void ime_clear(void);
+void ime_clear_all(void);
+void ime_focus(uint64_t context_id);
+void ime_context_capacity(uint32_t capacity);
+void ime_forget_context(uint64_t context_id);
+int64_t ime_get_buffer(uint32_t *out, int64_t max_len);
+void ime_restore_word(const char *word);
+
+/* Events (callback may be NULL to unregister) */
+void ime_set_event_callback(ImeEventCallback callback, void *user_data);
+
+/* Shortcuts */
+void ime_add_shortcut(const char *trigger, const char *replacement);
+void ime_remove_shortcut(const char *trigger);
+void ime_clear_shortcuts(void);
+
+/* Text conversion (no ime_init needed; options = config text or NULL) */
+int64_t ime_convert(const char *input, uint8_t method, const char *options, char *out, int64_t max_len);
+int64_t ime_strip_diacritics(const char *text, char *out, int64_t max_len);
+int64_t ime_slugify(const char *text, uint8_t separator, bool lowercase, char *out, int64_t max_len);
+int64_t ime_change_case(const char *text, uint8_t case_id, char *out, int64_t max_len);
+
+/* ============================================================
+ * Engine Handles
+ * ============================================================ */
+
+ImeEngine *ime_engine_new(void);
+void ime_engine_free(ImeEngine *handle);
+
+ImeResult *ime_engine_key(ImeEngine *handle, uint16_t key, bool caps, bool ctrl);
+ImeResult *ime_engine_key_ext(ImeEngine *handle, uint16_t key, bool caps, bool ctrl, bool shift);
+ImeResult *ime_engine_key_platform(ImeEngine *handle, uint8_t ns, uint32_t code, uint8_t mods);
+ImeResult *ime_engine_char(ImeEngine *handle, uint32_t codepoint);
+
+ImeResult *ime_engine_result_next_chunk(ImeEngine *handle);
+bool ime_engine_output_encoding(ImeEngine *handle, uint8_t encoding);
+int64_t ime_engine_result_text(ImeEngine *handle, const ImeResult *result, void *out,
+                               int64_t max_len);
+
+void ime_engine_method(ImeEngine *handle, uint8_t method);
+bool ime_engine_method_table(ImeEngine *handle, const char *table);
+bool ime_engine_key_bindings(ImeEngine *handle, const char *bindings);
+bool ime_engine_layout(ImeEngine *handle, uint8_t layout);
+bool ime_engine_custom_layout(ImeEngine *handle, const char *map);
+void ime_engine_enabled(ImeEngine *handle, bool enabled);
+void ime_engine_skip_w_shortcut(ImeEngine *handle, bool skip);
+void ime_engine_esc_restore(ImeEngine *handle, bool enabled);
+void ime_engine_free_tone(ImeEngine *handle, bool enabled);
+void ime_engine_modern(ImeEngine *handle, bool modern);
+void ime_engine_english_auto_restore(ImeEngine *handle, bool enabled);
+void ime_engine_auto_capitalize(ImeEngine *handle, bool enabled);
+void ime_engine_bracket_vowels(ImeEngine *handle, bool enabled);
+bool ime_engine_orthography(ImeEngine *handle, uint8_t orthography);
+bool ime_engine_charset(ImeEngine *handle, uint8_t charset);
+int64_t ime_engine_get_config(ImeEngine *handle, char *out, int64_t max_len);
+bool ime_engine_set_config(ImeEngine *handle, const char *text);
+
+void ime_engine_clear(ImeEngine *handle);
+void ime_engine_clear_all(ImeEngine *handle);
+void ime_engine_focus(ImeEngine *handle, uint64_t context_id);
+void ime_engine_context_capacity(ImeEngine *handle, uint32_t capacity);
+void ime_engine_forget_context(ImeEngine *handle, uint64_t context_id);
+int64_t ime_engine_get_buffer(ImeEngine *handle, uint32_t *out, int64_t max_len);
+void ime_engine_restore_word(ImeEngine *handle, const char *word);
+
+void ime_engine_set_event_callback(ImeEngine *handle, ImeEventCallback callback,
+                                   void *user_data);
+
+void ime_engine_add_shortcut(ImeEngine *handle, const char *trigger, const char *replacement);
+void ime_engine_remove_shortcut(ImeEngine *handle, const char *trigger);
+void ime_engine_clear_shortcuts(ImeEngine *handle);
+
+/* ============================================================
+ * Updater
+ * ============================================================ */
+
+/* -1 if v1 < v2, 0 if equal, 1 if v1 > v2, -99 on parse error */
+int32_t version_compare(const char *v1, const char *v2);
+/* 1 if latest > current, 0 if not, -99 on parse error */
+int32_t version_has_update(const char *current, const char *latest);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GONHANH_H */
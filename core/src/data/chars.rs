@@ -3,6 +3,7 @@
 //! Provides bidirectional conversion between:
 //! - Forward: base vowels + modifiers + marks → Vietnamese Unicode
 //! - Reverse: Vietnamese Unicode → components (for buffer restoration)
+//! - Decomposed (NFD) letters: base + combining diacritics, both ways
 //!
 //! ## Design Principles
 //! - Single lookup table for all vowel combinations (12 bases × 6 marks = 72)
@@ -11,6 +12,7 @@
 //!
 //! ## Character Components
 //! - Base vowel: a, ă, â, e, ê, i, o, ô, ơ, u, ư, y
+//!   (plus ĕ, ŏ, ŭ, ơ̆ of the highlands orthographies, see `orthography`)
 //! - Mark (dấu thanh): none, sắc, huyền, hỏi, ngã, nặng
 //! - Case: lowercase, uppercase
 
@@ -21,6 +23,8 @@ pub mod tone {
     pub const NONE: u8 = 0;
     pub const CIRCUMFLEX: u8 = 1; // ^ (mũ): a→â, e→ê, o→ô
     pub const HORN: u8 = 2; // ơ, ư or breve ă
+    pub const SHORT: u8 = 3; // short vowels ĕ, ŏ, ŭ (highlands orthographies; never ă)
+    pub const HORN_SHORT: u8 = 4; // ơ̆ (highlands orthographies)
 }
 
 /// Marks (dấu thanh) - Vietnamese tone marks
@@ -33,8 +37,21 @@ pub mod mark {
     pub const NANG: u8 = 5; // nặng (ạ)
 }
 
+/// Combining breve, for ơ̆ (no precomposed form)
+pub const COMBINING_BREVE: char = '\u{306}';
+
+/// Combining marks [sắc, huyền, hỏi, ngã, nặng], for letters without
+/// precomposed marked forms (ĕ́)
+pub const COMBINING_MARKS: [char; 5] = ['\u{301}', '\u{300}', '\u{309}', '\u{303}', '\u{323}'];
+
+/// Combining circumflex, horn and caron of decomposed (NFD) letters (ê, ơ, č)
+pub const COMBINING_CIRCUMFLEX: char = '\u{302}';
+pub const COMBINING_HORN: char = '\u{31B}';
+pub const COMBINING_CARON: char = '\u{30C}';
+
 /// Vietnamese vowel lookup table
 /// Each entry: (base_char, [sắc, huyền, hỏi, ngã, nặng])
+/// Highlands letters (ĕ ŏ ŭ ơ̆) have no precomposed marked forms
 const VOWEL_TABLE: [(char, [char; 5]); 12] = [
     ('a', ['á', 'à', 'ả', 'ã', 'ạ']),
     ('ă', ['ắ', 'ằ', 'ẳ', 'ẵ', 'ặ']),
@@ -54,30 +71,38 @@ const VOWEL_TABLE: [(char, [char; 5]); 12] = [
 ///
 /// # Arguments
 /// * `key` - Virtual keycode (a, e, i, o, u, y)
-/// * `tone` - Tone modifier: 0=none, 1=circumflex(^), 2=horn/breve
+/// * `tone` - Tone modifier: 0=none, 1=circumflex(^), 2=horn/breve ă, 3=short ĕŏŭ, 4=ơ̆
 ///
 /// # Returns
-/// Base vowel character: a, ă, â, e, ê, i, o, ô, ơ, u, ư, y
+/// Base vowel character: a, ă, â, e, ê, ĕ, i, o, ô, ơ, ŏ, u, ư, ŭ, y
+/// (ơ for ơ̆, whose breve is combining)
 fn get_base_char(key: u16, t: u8) -> Option<char> {
     match key {
         keys::A => Some(match t {
             tone::CIRCUMFLEX => 'â',
             tone::HORN => 'ă', // breve for 'a'
-            _ => 'a',
+            tone::NONE => 'a',
+            _ => return None,
         }),
         keys::E => Some(match t {
             tone::CIRCUMFLEX => 'ê',
-            _ => 'e',
+            tone::SHORT => 'ĕ',
+            tone::NONE => 'e',
+            _ => return None,
         }),
         keys::I => Some('i'),
         keys::O => Some(match t {
             tone::CIRCUMFLEX => 'ô',
-            tone::HORN => 'ơ',
-            _ => 'o',
+            tone::HORN | tone::HORN_SHORT => 'ơ',
+            tone::SHORT => 'ŏ',
+            tone::NONE => 'o',
+            _ => return None,
         }),
         keys::U => Some(match t {
             tone::HORN => 'ư',
-            _ => 'u',
+            tone::SHORT => 'ŭ',
+            tone::NONE => 'u',
+            _ => return None,
         }),
         keys::Y => Some('y'),
         _ => None,
@@ -91,16 +116,17 @@ fn get_base_char(key: u16, t: u8) -> Option<char> {
 /// # Arguments
 /// * `base` - Base vowel character (a, ă, â, e, ê, i, o, ô, ơ, u, ư, y)
 /// * `mark` - Mark: 0=none, 1=sắc, 2=huyền, 3=hỏi, 4=ngã, 5=nặng
-fn apply_mark(base: char, m: u8) -> char {
+///
+/// Returns None if the marked letter has no precomposed form (ĕ́).
+fn apply_mark(base: char, m: u8) -> Option<char> {
     if m == mark::NONE || m > mark::NANG {
-        return base;
+        return Some(base);
     }
 
     VOWEL_TABLE
         .iter()
         .find(|(b, _)| *b == base)
         .map(|(_, marks)| marks[(m - 1) as usize])
-        .unwrap_or(base)
 }
 
 /// Convert to uppercase using Rust's Unicode-aware method
@@ -118,14 +144,19 @@ fn to_upper(ch: char) -> char {
 /// * `caps` - Uppercase flag
 /// * `tone` - Tone modifier: 0=none, 1=circumflex(^), 2=horn/breve
 /// * `mark` - Mark: 0=none, 1=sắc, 2=huyền, 3=hỏi, 4=ngã, 5=nặng
+///
+/// Returns None for letters that need combining marks (ơ̆, ĕ́); see `push_letter`.
 pub fn to_char(key: u16, caps: bool, tone: u8, mark: u8) -> Option<char> {
     // Handle D specially (not a vowel but needs conversion)
     if key == keys::D {
         return Some(if caps { 'D' } else { 'd' });
     }
+    if tone == tone::HORN_SHORT {
+        return None;
+    }
 
     let base = get_base_char(key, tone)?;
-    let marked = apply_mark(base, mark);
+    let marked = apply_mark(base, mark)?;
     Some(if caps { to_upper(marked) } else { marked })
 }
 
@@ -138,6 +169,65 @@ pub fn get_d(caps: bool) -> char {
     }
 }
 
+/// Stroked consonant: đ, and č/ñ of the highlands orthographies
+pub fn get_stroked(key: u16, caps: bool) -> Option<char> {
+    let ch = match key {
+        keys::D => 'đ',
+        keys::C => 'č',
+        keys::N => 'ñ',
+        _ => return None,
+    };
+    Some(if caps { to_upper(ch) } else { ch })
+}
+
+/// Push a letter with its diacritics, for letters `to_char` can't give
+/// as one char: ơ̆ is ơ + combining breve, marks on ĕ ŏ ŭ ơ̆ are combining
+///
+/// Returns false (nothing pushed) if `key` isn't a vowel or stroked consonant.
+pub fn push_letter(
+    out: &mut impl Extend<char>,
+    key: u16,
+    caps: bool,
+    t: u8,
+    m: u8,
+    stroke: bool,
+) -> bool {
+    if stroke {
+        if let Some(ch) = get_stroked(key, caps) {
+            out.extend([ch]);
+            return true;
+        }
+    }
+    if let Some(ch) = to_char(key, caps, t, m) {
+        out.extend([ch]);
+        return true;
+    }
+    let Some(base) = get_base_char(key, t) else {
+        return false;
+    };
+    out.extend([if caps { to_upper(base) } else { base }]);
+    if t == tone::HORN_SHORT {
+        out.extend([COMBINING_BREVE]);
+    }
+    if (mark::SAC..=mark::NANG).contains(&m) {
+        out.extend([COMBINING_MARKS[(m - 1) as usize]]);
+    }
+    true
+}
+
+/// Mark of a combining mark character (U+0301 → sắc)
+pub fn combining_mark(c: char) -> Option<u8> {
+    COMBINING_MARKS
+        .iter()
+        .position(|&m| m == c)
+        .map(|i| i as u8 + 1)
+}
+
+/// Whether `c` is a combining diacritic (U+0300–U+036F), part of the letter before it
+pub fn is_combining(c: char) -> bool {
+    ('\u{300}'..='\u{36f}').contains(&c)
+}
+
 // ============================================================
 // REVERSE PARSING: Vietnamese char → buffer components
 // ============================================================
@@ -356,9 +446,21 @@ pub fn parse_char(c: char) -> Option<ParsedChar> {
         'ỵ' => vowel!(Y, false, T0, NANG),
         'Ỵ' => vowel!(Y, true, T0, NANG),
 
+        // ===== Highlands letters (breve) =====
+        'ĕ' => vowel!(E, false, tone::SHORT, M0),
+        'Ĕ' => vowel!(E, true, tone::SHORT, M0),
+        'ŏ' => vowel!(O, false, tone::SHORT, M0),
+        'Ŏ' => vowel!(O, true, tone::SHORT, M0),
+        'ŭ' => vowel!(U, false, tone::SHORT, M0),
+        'Ŭ' => vowel!(U, true, tone::SHORT, M0),
+
         // ===== Consonants =====
         'đ' => Some(ParsedChar::stroke(D, false)),
         'Đ' => Some(ParsedChar::stroke(D, true)),
+        'č' => Some(ParsedChar::stroke(C, false)),
+        'Č' => Some(ParsedChar::stroke(C, true)),
+        'ñ' => Some(ParsedChar::stroke(N, false)),
+        'Ñ' => Some(ParsedChar::stroke(N, true)),
         'd' => Some(ParsedChar::new(D, false, 0, 0)),
         'D' => Some(ParsedChar::new(D, true, 0, 0)),
         'b' => Some(ParsedChar::new(B, false, 0, 0)),
@@ -404,6 +506,106 @@ pub fn parse_char(c: char) -> Option<ParsedChar> {
     }
 }
 
+// ============================================================
+// DECOMPOSED TEXT (NFD): base letter + combining diacritics
+// ============================================================
+
+/// Apply a combining diacritic to the letter before it (NFD text)
+///
+/// Returns false (letter unchanged) if it doesn't fit: wrong base, or the
+/// letter already has a diacritic of that kind.
+pub fn apply_combining(p: &mut ParsedChar, c: char) -> bool {
+    use keys::{A, C, E, N, O, U};
+    let bare = p.tone == tone::NONE;
+    match (c, p.key) {
+        (COMBINING_CIRCUMFLEX, A | E | O) if bare => p.tone = tone::CIRCUMFLEX,
+        (COMBINING_HORN, O | U) if bare => p.tone = tone::HORN,
+        (COMBINING_BREVE, A) if bare => p.tone = tone::HORN,
+        (COMBINING_BREVE, E | O | U) if bare => p.tone = tone::SHORT,
+        (COMBINING_BREVE, O) if p.tone == tone::HORN => p.tone = tone::HORN_SHORT,
+        (COMBINING_CARON, C) | ('\u{303}', N) if !p.stroke => p.stroke = true,
+        _ => match combining_mark(c) {
+            Some(m) if keys::is_vowel(p.key) && p.mark == mark::NONE => p.mark = m,
+            _ => return false,
+        },
+    }
+    true
+}
+
+/// Push `c` decomposed (NFD): the base letter, then its combining
+/// diacritics in canonical order (horn, dot below, then the others)
+///
+/// đ and chars that aren't Vietnamese letters are pushed unchanged.
+pub fn push_decomposed(out: &mut impl Extend<char>, c: char) {
+    let base = match parse_char(c) {
+        Some(p) if p.key == keys::D => None,
+        Some(p) if p.stroke => Some((p, if p.key == keys::C { 'c' } else { 'n' })),
+        Some(p) if keys::is_vowel(p.key) => {
+            to_char(p.key, false, tone::NONE, mark::NONE).map(|b| (p, b))
+        }
+        _ => None,
+    };
+    let Some((p, base)) = base else {
+        out.extend([c]);
+        return;
+    };
+    let base = if p.caps { to_upper(base) } else { base };
+    let mut marks: Vec<char> = match (p.tone, p.key) {
+        (tone::CIRCUMFLEX, _) => vec![COMBINING_CIRCUMFLEX],
+        (tone::HORN, keys::A) | (tone::SHORT, _) => vec![COMBINING_BREVE],
+        (tone::HORN, _) => vec![COMBINING_HORN],
+        (tone::HORN_SHORT, _) => vec![COMBINING_HORN, COMBINING_BREVE],
+        _ => Vec::new(),
+    };
+    if p.stroke {
+        marks.push(if p.key == keys::C {
+            COMBINING_CARON
+        } else {
+            COMBINING_MARKS[3]
+        });
+    }
+    if (mark::SAC..=mark::NANG).contains(&p.mark) {
+        marks.push(COMBINING_MARKS[(p.mark - 1) as usize]);
+    }
+    // Canonical combining classes: horn 216, dot below 220, the rest 230
+    marks.sort_by_key(|&m| match m {
+        COMBINING_HORN => 216,
+        '\u{323}' => 220,
+        _ => 230,
+    });
+    out.extend([base]);
+    out.extend(marks);
+}
+
+/// Compose decomposed letters (NFD → NFC)
+///
+/// Letters without a precomposed form keep their combining marks (ơ̆, ĕ́).
+pub fn compose(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut letter: Option<(ParsedChar, char)> = None;
+    let flush = |out: &mut String, letter: Option<(ParsedChar, char)>| {
+        if let Some((p, c)) = letter {
+            if !push_letter(out, p.key, p.caps, p.tone, p.mark, p.stroke) {
+                out.push(c);
+            }
+        }
+    };
+    for c in text.chars() {
+        if let Some((p, _)) = letter.as_mut() {
+            if apply_combining(p, c) {
+                continue;
+            }
+        }
+        flush(&mut out, letter.take());
+        match parse_char(c) {
+            Some(p) => letter = Some((p, c)),
+            None => out.push(c),
+        }
+    }
+    flush(&mut out, letter);
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -507,6 +709,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_highlands_letters() {
+        assert_eq!(to_char(keys::E, false, tone::SHORT, 0), Some('ĕ'));
+        assert_eq!(to_char(keys::U, true, tone::SHORT, 0), Some('Ŭ'));
+        // No precomposed forms
+        assert_eq!(to_char(keys::O, false, tone::HORN_SHORT, 0), None);
+        assert_eq!(to_char(keys::O, false, tone::SHORT, mark::SAC), None);
+        // ă is tone::HORN on a; the highlands short tone doesn't apply to it
+        assert_eq!(to_char(keys::A, false, tone::SHORT, 0), None);
+        assert_eq!(get_stroked(keys::C, true), Some('Č'));
+        assert_eq!(get_stroked(keys::N, false), Some('ñ'));
+        assert_eq!(get_stroked(keys::T, false), None);
+
+        let mut s = String::new();
+        assert!(push_letter(
+            &mut s,
+            keys::O,
+            true,
+            tone::HORN_SHORT,
+            0,
+            false
+        ));
+        assert!(push_letter(
+            &mut s,
+            keys::E,
+            false,
+            tone::SHORT,
+            mark::SAC,
+            false
+        ));
+        assert!(push_letter(&mut s, keys::N, false, 0, 0, true));
+        assert!(!push_letter(&mut s, keys::T, false, 0, 0, false));
+        assert_eq!(s, "Ơ\u{306}ĕ\u{301}ñ");
+
+        let p = parse_char('ŏ').unwrap();
+        assert_eq!((p.key, p.tone), (keys::O, tone::SHORT));
+        let p = parse_char('Č').unwrap();
+        assert_eq!((p.key, p.caps, p.stroke), (keys::C, true, true));
+        assert_eq!(combining_mark('\u{323}'), Some(mark::NANG));
+        assert_eq!(combining_mark('a'), None);
+    }
+
     #[test]
     fn test_parse_d_stroke() {
         let p = parse_char('đ').unwrap();
@@ -552,4 +796,43 @@ mod tests {
             assert_eq!((p.key, p.tone, p.mark), (key, t, m), "Failed for '{}'", ch);
         }
     }
+
+    #[test]
+    fn test_decompose() {
+        let nfd = |s: &str| {
+            let mut out = String::new();
+            s.chars().for_each(|c| push_decomposed(&mut out, c));
+            out
+        };
+        // Same forms as Unicode canonical decomposition
+        assert_eq!(nfd("ế"), "e\u{302}\u{301}");
+        assert_eq!(nfd("ệ"), "e\u{323}\u{302}");
+        assert_eq!(nfd("ợ"), "o\u{31B}\u{323}");
+        assert_eq!(nfd("ặ"), "a\u{323}\u{306}");
+        assert_eq!(nfd("Ứ"), "U\u{31B}\u{301}");
+        assert_eq!(nfd("ĕčÑ"), "e\u{306}c\u{30C}N\u{303}");
+        // đ and non-letters have no decomposition
+        assert_eq!(nfd("đi 1!"), "đi 1!");
+    }
+
+    #[test]
+    fn test_compose() {
+        let words = [
+            "Tiếng Việt",
+            "được",
+            "ặc",
+            "ỰA",
+            "Ơ\u{306}ñĕ\u{301}",
+            "đăk č",
+        ];
+        for word in words {
+            let mut nfd = String::new();
+            word.chars().for_each(|c| push_decomposed(&mut nfd, c));
+            assert_eq!(compose(&nfd), word, "{:?}", nfd);
+        }
+        // Any mark order composes; marks that don't fit stay combining
+        assert_eq!(compose("e\u{301}\u{302}"), "ế");
+        assert_eq!(compose("x\u{301}"), "x\u{301}");
+        assert_eq!(compose("\u{301}a"), "\u{301}a");
+    }
 }
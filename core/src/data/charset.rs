@@ -0,0 +1,613 @@
+//! Legacy Vietnamese charsets
+//!
+//! Older documents use 8-bit fonts where each byte is a glyph: TCVN3
+//! (ABC, `.VnTime`), VNI-Windows (`VNI-Times`) and VISCII. Text in these
+//! charsets is carried as chars U+0000–U+00FF, one per byte, which is what
+//! hosts type into and read back from documents using those fonts.
+//!
+//! VNI-Windows writes most letters as base + mark byte ("á" → "aù",
+//! "ệ" → "eä"), so a letter can take two code units. Letters a charset
+//! can't hold (ĕ, č of the highlands orthography) stay in Unicode.
+//!
+//! NFD is Unicode with every letter decomposed into base + combining
+//! diacritics ("ế" → "e" U+0302 U+0301), as macOS filenames and some web
+//! editors store it. VIQR (RFC 1456) writes diacritics as ASCII after the
+//! letter ("ế" → "e^'", "đ" → "dd"), with `\` keeping a following
+//! diacritic char literal ("Hà Nội\.").
+//!
+//! `convert_charset` converts existing text between any two of these.
+//!
+//! VPS is not supported: it needs a byte table verified against a
+//! reference mapping, and `charset=vps` is rejected until then.
+
+use super::chars::{self, mark, tone, ParsedChar};
+use super::keys;
+
+/// Charset ids (`Engine::set_charset`, FFI `ime_charset`)
+pub mod id {
+    pub const UNICODE: u8 = 0;
+    /// TCVN 5712:1993 VN3 (ABC)
+    pub const TCVN3: u8 = 1;
+    pub const VNI_WIN: u8 = 2;
+    pub const VISCII: u8 = 3;
+    /// Decomposed Unicode (base letter + combining diacritics)
+    pub const NFD: u8 = 4;
+    /// ASCII diacritics (RFC 1456)
+    pub const VIQR: u8 = 5;
+}
+
+/// Charset names for config text (index = id)
+pub const NAMES: [&str; 6] = ["unicode", "tcvn3", "vni_win", "viscii", "nfd", "viqr"];
+
+/// Single-byte charset: the letter of each byte from `first` on (space where
+/// the byte isn't a letter), plus letters placed in C0 control positions
+struct ByteTable {
+    first: u8,
+    rows: [&'static str; 8],
+    c0: &'static [(u8, char)],
+}
+
+/// TCVN3 (ABC): lowercase letters plus the capitals with their own byte
+/// (toned capitals come from the matching uppercase font, see `encode_char`)
+const TCVN3: ByteTable = ByteTable {
+    first: 0xA0,
+    rows: [
+        " ĂÂÊÔƠƯĐăâêôơưđ ", // 0xA0
+        "     àảãáạ ằẳẵắ ", // 0xB0
+        "      ặầẩẫấậè ẻẽ", // 0xC0
+        "éẹềểễếệìỉ   ĩíịò", // 0xD0
+        " ỏõóọồổỗốộờởỡớợù", // 0xE0
+        " ủũúụừửữứựỳỷỹýỵ ", // 0xF0
+        "",
+        "",
+    ],
+    c0: &[],
+};
+
+/// VISCII (RFC 1456): all 134 letters, six of them in C0 control positions
+const VISCII: ByteTable = ByteTable {
+    first: 0x80,
+    rows: [
+        "ẠẮẰẶẤẦẨẬẼẸẾỀỂỄỆỐ", // 0x80
+        "ỒỔỖỘỢỚỜỞỊỎỌỈỦŨỤỲ", // 0x90
+        "Õắằặấầẩậẽẹếềểễệố", // 0xA0
+        "ồổỗỠƠộờởịỰỨỪỬơớƯ", // 0xB0
+        "ÀÁÂÃẢĂẳẵÈÉÊẺÌÍĨỳ", // 0xC0
+        "ĐứÒÓÔạỷừửÙÚỹỵÝỡư", // 0xD0
+        "àáâãảăữẫèéêẻìíĩỉ", // 0xE0
+        "đựòóôõỏọụùúũủýợỮ", // 0xF0
+    ],
+    c0: &[
+        (0x02, 'Ẳ'),
+        (0x05, 'Ẵ'),
+        (0x06, 'Ẫ'),
+        (0x14, 'Ỷ'),
+        (0x19, 'Ỹ'),
+        (0x1E, 'Ỵ'),
+    ],
+};
+
+impl ByteTable {
+    /// (byte, letter) pairs
+    fn letters(&self) -> impl Iterator<Item = (u8, char)> + '_ {
+        let high = self.rows.iter().flat_map(|row| row.chars());
+        (self.first..=0xFF)
+            .zip(high)
+            .filter(|&(_, c)| c != ' ')
+            .chain(self.c0.iter().copied())
+    }
+
+    /// Byte of a letter, as a char
+    fn byte(&self, c: char) -> Option<char> {
+        self.letters()
+            .find(|&(_, ch)| ch == c)
+            .map(|(b, _)| char::from(b))
+    }
+
+    /// Letter of a byte (unchanged if not a letter)
+    fn letter(&self, c: char) -> char {
+        self.letters()
+            .find(|&(b, _)| u32::from(b) == c as u32)
+            .map_or(c, |(_, ch)| ch)
+    }
+}
+
+/// VNI-Windows bytes after the base letter, by mark (sắc, huyền, hỏi, ngã, nặng)
+const VNI_MARKS: [[char; 5]; 2] = [['ù', 'ø', 'û', 'õ', 'ï'], ['Ù', 'Ø', 'Û', 'Õ', 'Ï']];
+
+/// VNI-Windows circumflex bytes (â ê ô), unmarked first
+const VNI_CIRCUMFLEX: [[char; 6]; 2] = [
+    ['â', 'á', 'à', 'å', 'ã', 'ä'],
+    ['Â', 'Á', 'À', 'Å', 'Ã', 'Ä'],
+];
+
+/// VNI-Windows breve bytes (ă), unmarked first
+const VNI_BREVE: [[char; 6]; 2] = [
+    ['ê', 'é', 'è', 'ú', 'ü', 'ë'],
+    ['Ê', 'É', 'È', 'Ú', 'Ü', 'Ë'],
+];
+
+/// VNI-Windows single-byte marked i (í ì ỉ ĩ ị)
+const VNI_I: [[char; 5]; 2] = [['í', 'ì', 'æ', 'ó', 'ò'], ['Í', 'Ì', 'Æ', 'Ó', 'Ò']];
+
+/// Whether `charset_id` is a known charset
+pub fn is_known(charset_id: u8) -> bool {
+    (charset_id as usize) < NAMES.len()
+}
+
+/// Charset id by config name (`vni_win`)
+pub fn from_name(name: &str) -> Option<u8> {
+    NAMES.iter().position(|&n| n == name).map(|i| i as u8)
+}
+
+/// Push `c` encoded in `charset_id`
+///
+/// Returns false if the charset can't hold `c`; it is then pushed unchanged.
+/// TCVN3 has no toned capitals: they take the lowercase byte, which the
+/// uppercase font (`.VnTimeH`) shows as a capital, and also return false
+/// since the case is lost.
+pub fn encode_char(c: char, charset_id: u8, out: &mut impl Extend<char>) -> bool {
+    if c.is_ascii() {
+        out.extend([c]);
+        return true;
+    }
+    let encoded = match charset_id {
+        id::UNICODE => Some(c),
+        id::TCVN3 => match TCVN3.byte(c) {
+            Some(b) => Some(b),
+            None => {
+                let lower = c.to_lowercase().next().and_then(|l| TCVN3.byte(l));
+                out.extend([lower.unwrap_or(c)]);
+                return false;
+            }
+        },
+        id::VISCII => VISCII.byte(c),
+        id::VNI_WIN => return encode_vni(c, out),
+        id::NFD => {
+            chars::push_decomposed(out, c);
+            return true;
+        }
+        id::VIQR => return encode_viqr(c, out),
+        _ => None,
+    };
+    out.extend([encoded.unwrap_or(c)]);
+    encoded.is_some()
+}
+
+/// Text encoded in `charset_id` (see `encode_char`)
+pub fn encode(text: &str, charset_id: u8) -> String {
+    let mut out = String::with_capacity(text.len());
+    encode_into(text, charset_id, &mut out);
+    out
+}
+
+/// Encode `text` into `out`, returning the chars the charset can't hold
+fn encode_into(text: &str, charset_id: u8, out: &mut String) -> Vec<char> {
+    let mut unconvertible = Vec::new();
+    let mut iter = text.chars().peekable();
+    while let Some(c) = iter.next() {
+        if !encode_char(c, charset_id, out) && !unconvertible.contains(&c) {
+            unconvertible.push(c);
+        }
+        if charset_id == id::VIQR {
+            if let Some(&next) = iter.peek() {
+                if viqr_escapes(c, next) {
+                    out.push('\\');
+                }
+            }
+        }
+    }
+    unconvertible
+}
+
+/// Text in `charset_id` back to Unicode
+///
+/// Chars that aren't letters of the charset are kept unchanged, so Unicode
+/// Vietnamese outside U+0000–U+00FF passes through.
+pub fn decode(text: &str, charset_id: u8) -> String {
+    match charset_id {
+        id::TCVN3 => text.chars().map(|c| TCVN3.letter(c)).collect(),
+        id::VISCII => text.chars().map(|c| VISCII.letter(c)).collect(),
+        id::VNI_WIN => decode_vni(text),
+        id::NFD => chars::compose(text),
+        id::VIQR => decode_viqr(text),
+        _ => text.to_string(),
+    }
+}
+
+/// Parse one code unit of `charset_id` to buffer components
+///
+/// VNI-Windows base + mark pairs and NFD letters take several units:
+/// `decode` the text first.
+pub fn parse_char(c: char, charset_id: u8) -> Option<ParsedChar> {
+    match charset_id {
+        id::TCVN3 => chars::parse_char(TCVN3.letter(c)),
+        id::VISCII => chars::parse_char(VISCII.letter(c)),
+        id::VNI_WIN => vni_letter(c),
+        _ => chars::parse_char(c),
+    }
+}
+
+/// Code units one letter (base + combining marks) takes in `charset_id`
+///
+/// Letters the charset can't hold stay Unicode and count as one.
+pub fn letter_width(letter: &[char], charset_id: u8) -> usize {
+    let mut units = Vec::new();
+    if letter
+        .iter()
+        .all(|&c| encode_char(c, charset_id, &mut units))
+    {
+        units.len()
+    } else {
+        1
+    }
+}
+
+/// Text converted between charsets (`convert_charset`)
+#[derive(Debug, PartialEq)]
+pub struct Converted {
+    pub text: String,
+    /// Chars the target charset can't hold, kept unchanged in `text`
+    /// (TCVN3 toned capitals: written as the lowercase byte), each listed
+    /// once, in order of appearance
+    pub unconvertible: Vec<char>,
+}
+
+/// Convert text from one charset to another (Unikey toolkit style)
+///
+/// Letters go through Unicode, so any pair of charsets works and round
+/// trips are lossless where both hold the text; Unicode output is
+/// composed (NFC). TCVN3 writes toned capitals as lowercase bytes (see
+/// `encode_char`) and reports them as unconvertible. Returns None for
+/// unknown ids.
+pub fn convert_charset(text: &str, from: u8, to: u8) -> Option<Converted> {
+    if !is_known(from) || !is_known(to) {
+        return None;
+    }
+    let unicode = chars::compose(&decode(text, from));
+    let mut out = String::with_capacity(unicode.len());
+    let unconvertible = encode_into(&unicode, to, &mut out);
+    Some(Converted {
+        text: out,
+        unconvertible,
+    })
+}
+
+/// Encode in VNI-Windows; false (pushed unchanged) if not representable
+fn encode_vni(c: char, out: &mut impl Extend<char>) -> bool {
+    let Some(p) = chars::parse_char(c) else {
+        out.extend([c]);
+        return false;
+    };
+    let case = p.caps as usize;
+    let m = p.mark as usize;
+    if p.stroke {
+        if p.key != keys::D {
+            out.extend([c]);
+            return false;
+        }
+        out.extend([['ñ', 'Ñ'][case]]);
+        return true;
+    }
+    let base = chars::to_char(p.key, p.caps, tone::NONE, mark::NONE).unwrap_or(c);
+    match (p.key, p.tone) {
+        (_, tone::SHORT | tone::HORN_SHORT) => {
+            out.extend([c]);
+            return false;
+        }
+        (keys::I, _) if m > 0 => out.extend([VNI_I[case][m - 1]]),
+        (keys::Y, _) if p.mark == mark::NANG => out.extend([['î', 'Î'][case]]),
+        (keys::O, tone::HORN) | (keys::U, tone::HORN) => {
+            let horn = if p.key == keys::O {
+                ['ô', 'Ô']
+            } else {
+                ['ö', 'Ö']
+            };
+            out.extend([horn[case]]);
+            if m > 0 {
+                out.extend([VNI_MARKS[case][m - 1]]);
+            }
+        }
+        (keys::A, tone::HORN) => out.extend([base, VNI_BREVE[case][m]]),
+        (_, tone::CIRCUMFLEX) => out.extend([base, VNI_CIRCUMFLEX[case][m]]),
+        _ => {
+            out.extend([base]);
+            if m > 0 {
+                out.extend([VNI_MARKS[case][m - 1]]);
+            }
+        }
+    }
+    true
+}
+
+/// Letter written with a single VNI-Windows byte (ASCII letters, ơ ư đ, marked i, ỵ)
+fn vni_letter(c: char) -> Option<ParsedChar> {
+    let caps = c.is_uppercase();
+    let letter = |key, t, m| ParsedChar {
+        key,
+        caps,
+        tone: t,
+        mark: m,
+        stroke: false,
+    };
+    match c {
+        'ô' | 'Ô' => Some(letter(keys::O, tone::HORN, mark::NONE)),
+        'ö' | 'Ö' => Some(letter(keys::U, tone::HORN, mark::NONE)),
+        'î' | 'Î' => Some(letter(keys::Y, tone::NONE, mark::NANG)),
+        'ñ' | 'Ñ' => Some(ParsedChar {
+            stroke: true,
+            ..letter(keys::D, tone::NONE, mark::NONE)
+        }),
+        _ if c.is_ascii() => chars::parse_char(c),
+        _ => VNI_I[caps as usize]
+            .iter()
+            .position(|&i| i == c)
+            .map(|i| letter(keys::I, tone::NONE, i as u8 + 1)),
+    }
+}
+
+/// Tone and mark of a VNI-Windows byte following a base letter
+fn vni_modifier(c: char) -> Option<(u8, u8)> {
+    let find = |row: &[char]| row.iter().position(|&x| x == c).map(|i| i as u8);
+    for case in 0..2 {
+        if let Some(m) = find(&VNI_MARKS[case]) {
+            return Some((tone::NONE, m + 1));
+        }
+        if let Some(m) = find(&VNI_CIRCUMFLEX[case]) {
+            return Some((tone::CIRCUMFLEX, m));
+        }
+        if let Some(m) = find(&VNI_BREVE[case]) {
+            return Some((tone::HORN, m));
+        }
+    }
+    None
+}
+
+fn decode_vni(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut iter = text.chars().peekable();
+    while let Some(c) = iter.next() {
+        let Some(mut p) = vni_letter(c) else {
+            out.push(c);
+            continue;
+        };
+        if keys::is_vowel(p.key) && p.mark == mark::NONE {
+            let fits = |&(t, _): &(u8, u8)| match t {
+                tone::CIRCUMFLEX => {
+                    p.tone == tone::NONE && matches!(p.key, keys::A | keys::E | keys::O)
+                }
+                tone::HORN => p.tone == tone::NONE && p.key == keys::A,
+                _ => true,
+            };
+            if let Some((t, m)) = iter.peek().and_then(|&n| vni_modifier(n)).filter(fits) {
+                iter.next();
+                if t != tone::NONE {
+                    p.tone = t;
+                }
+                p.mark = m;
+            }
+        }
+        if !chars::push_letter(&mut out, p.key, p.caps, p.tone, p.mark, p.stroke) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// VIQR diacritic chars: tone (^ + () then mark (' ` ? ~ .)
+const VIQR_CIRCUMFLEX: char = '^';
+const VIQR_HORN: char = '+';
+const VIQR_BREVE: char = '(';
+const VIQR_MARKS: [char; 5] = ['\'', '`', '?', '~', '.'];
+
+/// Encode in VIQR; false (pushed unchanged) if not representable
+fn encode_viqr(c: char, out: &mut impl Extend<char>) -> bool {
+    let Some(p) = chars::parse_char(c) else {
+        out.extend([c]);
+        return false;
+    };
+    let base = match (p.key, p.stroke) {
+        (keys::D, true) => Some(if p.caps { 'D' } else { 'd' }),
+        (_, true) => None,
+        _ => chars::to_char(p.key, p.caps, tone::NONE, mark::NONE),
+    };
+    let modifier = match (p.tone, p.key) {
+        (tone::NONE, _) => None,
+        (tone::CIRCUMFLEX, _) => Some(VIQR_CIRCUMFLEX),
+        (tone::HORN, keys::A) => Some(VIQR_BREVE),
+        (tone::HORN, _) => Some(VIQR_HORN),
+        _ => {
+            out.extend([c]);
+            return false;
+        }
+    };
+    let Some(base) = base else {
+        out.extend([c]);
+        return false;
+    };
+    out.extend([base]);
+    if p.stroke {
+        out.extend([base]);
+    }
+    out.extend(modifier);
+    if p.mark != mark::NONE {
+        out.extend([VIQR_MARKS[(p.mark - 1) as usize]]);
+    }
+    true
+}
+
+/// Apply a VIQR diacritic char (or the second d of "dd") to a letter
+fn apply_viqr(p: &mut ParsedChar, c: char) -> bool {
+    let bare = p.tone == tone::NONE && p.mark == mark::NONE;
+    match (c, p.key) {
+        (VIQR_CIRCUMFLEX, keys::A | keys::E | keys::O) if bare => p.tone = tone::CIRCUMFLEX,
+        (VIQR_HORN, keys::O | keys::U) | (VIQR_BREVE, keys::A) if bare => p.tone = tone::HORN,
+        ('d' | 'D', keys::D) if !p.stroke => p.stroke = true,
+        _ => match VIQR_MARKS.iter().position(|&m| m == c) {
+            Some(m) if keys::is_vowel(p.key) && p.mark == mark::NONE => p.mark = m as u8 + 1,
+            _ => return false,
+        },
+    }
+    true
+}
+
+/// Whether `next` must be escaped after `c` to stay literal in VIQR
+fn viqr_escapes(c: char, next: char) -> bool {
+    if c == '\\' {
+        return viqr_escapable(next);
+    }
+    chars::parse_char(c).is_some_and(|mut p| apply_viqr(&mut p, next))
+}
+
+/// Chars a preceding `\` keeps literal
+fn viqr_escapable(c: char) -> bool {
+    matches!(
+        c,
+        VIQR_CIRCUMFLEX | VIQR_HORN | VIQR_BREVE | 'd' | 'D' | '\\'
+    ) || VIQR_MARKS.contains(&c)
+}
+
+fn decode_viqr(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut letter: Option<(ParsedChar, char)> = None;
+    let flush = |out: &mut String, letter: Option<(ParsedChar, char)>| {
+        if let Some((p, c)) = letter {
+            if !chars::push_letter(out, p.key, p.caps, p.tone, p.mark, p.stroke) {
+                out.push(c);
+            }
+        }
+    };
+    let mut iter = text.chars().peekable();
+    while let Some(c) = iter.next() {
+        if c == '\\' {
+            if let Some(next) = iter.next_if(|&n| viqr_escapable(n)) {
+                flush(&mut out, letter.take());
+                out.push(next);
+                continue;
+            }
+        }
+        if let Some((p, _)) = letter.as_mut() {
+            if apply_viqr(p, c) {
+                continue;
+            }
+        }
+        flush(&mut out, letter.take());
+        match chars::parse_char(c).filter(|_| c.is_ascii()) {
+            Some(p) => letter = Some((p, c)),
+            None => out.push(c),
+        }
+    }
+    flush(&mut out, letter);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: [&str; 4] = ["Tiếng Việt", "được", "Đắk Lắk", "KHỦNG HOẢNG"];
+
+    #[test]
+    fn test_tcvn3() {
+        assert_eq!(encode("Việt", id::TCVN3), "Vi\u{D6}t");
+        assert_eq!(encode("ĐƯỜNG", id::TCVN3), "\u{A7}\u{A6}\u{EA}NG");
+        assert_eq!(decode("\u{AE}\u{AD}\u{EE}c", id::TCVN3), "được");
+    }
+
+    #[test]
+    fn test_vni_win() {
+        assert_eq!(encode("Việt Nam", id::VNI_WIN), "Vieät Nam");
+        assert_eq!(encode("được", id::VNI_WIN), "ñöôïc");
+        assert_eq!(encode("Đà Nẵng", id::VNI_WIN), "Ñaø Naüng");
+        assert_eq!(encode("chỉ thị", id::VNI_WIN), "chæ thò");
+        assert_eq!(encode("ĐỜI", id::VNI_WIN), "ÑÔØI");
+        assert_eq!(
+            decode("Thaønh phoá Hoà Chí Minh", id::VNI_WIN),
+            "Thành phố Hồ Chí Minh"
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for charset in [id::VNI_WIN, id::VISCII, id::NFD] {
+            for word in WORDS {
+                assert_eq!(decode(&encode(word, charset), charset), word, "{}", word);
+            }
+        }
+        assert_eq!(decode(&encode("Đắk Lắk", id::TCVN3), id::TCVN3), "Đắk Lắk");
+    }
+
+    #[test]
+    fn test_unrepresentable() {
+        let mut out = String::new();
+        assert!(!encode_char('ĕ', id::VNI_WIN, &mut out));
+        assert!(!encode_char('č', id::VISCII, &mut out));
+        assert_eq!(out, "ĕč");
+        assert_eq!(letter_width(&['ơ', chars::COMBINING_BREVE], id::TCVN3), 1);
+        assert_eq!(letter_width(&['ệ'], id::VNI_WIN), 2);
+        assert_eq!(letter_width(&['ư'], id::VNI_WIN), 1);
+        assert_eq!(letter_width(&['ệ'], id::NFD), 3);
+    }
+
+    #[test]
+    fn test_viqr() {
+        assert_eq!(encode("Tiếng Việt", id::VIQR), "Tie^'ng Vie^.t");
+        assert_eq!(encode("Đường ăn", id::VIQR), "DDu+o+`ng a(n");
+        assert_eq!(decode("DDu+o+`ng a(n", id::VIQR), "Đường ăn");
+        // Diacritic chars after a letter that would take them are escaped
+        assert_eq!(encode("Hà Nội.", id::VIQR), "Ha` No^.i\\.");
+        assert_eq!(encode("add?", id::VIQR), "ad\\d?");
+        assert_eq!(decode("Who\\'s", id::VIQR), "Who's");
+        // Mark already set: the char stays literal
+        assert_eq!(decode("ba'?", id::VIQR), "bá?");
+    }
+
+    #[test]
+    fn test_convert_charset() {
+        let all = [
+            id::UNICODE,
+            id::TCVN3,
+            id::VNI_WIN,
+            id::VISCII,
+            id::NFD,
+            id::VIQR,
+        ];
+        for from in all {
+            for to in all {
+                let text = "Tiếng Việt được, quả (nhỉ?) \\. dd";
+                let there = convert_charset(&encode(text, from), from, to).unwrap();
+                assert!(there.unconvertible.is_empty());
+                let back = convert_charset(&there.text, to, id::UNICODE).unwrap();
+                assert_eq!(
+                    back.text, text,
+                    "{} → {}",
+                    NAMES[from as usize], NAMES[to as usize]
+                );
+            }
+        }
+        // NFD input composes for Unicode output
+        let nfc = convert_charset("Vie\u{323}\u{302}t", id::UNICODE, id::UNICODE).unwrap();
+        assert_eq!(nfc.text, "Việt");
+        let r = convert_charset("ĕ € ĕ", id::UNICODE, id::TCVN3).unwrap();
+        assert_eq!(r.text, "ĕ € ĕ");
+        assert_eq!(r.unconvertible, ['ĕ', '€']);
+        // TCVN3 has Đ Ư but no toned capitals: Ờ loses its case
+        let r = convert_charset("ĐƯỜNG Việt", id::UNICODE, id::TCVN3).unwrap();
+        assert_eq!(r.unconvertible, ['Ờ']);
+        let back = convert_charset(&r.text, id::TCVN3, id::UNICODE).unwrap();
+        assert_eq!(back.text, "ĐƯờNG Việt");
+        assert_eq!(convert_charset("a", id::UNICODE, 9), None);
+    }
+
+    #[test]
+    fn test_parse_char() {
+        let p = parse_char('\u{AC}', id::TCVN3).unwrap();
+        assert_eq!((p.key, p.tone), (keys::O, tone::HORN));
+        assert!(parse_char('ñ', id::VNI_WIN).unwrap().stroke);
+        assert_eq!(parse_char('æ', id::VNI_WIN).unwrap().mark, mark::HOI);
+        assert_eq!(parse_char('\u{F0}', id::VISCII).unwrap().key, keys::D);
+        assert_eq!(from_name("vni_win"), Some(id::VNI_WIN));
+        assert_eq!(from_name("nfd"), Some(id::NFD));
+        assert!(!is_known(6));
+    }
+}
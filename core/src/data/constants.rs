@@ -64,6 +64,21 @@ pub const VALID_FINALS_1: &[u16] = &[
     keys::U, // semi-vowels
 ];
 
+/// Extra single finals of the Central Highlands orthographies (Ê-đê, Jrai, Bahnar, M'nông)
+/// Only valid when the engine uses the highlands profile (see `orthography`)
+pub const HIGHLANDS_FINALS_1: &[u16] = &[
+    keys::H,
+    keys::L,
+    keys::R, // Bahnar
+    keys::S,
+];
+
+/// Vowels taking a breve in the highlands profile besides a → ă (ĕ ŏ ŭ, and ơ → ơ̆)
+pub const HIGHLANDS_BREVE_VOWELS: &[u16] = &[keys::E, keys::O, keys::U];
+
+/// Consonants taking a caron or tilde in the highlands profile besides d → đ (č ñ)
+pub const HIGHLANDS_STROKE_CONSONANTS: &[u16] = &[keys::C, keys::N];
+
 /// Valid double final consonants
 pub const VALID_FINALS_2: &[[u16; 2]] = &[
     [keys::C, keys::H], // ch
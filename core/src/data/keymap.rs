@@ -0,0 +1,421 @@
+//! Platform keycode namespaces
+//!
+//! The engine works on macOS virtual keycodes internally (see `keys`).
+//! This module translates key events from other platforms into that
+//! namespace so every frontend shares one mapping table:
+//! - macOS virtual keycodes (identity)
+//! - Windows virtual-key codes (`VK_*`)
+//! - X11 keysyms (also used by IBus/Fcitx)
+//! - Linux evdev scancodes (`KEY_*` from `input-event-codes.h`)
+//!
+//! Only keys the engine cares about are mapped (letters, digits, break
+//! keys, DELETE). Unmapped keys translate to `None`.
+
+use super::keys;
+
+/// Keycode namespace of an incoming key event
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyNamespace {
+    /// macOS virtual keycode (kVK_*) - the engine's native namespace
+    MacVk = 0,
+    /// Windows virtual-key code (VK_*)
+    WinVk = 1,
+    /// X11 keysym (XK_*)
+    X11Keysym = 2,
+    /// Linux evdev key code (KEY_*)
+    Evdev = 3,
+}
+
+impl KeyNamespace {
+    /// Convert FFI namespace id to enum
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::MacVk),
+            1 => Some(Self::WinVk),
+            2 => Some(Self::X11Keysym),
+            3 => Some(Self::Evdev),
+            _ => None,
+        }
+    }
+}
+
+/// Modifier bits for platform key events
+pub mod modifier {
+    /// CapsLock is active
+    pub const CAPS: u8 = 0x01;
+    /// Cmd/Ctrl/Alt is pressed (bypasses IME)
+    pub const CTRL: u8 = 0x02;
+    /// Shift is pressed
+    pub const SHIFT: u8 = 0x04;
+}
+
+/// Platform-neutral key event in the engine's keycode namespace
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key: u16,
+    pub caps: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+impl KeyEvent {
+    pub fn new(key: u16, caps: bool, ctrl: bool, shift: bool) -> Self {
+        Self {
+            key,
+            caps,
+            ctrl,
+            shift,
+        }
+    }
+
+    /// Translate an already-resolved character (IBus, Wayland text-input,
+    /// browser extensions, editor plugins)
+    ///
+    /// Handles printable ASCII (shifted symbols set `shift`, uppercase letters
+    /// set `caps`) plus control characters for Backspace, Tab, Enter and ESC.
+    /// Returns None for any other character.
+    pub fn from_char(c: char) -> Option<Self> {
+        // Latin-1 keysyms equal their codepoints, so reuse the X11 table
+        let keysym = match c {
+            '\x08' | '\x7f' => 0xFF08, // Backspace / DEL
+            '\t' => 0xFF09,
+            '\n' | '\r' => 0xFF0D,
+            '\x1b' => 0xFF1B,
+            ' '..='~' => c as u32,
+            _ => return None,
+        };
+        Self::from_platform(KeyNamespace::X11Keysym, keysym, 0)
+    }
+
+    /// Translate a platform key event
+    ///
+    /// X11 keysyms are already resolved against Shift/CapsLock, so an
+    /// uppercase letter keysym implies `caps` and a shifted symbol keysym
+    /// (like `!` or `?`) implies `shift`, regardless of `mods`.
+    ///
+    /// Returns None if the key is not mapped.
+    pub fn from_platform(ns: KeyNamespace, code: u32, mods: u8) -> Option<Self> {
+        let mut ev = Self::new(
+            translate(ns, code)?,
+            mods & modifier::CAPS != 0,
+            mods & modifier::CTRL != 0,
+            mods & modifier::SHIFT != 0,
+        );
+        if ns == KeyNamespace::X11Keysym {
+            if (0x41..=0x5A).contains(&code) {
+                ev.caps = true;
+            }
+            if x11_shifted_symbol(code).is_some() {
+                ev.shift = true;
+            }
+        }
+        Some(ev)
+    }
+}
+
+/// Translate a platform keycode to the engine's keycode
+pub fn translate(ns: KeyNamespace, code: u32) -> Option<u16> {
+    match ns {
+        KeyNamespace::MacVk => u16::try_from(code).ok(),
+        KeyNamespace::WinVk => from_win_vk(code),
+        KeyNamespace::X11Keysym => from_x11_keysym(code),
+        KeyNamespace::Evdev => from_evdev(code),
+    }
+}
+
+/// Windows virtual-key code → engine keycode
+fn from_win_vk(code: u32) -> Option<u16> {
+    let key = match code {
+        // Letters A-Z (0x41 - 0x5A) share ASCII layout
+        0x41..=0x5A => return ascii_letter(code as u8 + 0x20),
+        // Numbers 0-9 (0x30 - 0x39)
+        0x30..=0x39 => return ascii_digit(code as u8),
+        0x08 => keys::DELETE, // VK_BACK
+        0x09 => keys::TAB,
+        0x0D => keys::RETURN,
+        0x1B => keys::ESC,
+        0x20 => keys::SPACE,
+        0x25 => keys::LEFT,
+        0x26 => keys::UP,
+        0x27 => keys::RIGHT,
+        0x28 => keys::DOWN,
+        0xBA => keys::SEMICOLON, // VK_OEM_1
+        0xBB => keys::EQUAL,     // VK_OEM_PLUS
+        0xBC => keys::COMMA,     // VK_OEM_COMMA
+        0xBD => keys::MINUS,     // VK_OEM_MINUS
+        0xBE => keys::DOT,       // VK_OEM_PERIOD
+        0xBF => keys::SLASH,     // VK_OEM_2
+        0xC0 => keys::BACKQUOTE, // VK_OEM_3
+        0xDB => keys::LBRACKET,  // VK_OEM_4
+        0xDC => keys::BACKSLASH, // VK_OEM_5
+        0xDD => keys::RBRACKET,  // VK_OEM_6
+        0xDE => keys::QUOTE,     // VK_OEM_7
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// X11 keysym → engine keycode
+///
+/// Latin-1 keysyms equal their ASCII codepoints; function keys live at 0xFFxx.
+fn from_x11_keysym(code: u32) -> Option<u16> {
+    let key = match code {
+        0x61..=0x7A => return ascii_letter(code as u8),
+        0x41..=0x5A => return ascii_letter(code as u8 + 0x20),
+        0x30..=0x39 => return ascii_digit(code as u8),
+        0x20 => keys::SPACE,
+        0x2E => keys::DOT,
+        0x2C => keys::COMMA,
+        0x2F => keys::SLASH,
+        0x3B => keys::SEMICOLON,
+        0x27 => keys::QUOTE,
+        0x5B => keys::LBRACKET,
+        0x5D => keys::RBRACKET,
+        0x5C => keys::BACKSLASH,
+        0x2D => keys::MINUS,
+        0x3D => keys::EQUAL,
+        0x60 => keys::BACKQUOTE,
+        0xFF08 => keys::DELETE, // XK_BackSpace
+        0xFF09 => keys::TAB,
+        0xFF0D => keys::RETURN,
+        0xFF1B => keys::ESC,
+        0xFF51 => keys::LEFT,
+        0xFF52 => keys::UP,
+        0xFF53 => keys::RIGHT,
+        0xFF54 => keys::DOWN,
+        0xFF8D => keys::ENTER, // XK_KP_Enter
+        _ => return x11_shifted_symbol(code),
+    };
+    Some(key)
+}
+
+/// Shifted symbol keysym → unshifted engine keycode (US layout)
+fn x11_shifted_symbol(code: u32) -> Option<u16> {
+    let key = match code {
+        0x21 => keys::N1,        // !
+        0x40 => keys::N2,        // @
+        0x23 => keys::N3,        // #
+        0x24 => keys::N4,        // $
+        0x25 => keys::N5,        // %
+        0x5E => keys::N6,        // ^
+        0x26 => keys::N7,        // &
+        0x2A => keys::N8,        // *
+        0x28 => keys::N9,        // (
+        0x29 => keys::N0,        // )
+        0x5F => keys::MINUS,     // _
+        0x2B => keys::EQUAL,     // +
+        0x3A => keys::SEMICOLON, // :
+        0x22 => keys::QUOTE,     // "
+        0x3C => keys::COMMA,     // <
+        0x3E => keys::DOT,       // >
+        0x3F => keys::SLASH,     // ?
+        0x7C => keys::BACKSLASH, // |
+        0x7B => keys::LBRACKET,  // {
+        0x7D => keys::RBRACKET,  // }
+        0x7E => keys::BACKQUOTE, // ~
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Linux evdev key code → engine keycode
+fn from_evdev(code: u32) -> Option<u16> {
+    let key = match code {
+        1 => keys::ESC,
+        2 => keys::N1,
+        3 => keys::N2,
+        4 => keys::N3,
+        5 => keys::N4,
+        6 => keys::N5,
+        7 => keys::N6,
+        8 => keys::N7,
+        9 => keys::N8,
+        10 => keys::N9,
+        11 => keys::N0,
+        12 => keys::MINUS,
+        13 => keys::EQUAL,
+        14 => keys::DELETE, // KEY_BACKSPACE
+        15 => keys::TAB,
+        16 => keys::Q,
+        17 => keys::W,
+        18 => keys::E,
+        19 => keys::R,
+        20 => keys::T,
+        21 => keys::Y,
+        22 => keys::U,
+        23 => keys::I,
+        24 => keys::O,
+        25 => keys::P,
+        26 => keys::LBRACKET, // KEY_LEFTBRACE
+        27 => keys::RBRACKET, // KEY_RIGHTBRACE
+        28 => keys::RETURN,   // KEY_ENTER
+        30 => keys::A,
+        31 => keys::S,
+        32 => keys::D,
+        33 => keys::F,
+        34 => keys::G,
+        35 => keys::H,
+        36 => keys::J,
+        37 => keys::K,
+        38 => keys::L,
+        39 => keys::SEMICOLON,
+        40 => keys::QUOTE, // KEY_APOSTROPHE
+        41 => keys::BACKQUOTE,
+        43 => keys::BACKSLASH,
+        44 => keys::Z,
+        45 => keys::X,
+        46 => keys::C,
+        47 => keys::V,
+        48 => keys::B,
+        49 => keys::N,
+        50 => keys::M,
+        51 => keys::COMMA,
+        52 => keys::DOT,
+        53 => keys::SLASH,
+        57 => keys::SPACE,
+        96 => keys::ENTER, // KEY_KPENTER
+        103 => keys::UP,
+        105 => keys::LEFT,
+        106 => keys::RIGHT,
+        108 => keys::DOWN,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Lowercase ASCII letter → engine keycode
+fn ascii_letter(c: u8) -> Option<u16> {
+    let key = match c {
+        b'a' => keys::A,
+        b'b' => keys::B,
+        b'c' => keys::C,
+        b'd' => keys::D,
+        b'e' => keys::E,
+        b'f' => keys::F,
+        b'g' => keys::G,
+        b'h' => keys::H,
+        b'i' => keys::I,
+        b'j' => keys::J,
+        b'k' => keys::K,
+        b'l' => keys::L,
+        b'm' => keys::M,
+        b'n' => keys::N,
+        b'o' => keys::O,
+        b'p' => keys::P,
+        b'q' => keys::Q,
+        b'r' => keys::R,
+        b's' => keys::S,
+        b't' => keys::T,
+        b'u' => keys::U,
+        b'v' => keys::V,
+        b'w' => keys::W,
+        b'x' => keys::X,
+        b'y' => keys::Y,
+        b'z' => keys::Z,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// ASCII digit → engine keycode
+fn ascii_digit(c: u8) -> Option<u16> {
+    let key = match c {
+        b'0' => keys::N0,
+        b'1' => keys::N1,
+        b'2' => keys::N2,
+        b'3' => keys::N3,
+        b'4' => keys::N4,
+        b'5' => keys::N5,
+        b'6' => keys::N6,
+        b'7' => keys::N7,
+        b'8' => keys::N8,
+        b'9' => keys::N9,
+        _ => return None,
+    };
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mac_identity() {
+        assert_eq!(
+            translate(KeyNamespace::MacVk, keys::A as u32),
+            Some(keys::A)
+        );
+        assert_eq!(translate(KeyNamespace::MacVk, 0x10000), None);
+    }
+
+    #[test]
+    fn test_win_vk() {
+        assert_eq!(translate(KeyNamespace::WinVk, 0x41), Some(keys::A));
+        assert_eq!(translate(KeyNamespace::WinVk, 0x53), Some(keys::S));
+        assert_eq!(translate(KeyNamespace::WinVk, 0x39), Some(keys::N9));
+        assert_eq!(translate(KeyNamespace::WinVk, 0x08), Some(keys::DELETE));
+        assert_eq!(translate(KeyNamespace::WinVk, 0xDB), Some(keys::LBRACKET));
+        assert_eq!(translate(KeyNamespace::WinVk, 0x70), None); // F1
+    }
+
+    #[test]
+    fn test_x11_keysym() {
+        assert_eq!(
+            translate(KeyNamespace::X11Keysym, 'a' as u32),
+            Some(keys::A)
+        );
+        assert_eq!(
+            translate(KeyNamespace::X11Keysym, 'Z' as u32),
+            Some(keys::Z)
+        );
+        assert_eq!(
+            translate(KeyNamespace::X11Keysym, '?' as u32),
+            Some(keys::SLASH)
+        );
+        assert_eq!(
+            translate(KeyNamespace::X11Keysym, 0xFF08),
+            Some(keys::DELETE)
+        );
+    }
+
+    #[test]
+    fn test_x11_implied_modifiers() {
+        let ev = KeyEvent::from_platform(KeyNamespace::X11Keysym, 'A' as u32, 0).unwrap();
+        assert!(ev.caps && !ev.shift);
+        let ev = KeyEvent::from_platform(KeyNamespace::X11Keysym, '@' as u32, 0).unwrap();
+        assert_eq!(ev.key, keys::N2);
+        assert!(ev.shift);
+    }
+
+    #[test]
+    fn test_from_char() {
+        let ev = KeyEvent::from_char('a').unwrap();
+        assert_eq!(ev, KeyEvent::new(keys::A, false, false, false));
+        let ev = KeyEvent::from_char('W').unwrap();
+        assert_eq!(ev, KeyEvent::new(keys::W, true, false, false));
+        let ev = KeyEvent::from_char('?').unwrap();
+        assert_eq!(ev, KeyEvent::new(keys::SLASH, false, false, true));
+        assert_eq!(KeyEvent::from_char('\x08').unwrap().key, keys::DELETE);
+        assert_eq!(KeyEvent::from_char('\n').unwrap().key, keys::RETURN);
+        assert_eq!(KeyEvent::from_char('é'), None);
+    }
+
+    #[test]
+    fn test_evdev() {
+        assert_eq!(translate(KeyNamespace::Evdev, 30), Some(keys::A));
+        assert_eq!(translate(KeyNamespace::Evdev, 17), Some(keys::W));
+        assert_eq!(translate(KeyNamespace::Evdev, 14), Some(keys::DELETE));
+        assert_eq!(translate(KeyNamespace::Evdev, 57), Some(keys::SPACE));
+        assert_eq!(translate(KeyNamespace::Evdev, 59), None); // F1
+    }
+
+    #[test]
+    fn test_modifiers() {
+        let mods = modifier::CAPS | modifier::SHIFT;
+        let ev = KeyEvent::from_platform(KeyNamespace::WinVk, 0x41, mods).unwrap();
+        assert_eq!(ev, KeyEvent::new(keys::A, true, false, true));
+        let ev = KeyEvent::from_platform(KeyNamespace::Evdev, 30, modifier::CTRL).unwrap();
+        assert!(ev.ctrl);
+    }
+}
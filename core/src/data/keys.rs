@@ -1,4 +1,7 @@
 //! macOS virtual keycodes
+//!
+//! The engine's internal key namespace. Other platforms translate into it
+//! via `keymap`.
 
 // Letters
 pub const A: u16 = 0;
@@ -0,0 +1,179 @@
+//! Logical keyboard layouts
+//!
+//! Keycodes in `keys` identify physical key positions (US QWERTY labels).
+//! A Dvorak, Colemak, AZERTY or QWERTZ user pressing the key labelled "S"
+//! on QWERTY expects a different letter, so the engine remaps positional
+//! keycodes through a `KeyboardLayout` before any Telex/VNI lookup.
+//!
+//! Layouts are described by the 47 characters the layout produces on the
+//! US QWERTY positions in `QWERTY_ORDER`. Only unshifted ASCII output is
+//! supported; the number row stays positional for layouts (like AZERTY)
+//! that need Shift for digits.
+
+use super::keymap::KeyEvent;
+
+/// US QWERTY printable keys, in row order (unshifted)
+pub const QWERTY_ORDER: &str = "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./";
+
+/// Built-in layout ids
+pub mod id {
+    pub const QWERTY: u8 = 0;
+    pub const DVORAK: u8 = 1;
+    pub const COLEMAK: u8 = 2;
+    pub const AZERTY: u8 = 3;
+    pub const QWERTZ: u8 = 4;
+}
+
+const DVORAK: &str = "`1234567890[]',.pyfgcrl/=\\aoeuidhtns-;qjkxbmwvz";
+const COLEMAK: &str = "`1234567890-=qwfpgjluy;[]\\arstdhneio'zxcvbkm,./";
+const AZERTY: &str = "`1234567890-=azertyuiop[]\\qsdfghjklm'wxcvbn,;./";
+const QWERTZ: &str = "`1234567890-=qwertzuiop[]\\asdfghjkl;'yxcvbnm,./";
+
+/// Keycode table size (covers all macOS virtual keycodes the engine uses)
+const TABLE_SIZE: usize = 128;
+
+/// Physical → logical keycode mapping
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardLayout {
+    map: [u16; TABLE_SIZE],
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> Self {
+        Self::qwerty()
+    }
+}
+
+impl KeyboardLayout {
+    /// US QWERTY (identity mapping)
+    pub fn qwerty() -> Self {
+        Self {
+            map: std::array::from_fn(|i| i as u16),
+        }
+    }
+
+    /// Get a built-in layout by id (see `id`)
+    pub fn builtin(layout_id: u8) -> Option<Self> {
+        match layout_id {
+            id::QWERTY => Some(Self::qwerty()),
+            id::DVORAK => Self::from_qwerty_map(DVORAK),
+            id::COLEMAK => Self::from_qwerty_map(COLEMAK),
+            id::AZERTY => Self::from_qwerty_map(AZERTY),
+            id::QWERTZ => Self::from_qwerty_map(QWERTZ),
+            _ => None,
+        }
+    }
+
+    /// Build a layout from the characters it produces on `QWERTY_ORDER` positions
+    ///
+    /// Example (Colemak): `` "`1234567890-=qwfpgjluy;[]\\arstdhneio'zxcvbkm,./" ``
+    ///
+    /// Returns None if the string doesn't have exactly 47 characters or
+    /// contains a character that isn't unshifted printable ASCII.
+    pub fn from_qwerty_map(produced: &str) -> Option<Self> {
+        if produced.chars().count() != QWERTY_ORDER.len() {
+            return None;
+        }
+        let mut layout = Self::qwerty();
+        for (physical, logical) in QWERTY_ORDER.chars().zip(produced.chars()) {
+            let from = KeyEvent::from_char(physical)?.key;
+            let to = KeyEvent::from_char(logical.to_ascii_lowercase())?;
+            if to.shift {
+                return None;
+            }
+            layout.map[from as usize] = to.key;
+        }
+        Some(layout)
+    }
+
+    /// Describe the layout as the characters it produces on `QWERTY_ORDER`
+    /// positions (inverse of `from_qwerty_map`)
+    pub fn to_qwerty_map(&self) -> String {
+        let key_of = |c: char| KeyEvent::from_char(c).map(|ev| ev.key);
+        QWERTY_ORDER
+            .chars()
+            .map(|physical| {
+                let logical = key_of(physical).map(|k| self.map(k));
+                QWERTY_ORDER
+                    .chars()
+                    .find(|&c| key_of(c) == logical)
+                    .unwrap_or(physical)
+            })
+            .collect()
+    }
+
+    /// Built-in id of this layout, if it is one of the built-ins
+    pub fn builtin_id(&self) -> Option<u8> {
+        (id::QWERTY..=id::QWERTZ).find(|&i| Self::builtin(i).as_ref() == Some(self))
+    }
+
+    /// Map a physical keycode to the logical keycode
+    #[inline]
+    pub fn map(&self, key: u16) -> u16 {
+        self.map.get(key as usize).copied().unwrap_or(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::keys;
+
+    #[test]
+    fn test_qwerty_identity() {
+        let l = KeyboardLayout::qwerty();
+        assert_eq!(l.map(keys::S), keys::S);
+        assert_eq!(l.map(keys::SPACE), keys::SPACE);
+        assert_eq!(l.map(500), 500);
+    }
+
+    #[test]
+    fn test_builtins() {
+        for layout_id in [id::DVORAK, id::COLEMAK, id::AZERTY, id::QWERTZ] {
+            assert!(KeyboardLayout::builtin(layout_id).is_some());
+        }
+        assert!(KeyboardLayout::builtin(99).is_none());
+
+        let dvorak = KeyboardLayout::builtin(id::DVORAK).unwrap();
+        assert_eq!(dvorak.map(keys::S), keys::O);
+        assert_eq!(dvorak.map(keys::Q), keys::QUOTE);
+        assert_eq!(dvorak.map(keys::SEMICOLON), keys::S);
+
+        let colemak = KeyboardLayout::builtin(id::COLEMAK).unwrap();
+        assert_eq!(colemak.map(keys::S), keys::R);
+        assert_eq!(colemak.map(keys::D), keys::S);
+
+        let azerty = KeyboardLayout::builtin(id::AZERTY).unwrap();
+        assert_eq!(azerty.map(keys::Q), keys::A);
+        assert_eq!(azerty.map(keys::SEMICOLON), keys::M);
+        assert_eq!(azerty.map(keys::N1), keys::N1);
+
+        let qwertz = KeyboardLayout::builtin(id::QWERTZ).unwrap();
+        assert_eq!(qwertz.map(keys::Y), keys::Z);
+        assert_eq!(qwertz.map(keys::Z), keys::Y);
+    }
+
+    #[test]
+    fn test_qwerty_map_roundtrip() {
+        assert_eq!(KeyboardLayout::qwerty().to_qwerty_map(), QWERTY_ORDER);
+        let colemak = KeyboardLayout::builtin(id::COLEMAK).unwrap();
+        assert_eq!(colemak.to_qwerty_map(), COLEMAK);
+        assert_eq!(colemak.builtin_id(), Some(id::COLEMAK));
+        assert_eq!(
+            KeyboardLayout::from_qwerty_map(&colemak.to_qwerty_map()),
+            Some(colemak)
+        );
+    }
+
+    #[test]
+    fn test_custom_layout_validation() {
+        assert!(KeyboardLayout::from_qwerty_map("abc").is_none());
+        // Shifted symbol '!' is not allowed
+        let bad = QWERTY_ORDER.replacen('/', "!", 1);
+        assert!(KeyboardLayout::from_qwerty_map(&bad).is_none());
+        assert_eq!(
+            KeyboardLayout::from_qwerty_map(QWERTY_ORDER),
+            Some(KeyboardLayout::qwerty())
+        );
+    }
+}
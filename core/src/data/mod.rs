@@ -2,12 +2,20 @@
 //!
 //! This module contains all linguistic data for Vietnamese input:
 //! - `keys`: Virtual keycode definitions (platform-specific)
+//! - `keymap`: Translation from other platforms' keycodes into `keys`
+//! - `layout`: Logical keyboard layouts (Dvorak, Colemak, AZERTY, QWERTZ)
 //! - `chars`: Unicode character conversion (includes tone/mark constants)
+//! - `charset`: Legacy charsets (TCVN3, VNI-Windows, VISCII)
+//! - `orthography`: Standard and Central Highlands letter/final profiles
 //! - `vowel`: Vietnamese vowel phonology system
 
 pub mod chars;
+pub mod charset;
 pub mod constants;
+pub mod keymap;
 pub mod keys;
+pub mod layout;
+pub mod orthography;
 pub mod vowel;
 
 pub use chars::{get_d, mark, to_char, tone};
@@ -0,0 +1,84 @@
+//! Orthography profiles
+//!
+//! Standard Vietnamese, or the extended Latin orthographies of the Central
+//! Highlands languages (Ê-đê, Jrai, Bahnar, M'nông). The highlands profile
+//! adds letters (ĕ ŏ ŭ ơ̆ č ñ) and single finals (h l r s) on top of the
+//! standard ones; the Kr initial and K final are standard already (Krông, Đắk).
+//!
+//! Extra letters are typed with modifier keys (see `Engine::set_orthography`):
+//! - Breve: `v` in Telex ("ev" → "ĕ", "owv" → "ơ̆"), the method's breve key
+//!   elsewhere (VNI 8)
+//! - Caron/tilde: doubling where the method strokes by doubling ("cc" → "č",
+//!   "nn" → "ñ"), the method's stroke key elsewhere (VNI 9)
+
+use super::constants;
+use super::keys;
+
+/// Profile ids (`Engine::set_orthography`, FFI `ime_orthography`)
+pub mod id {
+    pub const STANDARD: u8 = 0;
+    /// Ê-đê, Jrai, Bahnar, M'nông
+    pub const HIGHLANDS: u8 = 1;
+}
+
+/// Profile names for config text (index = id)
+pub const NAMES: [&str; 2] = ["standard", "highlands"];
+
+/// Breve key of Telex-style methods, whose own `w` is the horn
+pub const TELEX_BREVE_KEY: u16 = keys::V;
+
+/// Letters and finals an orthography adds to standard Vietnamese
+#[derive(Debug, PartialEq)]
+pub struct Orthography {
+    /// Vowels the breve key reaches besides a (ĕ ŏ ŭ; ơ becomes ơ̆)
+    pub breve_vowels: &'static [u16],
+    /// Consonants taking a stroke besides d (č ñ)
+    pub stroke_consonants: &'static [u16],
+    /// Single final consonants besides `constants::VALID_FINALS_1`
+    pub finals: &'static [u16],
+}
+
+impl Orthography {
+    /// No extra letters: modifier keys keep their standard meaning
+    pub fn has_letters(&self) -> bool {
+        !self.breve_vowels.is_empty() || !self.stroke_consonants.is_empty()
+    }
+}
+
+pub static STANDARD: Orthography = Orthography {
+    breve_vowels: &[],
+    stroke_consonants: &[],
+    finals: &[],
+};
+
+pub static HIGHLANDS: Orthography = Orthography {
+    breve_vowels: constants::HIGHLANDS_BREVE_VOWELS,
+    stroke_consonants: constants::HIGHLANDS_STROKE_CONSONANTS,
+    finals: constants::HIGHLANDS_FINALS_1,
+};
+
+/// Profile by id (None for unknown ids)
+pub fn get(orthography_id: u8) -> Option<&'static Orthography> {
+    match orthography_id {
+        id::STANDARD => Some(&STANDARD),
+        id::HIGHLANDS => Some(&HIGHLANDS),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        assert_eq!(get(id::STANDARD), Some(&STANDARD));
+        assert!(!STANDARD.has_letters());
+        let h = get(id::HIGHLANDS).unwrap();
+        assert!(h.has_letters());
+        assert!(h.finals.contains(&keys::S));
+        assert!(h.stroke_consonants.contains(&keys::N));
+        assert_eq!(get(2), None);
+        assert_eq!(NAMES[id::HIGHLANDS as usize], "highlands");
+    }
+}
@@ -2,6 +2,7 @@
 
 pub const MAX: usize = 64;
 
+use crate::data::chars;
 use crate::utils;
 
 /// Single character in buffer
@@ -9,12 +10,12 @@ use crate::utils;
 /// Modifiers:
 /// - `tone`: vowel diacritics (^, horn, breve)
 /// - `mark`: tone marks (sắc, huyền, hỏi, ngã, nặng)
-/// - `stroke`: consonant stroke (d → đ)
+/// - `stroke`: consonant stroke (d → đ, highlands c → č, n → ñ)
 #[derive(Clone, Copy, Default)]
 pub struct Char {
     pub key: u16,
     pub caps: bool,
-    pub tone: u8,     // 0=none, 1=circumflex(^), 2=horn/breve
+    pub tone: u8,     // 0=none, 1=circumflex(^), 2=horn/breve ă, 3=short ĕŏŭ, 4=ơ̆
     pub mark: u8,     // 0=none, 1=sắc, 2=huyền, 3=hỏi, 4=ngã, 5=nặng
     pub stroke: bool, // true if 'd' → 'đ' (stroke through)
 }
@@ -37,6 +38,16 @@ impl Char {
     pub fn has_mark(&self) -> bool {
         self.mark > 0
     }
+
+    /// Push the text of this char: the letter with its diacritics (ơ̆ is
+    /// two chars), or the typed key
+    pub fn push_text(&self, out: &mut impl Extend<char>) {
+        if !chars::push_letter(out, self.key, self.caps, self.tone, self.mark, self.stroke) {
+            if let Some(ch) = utils::key_to_char(self.key, self.caps) {
+                out.extend([ch]);
+            }
+        }
+    }
 }
 
 /// Typing buffer
@@ -165,22 +176,11 @@ impl Buffer {
     /// This includes tone marks (sắc/huyền/hỏi/ngã/nặng), vowel marks (circumflex/horn/breve),
     /// and stroked consonants (đ). Use this for shortcut matching to ensure exact comparison.
     pub fn to_full_string(&self) -> String {
-        use crate::data::{chars, keys};
-        self.data[..self.len]
-            .iter()
-            .filter_map(|c| {
-                // Handle đ/Đ (stroked D)
-                if c.key == keys::D && c.stroke {
-                    return Some(chars::get_d(c.caps));
-                }
-                // Try to get full Vietnamese character with diacritics
-                if let Some(ch) = chars::to_char(c.key, c.caps, c.tone, c.mark) {
-                    return Some(ch);
-                }
-                // Fallback to basic character
-                utils::key_to_char(c.key, c.caps)
-            })
-            .collect()
+        let mut s = String::with_capacity(self.len);
+        for c in self.iter() {
+            c.push_text(&mut s);
+        }
+        s
     }
 }
 
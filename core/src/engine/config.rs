@@ -0,0 +1,250 @@
+//! Engine Configuration
+//!
+//! All user-facing engine settings in one struct, with a stable
+//! `key=value` text format so every platform persists and loads settings
+//! identically.
+//!
+//! ```text
+//! # Gõ Nhanh engine config
+//! method=0
+//! enabled=true
+//! modern_tone=true
+//! layout=qwerty
+//! ```
+//!
+//! Parsing starts from defaults: missing keys keep their default value,
+//! unknown keys are ignored (forward compatibility), blank lines and lines
+//! starting with `#` are skipped.
+
+use super::encoding::OutputEncoding;
+use crate::data::charset;
+use crate::data::layout::KeyboardLayout;
+use crate::data::orthography;
+use crate::input::{self, KeyBindings};
+
+/// Engine settings snapshot
+#[derive(Clone, Debug, PartialEq)]
+pub struct EngineConfig {
+    /// Input method: 0=Telex, 1=VNI, 2=VIQR, 3=table, 4=Telex+VNI, 5=direct
+    pub method: u8,
+    pub enabled: bool,
+    /// Skip w→ư shortcut at word start (Telex)
+    pub skip_w_shortcut: bool,
+    /// ESC restores raw ASCII
+    pub esc_restore: bool,
+    /// Place diacritics without spelling validation
+    pub free_tone: bool,
+    /// Modern tone placement (hoà) instead of traditional (hòa)
+    pub modern_tone: bool,
+    /// Restore accidentally transformed English words
+    pub english_auto_restore: bool,
+    /// Capitalize first letter after sentence-ending punctuation
+    pub auto_capitalize: bool,
+    /// `[` types ơ and `]` types ư (Telex)
+    pub bracket_vowels: bool,
+    /// Moved or disabled modifier keys (`huyen:q nang:`)
+    pub key_bindings: KeyBindings,
+    /// Orthography profile: 0=standard, 1=highlands (ĕ ŏ ŭ ơ̆ č ñ, finals h l r s)
+    pub orthography: u8,
+    /// Logical keyboard layout
+    pub layout: KeyboardLayout,
+    /// Output charset: 0=Unicode, 1=TCVN3, 2=VNI-Windows, 3=VISCII, 4=NFD, 5=VIQR
+    pub charset: u8,
+    /// Encoding of result text read by hosts
+    pub output_encoding: OutputEncoding,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            method: 0,
+            enabled: true,
+            skip_w_shortcut: false,
+            esc_restore: false,
+            free_tone: false,
+            modern_tone: true,
+            english_auto_restore: false,
+            auto_capitalize: false,
+            bracket_vowels: false,
+            key_bindings: KeyBindings::default(),
+            orthography: orthography::id::STANDARD,
+            layout: KeyboardLayout::qwerty(),
+            charset: charset::id::UNICODE,
+            output_encoding: OutputEncoding::Utf32,
+        }
+    }
+}
+
+/// Layout names accepted in the `layout` key (index = built-in id)
+const LAYOUT_NAMES: [&str; 5] = ["qwerty", "dvorak", "colemak", "azerty", "qwertz"];
+
+impl EngineConfig {
+    /// Parse config text
+    ///
+    /// Returns None if a known key has an invalid value or a line has no `=`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut config = Self::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=')?;
+            config.set(key.trim(), value.trim())?;
+        }
+        Some(config)
+    }
+
+    /// Set a single key from its text value
+    ///
+    /// Returns None if the value is invalid; unknown keys are accepted and ignored.
+    pub fn set(&mut self, key: &str, value: &str) -> Option<()> {
+        match key {
+            "method" => self.method = value.parse().ok().filter(|&m| input::is_known(m))?,
+            "enabled" => self.enabled = parse_bool(value)?,
+            "skip_w_shortcut" => self.skip_w_shortcut = parse_bool(value)?,
+            "esc_restore" => self.esc_restore = parse_bool(value)?,
+            "free_tone" => self.free_tone = parse_bool(value)?,
+            "modern_tone" => self.modern_tone = parse_bool(value)?,
+            "english_auto_restore" => self.english_auto_restore = parse_bool(value)?,
+            "auto_capitalize" => self.auto_capitalize = parse_bool(value)?,
+            "bracket_vowels" => self.bracket_vowels = parse_bool(value)?,
+            "key_bindings" => self.key_bindings = KeyBindings::parse(value)?,
+            "orthography" => self.orthography = parse_orthography(value)?,
+            "layout" => self.layout = parse_layout(value)?,
+            "charset" => self.charset = charset::from_name(value)?,
+            "output_encoding" => self.output_encoding = OutputEncoding::from_name(value)?,
+            _ => {}
+        }
+        Some(())
+    }
+
+    /// Serialize to config text (one `key=value` per line, stable order)
+    pub fn to_text(&self) -> String {
+        let layout = match self.layout.builtin_id() {
+            Some(id) => LAYOUT_NAMES[id as usize].to_string(),
+            None => self.layout.to_qwerty_map(),
+        };
+        format!(
+            "method={}\n\
+             enabled={}\n\
+             skip_w_shortcut={}\n\
+             esc_restore={}\n\
+             free_tone={}\n\
+             modern_tone={}\n\
+             english_auto_restore={}\n\
+             auto_capitalize={}\n\
+             bracket_vowels={}\n\
+             key_bindings={}\n\
+             orthography={}\n\
+             layout={}\n\
+             charset={}\n\
+             output_encoding={}\n",
+            self.method,
+            self.enabled,
+            self.skip_w_shortcut,
+            self.esc_restore,
+            self.free_tone,
+            self.modern_tone,
+            self.english_auto_restore,
+            self.auto_capitalize,
+            self.bracket_vowels,
+            self.key_bindings.to_text(),
+            orthography::NAMES
+                .get(self.orthography as usize)
+                .unwrap_or(&orthography::NAMES[0]),
+            layout,
+            charset::NAMES
+                .get(self.charset as usize)
+                .unwrap_or(&charset::NAMES[0]),
+            self.output_encoding.name(),
+        )
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Orthography profile by name (`highlands`)
+fn parse_orthography(value: &str) -> Option<u8> {
+    orthography::NAMES
+        .iter()
+        .position(|&n| n == value)
+        .map(|id| id as u8)
+}
+
+/// Layout by name (`colemak`) or as a 47-char QWERTY map
+fn parse_layout(value: &str) -> Option<KeyboardLayout> {
+    match LAYOUT_NAMES.iter().position(|&n| n == value) {
+        Some(id) => KeyboardLayout::builtin(id as u8),
+        None => KeyboardLayout::from_qwerty_map(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::layout;
+
+    #[test]
+    fn test_roundtrip() {
+        let config = EngineConfig {
+            method: 1,
+            esc_restore: true,
+            modern_tone: false,
+            bracket_vowels: true,
+            key_bindings: KeyBindings::parse("huyen:q nang:").unwrap(),
+            orthography: orthography::id::HIGHLANDS,
+            layout: KeyboardLayout::builtin(layout::id::DVORAK).unwrap(),
+            charset: charset::id::VNI_WIN,
+            output_encoding: OutputEncoding::Utf16,
+            ..Default::default()
+        };
+        let text = config.to_text();
+        assert!(text.contains("bracket_vowels=true\n"));
+        assert!(text.contains("key_bindings=huyen:q nang:\n"));
+        assert!(text.contains("orthography=highlands\n"));
+        assert!(text.contains("layout=dvorak\n"));
+        assert!(text.contains("charset=vni_win\n"));
+        assert!(text.contains("output_encoding=utf16\n"));
+        assert_eq!(EngineConfig::parse(&text), Some(config));
+    }
+
+    #[test]
+    fn test_custom_layout_roundtrip() {
+        let map = layout::QWERTY_ORDER.replace("qw", "wq");
+        let config = EngineConfig {
+            layout: KeyboardLayout::from_qwerty_map(&map).unwrap(),
+            ..Default::default()
+        };
+        let text = config.to_text();
+        assert!(text.contains(&format!("layout={}\n", map)));
+        assert_eq!(EngineConfig::parse(&text), Some(config));
+    }
+
+    #[test]
+    fn test_parse_defaults_comments_unknown() {
+        let config = EngineConfig::parse("# comment\n\nfree_tone = 1\nfuture_key=x\n").unwrap();
+        assert!(config.free_tone);
+        assert_eq!(config.method, 0);
+        assert!(config.modern_tone);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(EngineConfig::parse("enabled=maybe"), None);
+        assert_eq!(EngineConfig::parse("method=x"), None);
+        assert_eq!(EngineConfig::parse("method=9"), None);
+        assert_eq!(EngineConfig::parse("layout=klingon"), None);
+        assert_eq!(EngineConfig::parse("output_encoding=latin1"), None);
+        assert_eq!(EngineConfig::parse("key_bindings=sac:q hoi:q"), None);
+        assert_eq!(EngineConfig::parse("orthography=1"), None);
+        assert_eq!(EngineConfig::parse("charset=vps"), None);
+        assert_eq!(EngineConfig::parse("no equals sign"), None);
+    }
+}
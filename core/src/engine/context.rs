@@ -0,0 +1,236 @@
+//! Focus Contexts
+//!
+//! Hosts report which text field has focus with `Engine::focus(id)`. The
+//! engine keeps the typing state (buffer, raw keystrokes, word history,
+//! pending flags) of each context, so backspace-after-space and ESC restore
+//! still work after focus bounces between windows. Settings and shortcuts
+//! are shared by all contexts.
+//!
+//! Inactive contexts are kept in LRU order; the least recently focused one
+//! is dropped when the store is full.
+
+use super::buffer::Buffer;
+use super::{Engine, Transform, WordHistory};
+
+/// Default number of inactive contexts remembered
+pub const DEFAULT_CAPACITY: usize = 16;
+
+/// Per-context typing state (everything `Engine::clear_all` resets)
+pub(super) struct TypingState {
+    buf: Buffer,
+    last_transform: Option<Transform>,
+    raw_input: Vec<(u16, bool, bool)>,
+    has_non_letter_prefix: bool,
+    word_history: WordHistory,
+    spaces_after_commit: u8,
+    pending_breve_pos: Option<usize>,
+    pending_u_horn_pos: Option<usize>,
+    stroke_reverted: bool,
+    had_mark_revert: bool,
+    pending_mark_revert_pop: bool,
+    had_any_transform: bool,
+    had_vowel_triggered_circumflex: bool,
+    shortcut_prefix: String,
+    restored_pending_clear: bool,
+    pending_capitalize: bool,
+    auto_capitalize_used: bool,
+    screen: Vec<char>,
+}
+
+impl TypingState {
+    /// Fresh state of a never-seen context
+    fn new() -> Self {
+        Self {
+            buf: Buffer::new(),
+            last_transform: None,
+            raw_input: Vec::with_capacity(64),
+            has_non_letter_prefix: false,
+            word_history: WordHistory::new(),
+            spaces_after_commit: 0,
+            pending_breve_pos: None,
+            pending_u_horn_pos: None,
+            stroke_reverted: false,
+            had_mark_revert: false,
+            pending_mark_revert_pop: false,
+            had_any_transform: false,
+            had_vowel_triggered_circumflex: false,
+            shortcut_prefix: String::new(),
+            restored_pending_clear: false,
+            pending_capitalize: false,
+            auto_capitalize_used: false,
+            screen: Vec::new(),
+        }
+    }
+
+    /// Exchange this state with the engine's live state
+    fn swap(&mut self, e: &mut Engine) {
+        use std::mem::swap;
+        swap(&mut self.buf, &mut e.buf);
+        swap(&mut self.last_transform, &mut e.last_transform);
+        swap(&mut self.raw_input, &mut e.raw_input);
+        swap(
+            &mut self.has_non_letter_prefix,
+            &mut e.has_non_letter_prefix,
+        );
+        swap(&mut self.word_history, &mut e.word_history);
+        swap(&mut self.spaces_after_commit, &mut e.spaces_after_commit);
+        swap(&mut self.pending_breve_pos, &mut e.pending_breve_pos);
+        swap(&mut self.pending_u_horn_pos, &mut e.pending_u_horn_pos);
+        swap(&mut self.stroke_reverted, &mut e.stroke_reverted);
+        swap(&mut self.had_mark_revert, &mut e.had_mark_revert);
+        swap(
+            &mut self.pending_mark_revert_pop,
+            &mut e.pending_mark_revert_pop,
+        );
+        swap(&mut self.had_any_transform, &mut e.had_any_transform);
+        swap(
+            &mut self.had_vowel_triggered_circumflex,
+            &mut e.had_vowel_triggered_circumflex,
+        );
+        swap(&mut self.shortcut_prefix, &mut e.shortcut_prefix);
+        swap(
+            &mut self.restored_pending_clear,
+            &mut e.restored_pending_clear,
+        );
+        swap(&mut self.pending_capitalize, &mut e.pending_capitalize);
+        swap(&mut self.auto_capitalize_used, &mut e.auto_capitalize_used);
+        swap(&mut self.screen, &mut e.screen);
+    }
+}
+
+/// Saved states of inactive contexts
+pub(super) struct ContextStore {
+    /// Context whose state is live in the engine
+    current: u64,
+    /// Inactive contexts, least recently used first
+    saved: Vec<(u64, TypingState)>,
+    capacity: usize,
+}
+
+impl ContextStore {
+    pub(super) fn new() -> Self {
+        Self {
+            current: 0,
+            saved: Vec::new(),
+            capacity: DEFAULT_CAPACITY,
+        }
+    }
+
+    fn take(&mut self, id: u64) -> Option<TypingState> {
+        let i = self
+            .saved
+            .iter()
+            .position(|(saved_id, _)| *saved_id == id)?;
+        Some(self.saved.remove(i).1)
+    }
+
+    fn evict(&mut self) {
+        let excess = self.saved.len().saturating_sub(self.capacity);
+        self.saved.drain(..excess);
+    }
+}
+
+impl Engine {
+    /// Switch to another focus context (host-defined id, e.g. window handle)
+    ///
+    /// Saves the current typing state under the previous id and restores the
+    /// state last seen for `context_id` (fresh state if unknown or evicted).
+    /// Focusing the current context is a no-op.
+    pub fn focus(&mut self, context_id: u64) {
+        if context_id == self.contexts.current {
+            return;
+        }
+        let mut state = self
+            .contexts
+            .take(context_id)
+            .unwrap_or_else(TypingState::new);
+        state.swap(self);
+        let previous = std::mem::replace(&mut self.contexts.current, context_id);
+        self.contexts.saved.push((previous, state));
+        self.contexts.evict();
+        self.pending_output.clear();
+    }
+
+    /// Id of the focused context (0 until `focus` is called)
+    pub fn context_id(&self) -> u64 {
+        self.contexts.current
+    }
+
+    /// Number of inactive contexts whose state is remembered
+    pub fn saved_contexts(&self) -> usize {
+        self.contexts.saved.len()
+    }
+
+    /// Set how many inactive contexts are remembered (LRU eviction)
+    pub fn set_context_capacity(&mut self, capacity: usize) {
+        self.contexts.capacity = capacity;
+        self.contexts.evict();
+    }
+
+    /// Drop the saved state of a context (e.g. its window was closed)
+    pub fn forget_context(&mut self, context_id: u64) {
+        self.contexts.saved.retain(|(id, _)| *id != context_id);
+    }
+
+    /// Drop the saved state of all inactive contexts
+    pub fn forget_all_contexts(&mut self) {
+        self.contexts.saved.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::type_word;
+
+    #[test]
+    fn test_focus_swaps_state() {
+        let mut e = Engine::new();
+        type_word(&mut e, "vieet");
+        e.focus(1);
+        assert!(e.buf.is_empty());
+        type_word(&mut e, "ab");
+        e.focus(0);
+        assert_eq!(e.get_buffer_string(), "viêt");
+        assert_eq!(e.raw_input.len(), 5);
+        e.focus(1);
+        assert_eq!(e.get_buffer_string(), "ab");
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut e = Engine::new();
+        e.set_context_capacity(2);
+        type_word(&mut e, "a");
+        for id in 1..=3 {
+            e.focus(id);
+            type_word(&mut e, "b");
+        }
+        // 0 was evicted (least recently used), 1 and 2 remain
+        assert_eq!(e.saved_contexts(), 2);
+        e.focus(0);
+        assert!(e.buf.is_empty());
+        e.focus(2);
+        assert_eq!(e.get_buffer_string(), "b");
+    }
+
+    #[test]
+    fn test_refocus_updates_lru_order() {
+        let mut e = Engine::new();
+        e.set_context_capacity(2);
+        type_word(&mut e, "a");
+        e.focus(1);
+        type_word(&mut e, "b");
+        e.focus(2); // saved: [0, 1]
+        e.focus(0); // saved: [1, 2]
+        e.focus(3); // saved: [2, 0] (1 evicted)
+        e.focus(1);
+        assert!(e.buf.is_empty());
+        e.focus(0);
+        assert_eq!(e.get_buffer_string(), "a");
+
+        e.forget_context(1);
+        e.focus(1);
+        assert!(e.buf.is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+//! Offline Conversion
+//!
+//! Runs text typed with the IME off ("Tieengs Vieetj") through an engine
+//! as if it were typed key by key, and returns what the document would
+//! show ("Tiếng Việt"). Spaces, punctuation, shortcuts and auto-restore
+//! behave as when typing; the end of the input ends the last word.
+//!
+//! Chars that aren't keys (already-Vietnamese text, emoji) are copied
+//! unchanged and end the word they interrupt.
+//!
+//! `to_keystrokes` goes the other way: the Telex or VNI keys that type a
+//! word ("người" → "nguwowif"), checked by typing them back.
+
+use super::config::EngineConfig;
+use super::{Action, Engine, Result};
+use crate::data::chars::{self, mark, tone};
+use crate::data::{keymap::KeyEvent, keys};
+use crate::input::id;
+use crate::utils;
+
+/// Convert `input` typed with `method` (see `input::id`)
+///
+/// `options` supplies every other setting (tone placement, auto-restore,
+/// output charset...); its own `method` is ignored.
+pub fn convert(input: &str, method: u8, options: &EngineConfig) -> String {
+    let mut e = Engine::new();
+    e.apply_config(&EngineConfig {
+        method,
+        ..options.clone()
+    });
+    convert_with(&mut e, input)
+}
+
+/// Convert `input` with the settings and shortcuts of `e`
+///
+/// Typing state of `e` is cleared before and after.
+pub fn convert_with(e: &mut Engine, input: &str) -> String {
+    let mut screen = Screen {
+        text: String::with_capacity(input.len()),
+    };
+    e.clear_all();
+    for c in input.chars() {
+        let Some(ev) = KeyEvent::from_char(c) else {
+            e.clear_all();
+            screen.text.push(c);
+            continue;
+        };
+        let r = e.on_key_event(ev);
+        if r.action != Action::Send as u8 {
+            if ev.key == keys::DELETE {
+                screen.pop();
+            } else {
+                screen.text.push(c);
+            }
+            continue;
+        }
+        screen.apply(e, &r);
+        // Break keys are typed after the output unless consumed
+        let typed_after = !matches!(ev.key, keys::SPACE | keys::ESC | keys::DELETE)
+            && keys::is_break_ext(ev.key, ev.shift)
+            && !r.key_consumed();
+        if typed_after {
+            screen.text.push(c);
+        }
+    }
+    // End of input ends the word: a space finishes shortcuts and auto-restore
+    let r = e.on_key(keys::SPACE, false, false);
+    if r.action == Action::Send as u8 {
+        screen.apply(e, &r);
+        if screen.text.ends_with(' ') {
+            screen.text.pop();
+        }
+    }
+    e.clear_all();
+    screen.text
+}
+
+/// Keys that type `word` with `method` (Telex or VNI)
+///
+/// Canonical sequence: each letter followed by its modifier key ("ươ" →
+/// "uwow", "đ" → "dd" / "d9"), the mark key last ("người" → "nguwowif" /
+/// "ngu7o7i2"). The engine places the mark, so `modern` is the tone
+/// placement setting the keys are typed with (`Engine::set_modern_tone`).
+///
+/// Round trip: typing the keys into an engine with that method and
+/// placement gives back `word` exactly. Returns None if it wouldn't (tone
+/// on the other style's vowel, non-Vietnamese spelling), if the word has
+/// non-letters, or for other methods.
+pub fn to_keystrokes(word: &str, method: u8, modern: bool) -> Option<String> {
+    let telex = match method {
+        id::TELEX | id::TELEX_VNI => true,
+        id::VNI => false,
+        _ => return None,
+    };
+    let word = chars::compose(word);
+    let mut out = String::with_capacity(word.len() * 2);
+    let mut word_mark = None;
+    let mut prev: Option<chars::ParsedChar> = None;
+    for c in word.chars() {
+        let p = chars::parse_char(c)?;
+        let key = |k: char| if p.caps { k.to_ascii_uppercase() } else { k };
+        out.push(utils::key_to_char(p.key, p.caps)?);
+        let plain = p.tone == tone::NONE && !p.stroke;
+        // Telex doubles a/e/o/d: type a third key to keep two ("boong" → "booong")
+        let doubled =
+            prev.is_some_and(|q| q.key == p.key && q.tone == tone::NONE && !q.stroke && plain);
+        if telex && doubled && matches!(p.key, keys::A | keys::E | keys::O | keys::D) {
+            out.push(utils::key_to_char(p.key, p.caps)?);
+        }
+        let modifier = match (p.tone, p.key) {
+            (tone::NONE, _) => None,
+            (tone::CIRCUMFLEX, _) if telex => Some(out.chars().last()?),
+            (tone::CIRCUMFLEX, _) => Some('6'),
+            (tone::HORN, _) if telex => Some(key('w')),
+            (tone::HORN, keys::A) => Some('8'),
+            (tone::HORN, _) => Some('7'),
+            // Highlands letters (ĕ, ơ̆) need an orthography setting
+            _ => return None,
+        };
+        out.extend(modifier);
+        if p.stroke {
+            if p.key != keys::D {
+                return None;
+            }
+            out.push(if telex { key('d') } else { '9' });
+        }
+        if p.mark != mark::NONE {
+            if word_mark.is_some() {
+                return None;
+            }
+            word_mark = Some((p.mark, p.caps));
+        }
+        prev = Some(p);
+    }
+    if let Some((m, caps)) = word_mark {
+        let k = if telex {
+            b"sfrxj"[(m - 1) as usize] as char
+        } else {
+            (b'0' + m) as char
+        };
+        out.push(if caps { k.to_ascii_uppercase() } else { k });
+    }
+
+    let options = EngineConfig {
+        modern_tone: modern,
+        ..EngineConfig::default()
+    };
+    (convert(&out, method, &options) == word).then_some(out)
+}
+
+/// Document text the conversion has produced so far
+///
+/// Backspace deletes one code unit, as the engine counts them.
+struct Screen {
+    text: String,
+}
+
+impl Screen {
+    fn pop(&mut self) {
+        self.text.pop();
+    }
+
+    /// Apply a Send result, including output chunks that didn't fit in it
+    fn apply(&mut self, e: &mut Engine, r: &Result) {
+        for _ in 0..r.backspace {
+            self.pop();
+        }
+        self.text.extend(r.output());
+        while let Some(chunk) = e.next_output_chunk() {
+            self.text.extend(chunk.output());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::id;
+
+    #[test]
+    fn test_convert() {
+        let options = EngineConfig::default();
+        assert_eq!(
+            convert("Tieengs Vieetj, xin chaof!", id::TELEX, &options),
+            "Tiếng Việt, xin chào!"
+        );
+        assert_eq!(convert("Tie61ng Vie65t", id::VNI, &options), "Tiếng Việt");
+        // Last word ends with the input
+        assert_eq!(convert("hoaf", id::TELEX, &options), "hoà");
+        assert_eq!(convert("", id::TELEX, &options), "");
+    }
+
+    #[test]
+    fn test_to_keystrokes() {
+        assert_eq!(
+            to_keystrokes("giường", id::TELEX, true).as_deref(),
+            Some("giuwowngf")
+        );
+        assert_eq!(
+            to_keystrokes("quốc", id::VNI, true).as_deref(),
+            Some("quo6c1")
+        );
+        assert_eq!(to_keystrokes("ñ", id::TELEX, true), None);
+    }
+}
@@ -0,0 +1,136 @@
+//! Output Encoding
+//!
+//! `Result.chars` always holds UTF-32 codepoints. Hosts that need another
+//! encoding (UTF-16 for Windows SendInput, UTF-8 for IBus/Fcitx) convert
+//! through these helpers instead of hand-written loops, so emoji outside
+//! the BMP become proper surrogate pairs / 4-byte sequences everywhere.
+//!
+//! Invalid codepoints (lone surrogates, > U+10FFFF) are encoded as U+FFFD.
+
+/// Text encoding of result output
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum OutputEncoding {
+    /// UTF-32 codepoints (same as `Result.chars`)
+    #[default]
+    Utf32 = 0,
+    Utf8 = 1,
+    Utf16 = 2,
+}
+
+/// Config names (index = discriminant)
+const NAMES: [&str; 3] = ["utf32", "utf8", "utf16"];
+
+impl OutputEncoding {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Utf32),
+            1 => Some(Self::Utf8),
+            2 => Some(Self::Utf16),
+            _ => None,
+        }
+    }
+
+    /// Parse config name (`utf8`, `utf16`, `utf32`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let i = NAMES.iter().position(|&n| n == name)?;
+        Self::from_u8(i as u8)
+    }
+
+    pub fn name(self) -> &'static str {
+        NAMES[self as usize]
+    }
+
+    /// Encode codepoints as code units of this encoding (widened to u32)
+    pub fn encode(self, chars: &[u32]) -> Vec<u32> {
+        match self {
+            Self::Utf32 => chars.iter().map(|&c| to_char(c) as u32).collect(),
+            Self::Utf8 => to_utf8(chars).bytes().map(u32::from).collect(),
+            Self::Utf16 => to_utf16(chars).into_iter().map(u32::from).collect(),
+        }
+    }
+}
+
+#[inline]
+fn to_char(c: u32) -> char {
+    char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// UTF-32 codepoints → UTF-8 string
+pub fn to_utf8(chars: &[u32]) -> String {
+    chars.iter().map(|&c| to_char(c)).collect()
+}
+
+/// UTF-32 codepoints → UTF-16 code units (surrogate pairs above U+FFFF)
+pub fn to_utf16(chars: &[u32]) -> Vec<u16> {
+    let mut out = Vec::with_capacity(chars.len());
+    let mut buf = [0u16; 2];
+    for &c in chars {
+        out.extend_from_slice(to_char(c).encode_utf16(&mut buf));
+    }
+    out
+}
+
+/// Whether `units[i]` starts a character (safe truncation point)
+///
+/// `i == units.len()` is always a boundary.
+pub fn is_boundary(encoding: OutputEncoding, units: &[u32], i: usize) -> bool {
+    match (encoding, units.get(i)) {
+        (_, None) => true,
+        (OutputEncoding::Utf32, _) => true,
+        // UTF-8 continuation byte: 10xxxxxx
+        (OutputEncoding::Utf8, Some(&u)) => u & 0xC0 != 0x80,
+        // UTF-16 low surrogate
+        (OutputEncoding::Utf16, Some(&u)) => !(0xDC00..0xE000).contains(&u),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: [u32; 3] = ['V' as u32, 'ệ' as u32, '😀' as u32];
+
+    #[test]
+    fn test_utf8() {
+        assert_eq!(to_utf8(&TEXT), "Vệ😀");
+        assert_eq!(OutputEncoding::Utf8.encode(&TEXT).len(), 1 + 3 + 4);
+    }
+
+    #[test]
+    fn test_utf16_surrogates() {
+        assert_eq!(to_utf16(&TEXT), vec![0x56, 0x1EC7, 0xD83D, 0xDE00]);
+        let units = OutputEncoding::Utf16.encode(&TEXT);
+        assert!(is_boundary(OutputEncoding::Utf16, &units, 2));
+        assert!(!is_boundary(OutputEncoding::Utf16, &units, 3));
+        assert!(is_boundary(OutputEncoding::Utf16, &units, 4));
+    }
+
+    #[test]
+    fn test_utf8_boundaries() {
+        let units = OutputEncoding::Utf8.encode(&TEXT);
+        assert!(is_boundary(OutputEncoding::Utf8, &units, 1));
+        assert!(!is_boundary(OutputEncoding::Utf8, &units, 2));
+        assert!(is_boundary(OutputEncoding::Utf8, &units, 4));
+    }
+
+    #[test]
+    fn test_invalid_codepoints() {
+        assert_eq!(to_utf8(&[0xD800, 0x110000]), "\u{FFFD}\u{FFFD}");
+        assert_eq!(to_utf16(&[0xDC00]), vec![0xFFFD]);
+    }
+
+    #[test]
+    fn test_names() {
+        for enc in [
+            OutputEncoding::Utf32,
+            OutputEncoding::Utf8,
+            OutputEncoding::Utf16,
+        ] {
+            assert_eq!(OutputEncoding::from_name(enc.name()), Some(enc));
+            assert_eq!(OutputEncoding::from_u8(enc as u8), Some(enc));
+        }
+        assert_eq!(OutputEncoding::from_name("latin1"), None);
+        assert_eq!(OutputEncoding::from_u8(3), None);
+    }
+}
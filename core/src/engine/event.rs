@@ -0,0 +1,70 @@
+//! Engine Events
+//!
+//! Typed notifications of what the engine did, so hosts can show
+//! notifications, collect stats or learn words without re-deriving it from
+//! `Result.action`/`flags`. Recording is off until enabled with
+//! `Engine::set_events_enabled`; recorded events are drained with
+//! `Engine::take_events`.
+
+/// Event kind ids (FFI `ImeEvent.kind`)
+pub mod kind {
+    pub const COMMIT: u8 = 1;
+    pub const AUTO_RESTORE: u8 = 2;
+    pub const ESC_RESTORE: u8 = 3;
+    pub const SHORTCUT: u8 = 4;
+    pub const AUTO_CAPITALIZE: u8 = 5;
+}
+
+/// Something the engine did while processing a key
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// Word finished by space or a break key
+    Commit {
+        /// Final text of the word (after any auto-restore)
+        text: String,
+        /// Keystrokes that produced it
+        raw: String,
+    },
+    /// Invalid Vietnamese replaced by the raw English keystrokes
+    AutoRestore { from: String, to: String },
+    /// ESC replaced the composed word with its raw keystrokes
+    EscRestore { from: String, to: String },
+    /// Shortcut trigger replaced by its expansion
+    ShortcutExpanded {
+        trigger: String,
+        replacement: String,
+    },
+    /// Letter uppercased after sentence-ending punctuation
+    AutoCapitalize { letter: char },
+}
+
+impl Event {
+    /// Kind id (see `kind`)
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::Commit { .. } => kind::COMMIT,
+            Self::AutoRestore { .. } => kind::AUTO_RESTORE,
+            Self::EscRestore { .. } => kind::ESC_RESTORE,
+            Self::ShortcutExpanded { .. } => kind::SHORTCUT,
+            Self::AutoCapitalize { .. } => kind::AUTO_CAPITALIZE,
+        }
+    }
+
+    /// Main text and detail text, as exposed over FFI
+    ///
+    /// Commit: (text, raw); restores: (to, from);
+    /// shortcut: (replacement, trigger); auto-capitalize: (letter, "")
+    pub fn texts(&self) -> (String, String) {
+        match self {
+            Self::Commit { text, raw } => (text.clone(), raw.clone()),
+            Self::AutoRestore { from, to } | Self::EscRestore { from, to } => {
+                (to.clone(), from.clone())
+            }
+            Self::ShortcutExpanded {
+                trigger,
+                replacement,
+            } => (replacement.clone(), trigger.clone()),
+            Self::AutoCapitalize { letter } => (letter.to_string(), String::new()),
+        }
+    }
+}
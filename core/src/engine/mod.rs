@@ -11,6 +11,11 @@
 //! 4. **Longest-Match-First**: For diacritic placement
 
 pub mod buffer;
+pub mod config;
+pub mod context;
+pub mod convert;
+pub mod encoding;
+pub mod event;
 pub mod shortcut;
 pub mod syllable;
 pub mod transform;
@@ -18,14 +23,28 @@ pub mod validation;
 
 use crate::data::{
     chars::{self, mark, tone},
-    constants, keys,
+    charset, constants,
+    keymap::{KeyEvent, KeyNamespace},
+    keys,
+    layout::KeyboardLayout,
+    orthography::{self, Orthography},
     vowel::{Phonology, Vowel},
 };
-use crate::input::{self, ToneType};
+use crate::input::{
+    self, DirectLetter, KeyBindings, Rebound, SharedMethod, StrokeStyle, TableMethod, ToneType,
+};
+use crate::text::case;
 use crate::utils;
 use buffer::{Buffer, Char, MAX};
+use config::EngineConfig;
+use context::ContextStore;
+use encoding::OutputEncoding;
+use event::Event;
 use shortcut::{InputMethod, ShortcutTable};
-use validation::{is_foreign_word_pattern, is_valid, is_valid_for_transform, is_valid_with_tones};
+use std::sync::Arc;
+use validation::{
+    is_foreign_word_pattern_in, is_valid_for_transform_in, is_valid_in, is_valid_with_tones_in,
+};
 
 /// Engine action result
 #[repr(u8)]
@@ -52,6 +71,10 @@ pub struct Result {
 /// Flag: key was consumed by shortcut, don't pass through
 pub const FLAG_KEY_CONSUMED: u8 = 0x01;
 
+/// Flag: output didn't fit in `chars`; fetch the rest with
+/// `Engine::next_output_chunk` until a chunk comes back without this flag
+pub const FLAG_MORE_OUTPUT: u8 = 0x02;
+
 impl Result {
     pub fn none() -> Self {
         Self {
@@ -63,6 +86,7 @@ impl Result {
         }
     }
 
+    /// Send output; at most `MAX` chars are kept (see `FLAG_MORE_OUTPUT`)
     pub fn send(backspace: u8, chars: &[char]) -> Self {
         let mut result = Self {
             chars: [0; MAX],
@@ -88,6 +112,28 @@ impl Result {
     pub fn key_consumed(&self) -> bool {
         self.flags & FLAG_KEY_CONSUMED != 0
     }
+
+    /// Check if more output follows in further chunks
+    pub fn has_more_output(&self) -> bool {
+        self.flags & FLAG_MORE_OUTPUT != 0
+    }
+
+    /// Output chars of this result
+    pub fn output(&self) -> impl Iterator<Item = char> + '_ {
+        self.chars[..self.count as usize]
+            .iter()
+            .filter_map(|&c| char::from_u32(c))
+    }
+
+    /// Output as UTF-8
+    pub fn to_utf8(&self) -> String {
+        encoding::to_utf8(&self.chars[..self.count as usize])
+    }
+
+    /// Output as UTF-16 code units (emoji become surrogate pairs)
+    pub fn to_utf16(&self) -> Vec<u16> {
+        encoding::to_utf16(&self.chars[..self.count as usize])
+    }
 }
 
 /// Transform type for revert tracking
@@ -103,8 +149,13 @@ enum Transform {
     WAsVowel,
     /// W shortcut was explicitly skipped (prevent re-transformation)
     WShortcutSkipped,
+    /// Highlands breve/caron/tilde by key at position (for revert: evv → ev)
+    OrthographyLetter(u16, usize),
 }
 
+/// Chars of recent output tracked for legacy charsets (see `Engine::screen`)
+const SCREEN_CAPACITY: usize = 64;
+
 /// Word history ring buffer capacity (stores last N committed words)
 const HISTORY_CAPACITY: usize = 10;
 
@@ -154,14 +205,12 @@ impl WordHistory {
 }
 
 /// Check if key is sentence-ending punctuation (triggers auto-capitalize)
-/// Triggers: . ! ? Enter
+/// Triggers: . ! ? Enter (same rules as `text::case` for existing text)
 #[inline]
 fn is_sentence_ending(key: u16, shift: bool) -> bool {
     key == keys::RETURN
         || key == keys::ENTER
-        || key == keys::DOT
-        || (shift && key == keys::N1) // !
-        || (shift && key == keys::SLASH) // ?
+        || break_key_to_char(key, shift).is_some_and(case::is_sentence_ending)
 }
 
 /// Check if a break key should reset pending_capitalize
@@ -171,18 +220,12 @@ fn is_sentence_ending(key: u16, shift: bool) -> bool {
 fn should_reset_pending_capitalize(key: u16, shift: bool) -> bool {
     // These neutral characters/keys should NOT reset pending_capitalize:
     // - Space: ". " followed by letter should capitalize
-    // - Quotes: ' " (QUOTE with/without shift)
-    // - Parentheses: ( ) (Shift+9, Shift+0)
-    // - Brackets: [ ] { } (LBRACKET, RBRACKET with/without shift)
+    // - Quotes, parentheses, brackets: ' " ( ) [ ] { } (`case::is_sentence_neutral`)
     // - Arrow keys: navigation shouldn't reset pending state
     // - Tab, ESC: navigation/cancel shouldn't reset pending state
     // - Enter: new line should also trigger capitalize (handled separately)
     let is_neutral = key == keys::SPACE
-        || key == keys::QUOTE
-        || key == keys::LBRACKET
-        || key == keys::RBRACKET
-        || (shift && key == keys::N9)  // (
-        || (shift && key == keys::N0)  // )
+        || break_key_to_char(key, shift).is_some_and(case::is_sentence_neutral)
         || key == keys::LEFT
         || key == keys::RIGHT
         || key == keys::UP
@@ -247,6 +290,12 @@ fn break_key_to_char(key: u16, shift: bool) -> Option<char> {
 pub struct Engine {
     buf: Buffer,
     method: u8,
+    /// Key mappings and capabilities of `method`
+    input: SharedMethod,
+    /// Table loaded by `set_method_table` (used when `method` is CUSTOM)
+    custom_method: Option<SharedMethod>,
+    /// User key bindings applied on top of `method`
+    key_bindings: KeyBindings,
     enabled: bool,
     last_transform: Option<Transform>,
     shortcuts: ShortcutTable,
@@ -322,6 +371,30 @@ pub struct Engine {
     /// Tracks if auto-capitalize was just used on the current word
     /// Used to restore pending_capitalize when user deletes the capitalized letter
     auto_capitalize_used: bool,
+    /// Telex bracket vowels: `[` types ơ, `]` types ư (Unikey style)
+    /// When false, brackets are plain punctuation that break the word
+    bracket_vowels: bool,
+    /// Orthography profile id (`orthography::id`): highlands letters and finals
+    orthography: u8,
+    /// Logical keyboard layout applied to positional keycodes
+    /// (Dvorak, Colemak, AZERTY, QWERTZ users typing on QWERTY-labelled keycodes)
+    layout: KeyboardLayout,
+    /// Charset of result output (`charset::id`): Unicode or a legacy 8-bit charset
+    charset: u8,
+    /// Recent text the host shows, in Unicode (see `multi_unit_letters`), to
+    /// turn letter backspace counts into code units of the output
+    screen: Vec<char>,
+    /// Output that didn't fit in the last Result (long shortcut replacements)
+    /// Drained in `MAX`-sized chunks by `next_output_chunk`
+    pending_output: Vec<char>,
+    /// Encoding hosts get from `ime_result_text`
+    output_encoding: OutputEncoding,
+    /// Record events for `take_events` (off by default: no allocation cost)
+    events_enabled: bool,
+    /// Events recorded since the last `take_events`
+    events: Vec<Event>,
+    /// Typing state of inactive focus contexts (see `focus`)
+    contexts: ContextStore,
 }
 
 impl Default for Engine {
@@ -335,6 +408,9 @@ impl Engine {
         Self {
             buf: Buffer::new(),
             method: 0,
+            input: input::shared(input::id::TELEX),
+            custom_method: None,
+            key_bindings: KeyBindings::default(),
             enabled: true,
             last_transform: None,
             shortcuts: ShortcutTable::with_defaults(),
@@ -359,11 +435,73 @@ impl Engine {
             auto_capitalize: false, // Default: OFF
             pending_capitalize: false,
             auto_capitalize_used: false,
+            bracket_vowels: false, // Default: OFF
+            orthography: orthography::id::STANDARD,
+            layout: KeyboardLayout::qwerty(),
+            charset: charset::id::UNICODE,
+            screen: Vec::new(),
+            pending_output: Vec::new(),
+            output_encoding: OutputEncoding::Utf32,
+            events_enabled: false,
+            events: Vec::new(),
+            contexts: ContextStore::new(),
         }
     }
 
-    pub fn set_method(&mut self, method: u8) {
+    /// Select input method by id (see `input::id`)
+    ///
+    /// CUSTOM selects the table from `set_method_table`. Key bindings stay
+    /// applied; where they clash with the new method's keys, the bound key
+    /// wins. Returns false (method unchanged) for unknown ids, or CUSTOM
+    /// with no table loaded.
+    pub fn set_method(&mut self, method: u8) -> bool {
+        if !self.can_select(method) {
+            return false;
+        }
         self.method = method;
+        let base = self.base_method(method);
+        self.input = if self.key_bindings.is_empty() {
+            base
+        } else {
+            Arc::new(Rebound::new(base, self.key_bindings.clone()))
+        };
+        true
+    }
+
+    /// Whether `set_method(method)` would succeed
+    fn can_select(&self, method: u8) -> bool {
+        input::is_known(method) && (method != input::id::CUSTOM || self.custom_method.is_some())
+    }
+
+    /// Method `id` without key bindings
+    fn base_method(&self, id: u8) -> SharedMethod {
+        match (&self.custom_method, id) {
+            (Some(table), input::id::CUSTOM) => SharedMethod::clone(table),
+            _ => input::shared(id),
+        }
+    }
+
+    /// Move or disable modifier keys of the current method (see `input::bindings`)
+    ///
+    /// Returns false (bindings unchanged) if a bound key is still used by
+    /// another function of the method, e.g. `sac:f` in Telex without moving huyền.
+    pub fn set_key_bindings(&mut self, bindings: KeyBindings) -> bool {
+        if !bindings.fits(self.base_method(self.method).as_ref()) {
+            return false;
+        }
+        self.key_bindings = bindings;
+        self.set_method(self.method);
+        true
+    }
+
+    pub fn key_bindings(&self) -> &KeyBindings {
+        &self.key_bindings
+    }
+
+    /// Load a user-defined method table and select it
+    pub fn set_method_table(&mut self, table: TableMethod) {
+        self.custom_method = Some(Arc::new(table));
+        self.set_method(input::id::CUSTOM);
     }
 
     pub fn set_enabled(&mut self, enabled: bool) {
@@ -408,6 +546,134 @@ impl Engine {
         }
     }
 
+    /// Set whether `[`/`]` type ơ/ư (Telex-style methods only)
+    pub fn set_bracket_vowels(&mut self, enabled: bool) {
+        self.bracket_vowels = enabled;
+    }
+
+    /// Select the orthography profile by id (see `data::orthography`)
+    ///
+    /// Returns false (profile unchanged) for unknown ids.
+    pub fn set_orthography(&mut self, orthography_id: u8) -> bool {
+        if orthography::get(orthography_id).is_none() {
+            return false;
+        }
+        if self.orthography != orthography_id {
+            self.orthography = orthography_id;
+            self.screen.clear();
+        }
+        true
+    }
+
+    pub fn orthography(&self) -> u8 {
+        self.orthography
+    }
+
+    /// Current orthography profile
+    fn orth(&self) -> &'static Orthography {
+        orthography::get(self.orthography).unwrap_or(&orthography::STANDARD)
+    }
+
+    /// Set the logical keyboard layout for positional keycodes
+    pub fn set_layout(&mut self, layout: KeyboardLayout) {
+        self.layout = layout;
+        self.clear();
+    }
+
+    pub fn layout(&self) -> &KeyboardLayout {
+        &self.layout
+    }
+
+    /// Select the output charset by id (see `data::charset`)
+    ///
+    /// Legacy charsets also apply to `restore_word` input (decomposed
+    /// letters are read in any charset). Returns false
+    /// (charset unchanged) for unknown ids.
+    pub fn set_charset(&mut self, charset_id: u8) -> bool {
+        if !charset::is_known(charset_id) {
+            return false;
+        }
+        self.charset = charset_id;
+        self.screen.clear();
+        true
+    }
+
+    pub fn charset(&self) -> u8 {
+        self.charset
+    }
+
+    /// Set the encoding used when hosts read result text (`ime_result_text`)
+    pub fn set_output_encoding(&mut self, encoding: OutputEncoding) {
+        self.output_encoding = encoding;
+    }
+
+    pub fn output_encoding(&self) -> OutputEncoding {
+        self.output_encoding
+    }
+
+    /// Enable or disable event recording (disabling drops pending events)
+    pub fn set_events_enabled(&mut self, enabled: bool) {
+        self.events_enabled = enabled;
+        if !enabled {
+            self.events.clear();
+        }
+    }
+
+    /// Drain events recorded since the last call
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Snapshot of all user-facing settings
+    pub fn config(&self) -> EngineConfig {
+        EngineConfig {
+            method: self.method,
+            enabled: self.enabled,
+            skip_w_shortcut: self.skip_w_shortcut,
+            esc_restore: self.esc_restore_enabled,
+            free_tone: self.free_tone_enabled,
+            modern_tone: self.modern_tone,
+            english_auto_restore: self.english_auto_restore,
+            auto_capitalize: self.auto_capitalize,
+            bracket_vowels: self.bracket_vowels,
+            orthography: self.orthography,
+            key_bindings: self.key_bindings.clone(),
+            layout: self.layout.clone(),
+            charset: self.charset,
+            output_encoding: self.output_encoding,
+        }
+    }
+
+    /// Apply all settings at once (same effect as calling each setter)
+    ///
+    /// Returns false (nothing applied) if the method can't be selected:
+    /// an unknown id, or CUSTOM with no table loaded (`set_method_table`).
+    pub fn apply_config(&mut self, config: &EngineConfig) -> bool {
+        if !self.can_select(config.method) {
+            return false;
+        }
+        self.set_method(config.method);
+        self.set_enabled(config.enabled);
+        self.set_skip_w_shortcut(config.skip_w_shortcut);
+        self.set_esc_restore(config.esc_restore);
+        self.set_free_tone(config.free_tone);
+        self.set_modern_tone(config.modern_tone);
+        self.set_english_auto_restore(config.english_auto_restore);
+        self.set_auto_capitalize(config.auto_capitalize);
+        self.set_bracket_vowels(config.bracket_vowels);
+        self.set_orthography(config.orthography);
+        // Bindings that clash with the configured method are dropped
+        if !self.set_key_bindings(config.key_bindings.clone()) {
+            self.set_key_bindings(KeyBindings::default());
+        }
+        self.set_charset(config.charset);
+        self.set_output_encoding(config.output_encoding);
+        if self.layout != config.layout {
+            self.set_layout(config.layout.clone());
+        }
+        true
+    }
+
     pub fn shortcuts(&self) -> &ShortcutTable {
         &self.shortcuts
     }
@@ -416,12 +682,20 @@ impl Engine {
         &mut self.shortcuts
     }
 
-    /// Get current input method as InputMethod enum
+    /// Shortcut set for the active method, from its mark and tone keys:
+    /// letters take Telex shortcuts, digits VNI ones, both (Telex+VNI) all
     fn current_input_method(&self) -> InputMethod {
-        match self.method {
-            0 => InputMethod::Telex,
-            1 => InputMethod::Vni,
-            _ => InputMethod::All,
+        let m = &self.input;
+        let modifies = |is_kind: fn(u16) -> bool| {
+            (0..128)
+                .filter(|&k| is_kind(k))
+                .any(|k| m.mark_ext(k, false).is_some() || m.tone_ext(k, false).is_some())
+        };
+        match (modifies(keys::is_letter), modifies(keys::is_number)) {
+            (true, true) => InputMethod::All,
+            (true, false) => InputMethod::Telex,
+            (false, true) => InputMethod::Vni,
+            (false, false) => InputMethod::Other,
         }
     }
 
@@ -435,6 +709,55 @@ impl Engine {
         self.on_key_ext(key, caps, ctrl, false)
     }
 
+    /// Handle a platform-neutral key event
+    pub fn on_key_event(&mut self, ev: KeyEvent) -> Result {
+        self.on_key_ext(ev.key, ev.caps, ev.ctrl, ev.shift)
+    }
+
+    /// Handle key event from any supported keycode namespace
+    ///
+    /// # Arguments
+    /// * `ns` - Keycode namespace (macOS VK, Windows VK, X11 keysym, evdev)
+    /// * `code` - Keycode in that namespace
+    /// * `mods` - Modifier bits (`keymap::modifier::{CAPS, CTRL, SHIFT}`)
+    ///
+    /// Unmapped keys are passed through without touching engine state.
+    /// X11 keysyms are already logical characters, and Windows letter VK codes
+    /// already follow the active layout, so both skip layout remapping.
+    pub fn on_key_platform(&mut self, ns: KeyNamespace, code: u32, mods: u8) -> Result {
+        let logical = match ns {
+            KeyNamespace::X11Keysym => true,
+            KeyNamespace::WinVk => (0x41..=0x5A).contains(&code),
+            _ => false,
+        };
+        match KeyEvent::from_platform(ns, code, mods) {
+            Some(ev) if logical => self.handle_key(ev.key, ev.caps, ev.ctrl, ev.shift),
+            Some(ev) => self.on_key_event(ev),
+            None => Result::none(),
+        }
+    }
+
+    /// Handle an already-resolved character instead of a physical key
+    ///
+    /// For hosts that only see characters (IBus, Wayland text-input, browser
+    /// extensions, editor plugins), so the engine works regardless of the
+    /// user's physical layout. Shifted symbols (`@`, `?`, `{`...) are mapped
+    /// back to their key + Shift, and `\x08`, `\t`, `\n`, `\x1b` act as
+    /// Backspace, Tab, Enter and ESC.
+    ///
+    /// Any other character (e.g. `é` from a foreign layout) ends the current
+    /// word: engine state is cleared and the character is passed through.
+    pub fn on_char(&mut self, c: char) -> Result {
+        // Characters are already logical - skip layout remapping
+        match KeyEvent::from_char(c) {
+            Some(ev) => self.handle_key(ev.key, ev.caps, ev.ctrl, ev.shift),
+            None => {
+                self.clear_all();
+                Result::none()
+            }
+        }
+    }
+
     /// Check if key+shift combo is a raw mode prefix character
     /// Raw prefixes: @ # : /
     #[allow(dead_code)] // TEMP DISABLED
@@ -462,7 +785,113 @@ impl Engine {
     /// * `caps` - true if Caps Lock is active (for uppercase letters)
     /// * `ctrl` - true if Cmd/Ctrl/Alt is pressed (bypasses IME)
     /// * `shift` - true if Shift key is pressed (for symbols like @, #, $)
+    ///
+    /// `key` is a physical position; it is remapped through the active
+    /// keyboard layout before processing.
     pub fn on_key_ext(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        let key = self.layout.map(key);
+        self.handle_key(key, caps, ctrl, shift)
+    }
+
+    /// Handle a logical key event (after layout remapping)
+    fn handle_key(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        let result = self.handle_key_unicode(key, caps, ctrl, shift);
+        if !self.multi_unit_letters() {
+            return result;
+        }
+        self.encode_result(result, key, caps || shift, ctrl, shift)
+    }
+
+    /// Whether a letter can take several code units of output: legacy
+    /// charsets, NFD, and highlands letters without a precomposed form
+    /// (ơ̆, ĕ́ are base + combining marks even in Unicode)
+    fn multi_unit_letters(&self) -> bool {
+        self.charset != charset::id::UNICODE || self.orthography != orthography::id::STANDARD
+    }
+
+    /// Re-encode a Unicode result in the output charset
+    ///
+    /// Backspace counts letters, but a letter can take several code units
+    /// (VNI-Windows "á" is "aù", "ơ̆" is two code points), so counts are
+    /// recomputed from the letters tracked in `screen`. A passed-through Backspace that would leave half
+    /// a letter becomes a Send deleting all of it.
+    fn encode_result(
+        &mut self,
+        result: Result,
+        key: u16,
+        caps: bool,
+        ctrl: bool,
+        shift: bool,
+    ) -> Result {
+        let typed = match key {
+            keys::SPACE => Some(' '),
+            _ => utils::key_to_char_ext(key, caps, shift),
+        };
+        if ctrl {
+            self.screen.clear();
+            return result;
+        }
+        if result.action != Action::Send as u8 {
+            if key == keys::DELETE {
+                let width = self.pop_screen_letter();
+                if width > 1 {
+                    return Result::send(width as u8, &[]);
+                }
+            } else if let Some(c) = typed {
+                self.push_screen(&[c]);
+            } else {
+                // Enter, Tab, arrows: the cursor left the tracked text
+                self.screen.clear();
+            }
+            return result;
+        }
+
+        let backspace: usize = (0..result.backspace)
+            .map(|_| self.pop_screen_letter())
+            .sum();
+        let mut output: Vec<char> = result.output().collect();
+        output.append(&mut self.pending_output);
+        self.push_screen(&output);
+        // Break keys are typed after the output unless consumed
+        if key != keys::SPACE && keys::is_break_ext(key, shift) && !result.key_consumed() {
+            if let Some(c) = typed {
+                self.push_screen(&[c]);
+            }
+        }
+
+        let mut encoded = Vec::with_capacity(output.len());
+        for c in output {
+            charset::encode_char(c, self.charset, &mut encoded);
+        }
+        let mut sent = self.send_output(backspace.min(u8::MAX as usize) as u8, encoded);
+        sent.flags |= result.flags & FLAG_KEY_CONSUMED;
+        sent
+    }
+
+    /// Track text the host now shows (bounded: older text counts one unit per letter)
+    fn push_screen(&mut self, text: &[char]) {
+        self.screen.extend_from_slice(text);
+        let excess = self.screen.len().saturating_sub(SCREEN_CAPACITY);
+        self.screen.drain(..excess);
+    }
+
+    /// Drop the last tracked letter (base + combining marks), returning its
+    /// width in code units of the output charset
+    fn pop_screen_letter(&mut self) -> usize {
+        let Some(start) = self.screen.iter().rposition(|&c| !chars::is_combining(c)) else {
+            self.screen.clear();
+            return 1;
+        };
+        let width = charset::letter_width(&self.screen[start..], self.charset);
+        self.screen.truncate(start);
+        width
+    }
+
+    /// `handle_key` with Unicode output
+    fn handle_key_unicode(&mut self, key: u16, caps: bool, ctrl: bool, shift: bool) -> Result {
+        // Unfetched chunks of the previous output are dropped on the next key
+        self.pending_output.clear();
+
         // Issue #129: Process shortcuts even when IME is disabled
         // Only bypass completely for Ctrl/Cmd modifier keys
         if ctrl {
@@ -498,8 +927,14 @@ impl Engine {
                         // Found a match! Send the replacement
                         let output: Vec<char> = m.output.chars().collect();
                         let backspace_count = (m.backspace_count as u8).saturating_sub(1);
+                        self.emit(|e| Event::ShortcutExpanded {
+                            trigger: e.shortcut_prefix.clone(),
+                            replacement: m.output.clone(),
+                        });
                         self.shortcut_prefix.clear();
-                        return Result::send_consumed(backspace_count, &output);
+                        let mut result = self.send_output(backspace_count, output);
+                        result.flags |= FLAG_KEY_CONSUMED;
+                        return result;
                     }
                     // No match yet, keep accumulating
                     return Result::none();
@@ -546,6 +981,10 @@ impl Engine {
             // Example: "restore" → buffer was "rếtore" (6 chars), raw_input has 7 keys
             // After this, buffer has "restore" (7 chars) for correct history
             if restore_result.action != 0 {
+                self.emit(|e| Event::AutoRestore {
+                    from: e.buf.to_full_string(),
+                    to: restore_result.output().filter(|&c| c != ' ').collect(),
+                });
                 self.buf.clear();
                 for &(key, caps, _) in &self.raw_input {
                     self.buf.push(Char::new(key, caps));
@@ -554,6 +993,10 @@ impl Engine {
 
             // Push buffer to history before clearing (for backspace-after-space feature)
             if !self.buf.is_empty() {
+                self.emit(|e| Event::Commit {
+                    text: e.buf.to_full_string(),
+                    raw: e.raw_string(),
+                });
                 self.word_history.push(self.buf.clone());
                 self.spaces_after_commit = 1; // First space after word
             } else if self.spaces_after_commit > 0 {
@@ -573,12 +1016,39 @@ impl Engine {
             } else {
                 Result::none()
             };
+            if result.action != 0 {
+                self.emit(|e| Event::EscRestore {
+                    from: e.buf.to_full_string(),
+                    to: result.output().collect(),
+                });
+            }
             self.clear();
             self.word_history.clear();
             self.spaces_after_commit = 0;
             return result;
         }
 
+        // VIQR: punctuation right after a letter is a modifier (a' → á, o+ → ơ)
+        // It only breaks the word when it doesn't apply
+        if self.input.symbol_modifiers() && !self.buf.is_empty() {
+            if let Some(result) = self.try_symbol_modifier(key, shift) {
+                return result;
+            }
+        }
+        // Keys that type a Vietnamese letter (direct layout 2 → â, Telex `[` → ơ)
+        // Brackets only break the word when the letter can't extend it
+        if let Some(letter) = self.direct_letter(key) {
+            if let Some(result) = self.try_direct_letter(key, letter, caps, shift) {
+                return result;
+            }
+        }
+        // VIQR escape: `\` ends the word without being typed, so the next
+        // symbol stays literal ("cha`o\." → "chào.")
+        let viqr_escape = self.input.symbol_modifiers()
+            && key == keys::BACKSLASH
+            && !shift
+            && !self.buf.is_empty();
+
         // Other break keys (punctuation, arrows, etc.)
         // Also trigger auto-restore for invalid Vietnamese before clearing
         // Use is_break_ext to handle shifted symbols like @, !, #, etc.
@@ -618,8 +1088,14 @@ impl Engine {
                         // Example: "->" trigger has backspace_count=2, but only '-' is on screen
                         let output: Vec<char> = m.output.chars().collect();
                         let backspace_count = (m.backspace_count as u8).saturating_sub(1);
+                        self.emit(|e| Event::ShortcutExpanded {
+                            trigger: e.shortcut_prefix.clone(),
+                            replacement: m.output.clone(),
+                        });
                         self.shortcut_prefix.clear();
-                        return Result::send_consumed(backspace_count, &output);
+                        let mut result = self.send_output(backspace_count, output);
+                        result.flags |= FLAG_KEY_CONSUMED;
+                        return result;
                     }
 
                     // Auto-capitalize: set pending if sentence-ending (! or ?)
@@ -641,10 +1117,36 @@ impl Engine {
             self.auto_capitalize_used = false; // Reset on word boundary
 
             let restore_result = self.try_auto_restore_on_break();
+            if !self.buf.is_empty() {
+                let composed = self.buf.to_full_string();
+                if restore_result.action != 0 {
+                    self.emit(|_| Event::AutoRestore {
+                        from: composed.clone(),
+                        to: restore_result.output().collect(),
+                    });
+                }
+                self.emit(|e| Event::Commit {
+                    text: if restore_result.action != 0 {
+                        restore_result.output().collect()
+                    } else {
+                        composed
+                    },
+                    raw: e.raw_string(),
+                });
+            }
             self.clear();
             self.word_history.clear();
             self.spaces_after_commit = 0;
 
+            if viqr_escape {
+                let mut result = restore_result;
+                if result.action == Action::None as u8 {
+                    result = Result::send(0, &[]);
+                }
+                result.flags |= FLAG_KEY_CONSUMED;
+                return result;
+            }
+
             // Issue #130: After clearing buffer, store break char as potential shortcut prefix
             // This allows shortcuts like "->" to work after "abc->" (where "-" clears "abc")
             // Example: type "→abc->" should produce "→abc→"
@@ -710,7 +1212,7 @@ impl Engine {
         // This allows "cha" + restore + "f" → "chà" (f is mark key)
         // But "cha" + restore + "m" → "m..." (m is consonant, start fresh)
         if self.restored_pending_clear && keys::is_letter(key) {
-            let m = input::get(self.method);
+            let m = &self.input;
             let is_mark_or_tone = m.mark(key).is_some() || m.tone(key).is_some();
             if keys::is_consonant(key) && !is_mark_or_tone {
                 // Regular consonant (not mark/tone key) = user starting new word
@@ -725,6 +1227,11 @@ impl Engine {
         let effective_caps = if self.pending_capitalize && keys::is_letter(key) {
             self.pending_capitalize = false;
             self.auto_capitalize_used = true; // Track that we used auto-capitalize
+            if was_auto_capitalized {
+                self.emit(|_| Event::AutoCapitalize {
+                    letter: utils::key_to_char(key, true).unwrap_or_default(),
+                });
+            }
             true // Force uppercase
         } else {
             // Reset pending on number (e.g., "1.5" should not capitalize "5")
@@ -755,7 +1262,7 @@ impl Engine {
 
     /// Main processing pipeline - pattern-based
     fn process(&mut self, key: u16, caps: bool, shift: bool) -> Result {
-        let m = input::get(self.method);
+        let m = SharedMethod::clone(&self.input);
 
         // Handle pending mark revert pop: if previous key was a mark revert (like "ss"),
         // and THIS key is a consonant, pop the consumed modifier from raw_input.
@@ -798,8 +1305,8 @@ impl Engine {
         //   e.g., "dod" → "đo" + 'o' → "đô" (user typed d-o-d-o fast, intended "ddoo")
         // - Stroke keys ('d') - handled separately in try_stroke for proper revert behavior
         //   e.g., "dadd" → "dad" (d reverts stroke and adds itself, not "dadd")
-        let is_mark_key = m.mark(key).is_some();
-        let is_tone_key = m.tone(key).is_some();
+        let is_mark_key = m.mark_ext(key, shift).is_some();
+        let is_tone_key = m.tone_ext(key, shift).is_some();
         let is_stroke_key = m.stroke(key);
 
         if keys::is_letter(key)
@@ -819,7 +1326,7 @@ impl Engine {
             let mut buf_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
             buf_keys.push(key);
 
-            if !is_valid(&raw_keys) && !is_valid(&buf_keys) {
+            if !is_valid_in(&raw_keys, self.orth()) && !is_valid_in(&buf_keys, self.orth()) {
                 // Invalid pattern - revert stroke and rebuild from raw_input
                 if let Some(raw_chars) = self.build_raw_chars() {
                     // Calculate backspace: screen shows buffer content (e.g., "đe")
@@ -837,22 +1344,29 @@ impl Engine {
             }
         }
 
-        // In VNI mode, if Shift is pressed with a number key, skip all modifiers
+        // If Shift is pressed with a number key, skip all modifiers
         // User wants the symbol (@ for Shift+2, # for Shift+3, etc.), not VNI marks
-        let skip_vni_modifiers = self.method == 1 && shift && keys::is_number(key);
+        let skip_modifiers = shift && keys::is_number(key);
 
         // Check modifiers by scanning buffer for patterns
 
+        // 0. Orthography letters (highlands ĕ ŏ ŭ ơ̆ č ñ)
+        if !skip_modifiers {
+            if let Some(result) = self.try_orthography_letter(key, caps, shift) {
+                return result;
+            }
+        }
+
         // 1. Stroke modifier (d → đ)
-        if !skip_vni_modifiers && m.stroke(key) {
+        if !skip_modifiers && m.stroke(key) {
             if let Some(result) = self.try_stroke(key) {
                 return result;
             }
         }
 
         // 2. Tone modifier (circumflex, horn, breve)
-        if !skip_vni_modifiers {
-            if let Some(tone_type) = m.tone(key) {
+        if !skip_modifiers {
+            if let Some(tone_type) = m.tone_ext(key, shift) {
                 let targets = m.tone_targets(key);
                 if let Some(result) = self.try_tone(key, caps, tone_type, targets) {
                     return result;
@@ -861,8 +1375,8 @@ impl Engine {
         }
 
         // 3. Mark modifier
-        if !skip_vni_modifiers {
-            if let Some(mark_val) = m.mark(key) {
+        if !skip_modifiers {
+            if let Some(mark_val) = m.mark_ext(key, shift) {
                 if let Some(result) = self.try_mark(key, caps, mark_val) {
                     return result;
                 }
@@ -872,7 +1386,7 @@ impl Engine {
         // 4. Remove modifier
         // Only consume key if there's something to remove; otherwise fall through to normal letter
         // This allows shortcuts like "zz" to work when buffer has no marks/tones to remove
-        if !skip_vni_modifiers && m.remove(key) {
+        if !skip_modifiers && m.remove(key) {
             if let Some(result) = self.try_remove() {
                 return result;
             }
@@ -880,7 +1394,7 @@ impl Engine {
 
         // 5. In Telex: "w" as vowel "ư" when valid Vietnamese context
         // Examples: "w" → "ư", "nhw" → "như", but "kw" → "kw" (invalid)
-        if self.method == 0 && key == keys::W {
+        if m.w_as_vowel() && key == keys::W {
             if let Some(result) = self.try_w_as_vowel(caps) {
                 return result;
             }
@@ -890,6 +1404,146 @@ impl Engine {
         self.handle_normal_letter(key, caps)
     }
 
+    /// Apply a VIQR punctuation modifier to the current word
+    ///
+    /// Returns None when the key is not a modifier or can't apply, so it is
+    /// handled as a regular break key. Typing the same modifier twice undoes
+    /// it and lets the symbol through as punctuation ("a''" → "a'").
+    fn try_symbol_modifier(&mut self, key: u16, shift: bool) -> Option<Result> {
+        let m = SharedMethod::clone(&self.input);
+        let mark_val = m.mark_ext(key, shift);
+        let tone_type = m.tone_ext(key, shift);
+        if mark_val.is_none() && tone_type.is_none() {
+            return None;
+        }
+
+        let follows_itself = self
+            .raw_input
+            .last()
+            .is_some_and(|&(k, _, s)| k == key && s == shift);
+        let repeated = follows_itself
+            && match self.last_transform {
+                Some(Transform::Mark(k, v)) => k == key && mark_val == Some(v),
+                Some(Transform::Tone(k, v)) => k == key && tone_type.map(|t| t.value()) == Some(v),
+                _ => false,
+            };
+        if repeated {
+            return Some(self.undo_symbol_modifier(key, shift, mark_val.is_some()));
+        }
+        // Word already has this mark: the symbol is punctuation ("ba.n." → "bạn.")
+        if mark_val.is_some_and(|v| self.buf.iter().any(|c| c.mark == v)) {
+            return None;
+        }
+        // ` then ~ share a key: switch huyền → ngã instead of reverting
+        if matches!(self.last_transform, Some(Transform::Mark(k, _)) if k == key) {
+            self.last_transform = None;
+        }
+
+        self.raw_input.push((key, false, shift));
+        let result = match (tone_type, mark_val) {
+            (Some(tone_type), _) => self.try_tone(key, false, tone_type, m.tone_targets(key)),
+            (None, Some(mark_val)) => self.try_mark(key, false, mark_val),
+            (None, None) => None,
+        };
+        match result {
+            Some(mut result) => {
+                // The symbol itself must not reach the app
+                result.flags |= FLAG_KEY_CONSUMED;
+                Some(result)
+            }
+            None => {
+                self.raw_input.pop();
+                None
+            }
+        }
+    }
+
+    /// Remove the mark/tone set by the previous VIQR modifier and end the word
+    ///
+    /// The symbol key is passed through (no `FLAG_KEY_CONSUMED`).
+    fn undo_symbol_modifier(&mut self, key: u16, shift: bool, is_mark: bool) -> Result {
+        let pos = self.buf.find_vowels().into_iter().rev().find(|&i| {
+            self.buf
+                .get(i)
+                .is_some_and(|c| if is_mark { c.mark > 0 } else { c.tone > 0 })
+        });
+        let result = match pos {
+            Some(pos) => {
+                if let Some(c) = self.buf.get_mut(pos) {
+                    if is_mark {
+                        c.mark = mark::NONE;
+                    } else {
+                        c.tone = tone::NONE;
+                    }
+                }
+                self.rebuild_from(pos)
+            }
+            None => Result::none(),
+        };
+
+        // The literal symbol is punctuation: commit the word as a break key would
+        self.emit(|e| Event::Commit {
+            text: e.buf.to_full_string(),
+            raw: e.raw_string(),
+        });
+        if self.auto_capitalize && is_sentence_ending(key, shift) {
+            self.pending_capitalize = true;
+        }
+        self.auto_capitalize_used = false;
+        self.clear();
+        self.word_history.clear();
+        self.spaces_after_commit = 0;
+        result
+    }
+
+    /// Record an event (built lazily, only when recording is enabled)
+    fn emit(&mut self, event: impl FnOnce(&Self) -> Event) {
+        if self.events_enabled {
+            let event = event(self);
+            self.events.push(event);
+        }
+    }
+
+    /// Raw keystrokes of the current word as text
+    fn raw_string(&self) -> String {
+        self.raw_input
+            .iter()
+            .filter_map(|&(key, caps, shift)| utils::key_to_char_ext(key, caps, shift))
+            .collect()
+    }
+
+    /// Send output of any length: the first `MAX` chars go in the Result,
+    /// the rest is kept for `next_output_chunk` (sets `FLAG_MORE_OUTPUT`)
+    fn send_output(&mut self, backspace: u8, mut output: Vec<char>) -> Result {
+        if output.len() <= MAX {
+            return Result::send(backspace, &output);
+        }
+        self.pending_output = output.split_off(MAX);
+        let mut result = Result::send(backspace, &output);
+        result.flags |= FLAG_MORE_OUTPUT;
+        result
+    }
+
+    /// Next chunk of output that didn't fit in the last Result
+    ///
+    /// Call while the previous Result has `FLAG_MORE_OUTPUT` set. Chunks are
+    /// plain `Send` results without backspace; the last one has the flag clear.
+    /// Returns None if nothing is pending (pending output is dropped on the next key).
+    pub fn next_output_chunk(&mut self) -> Option<Result> {
+        if self.pending_output.is_empty() {
+            return None;
+        }
+        let rest = self
+            .pending_output
+            .split_off(self.pending_output.len().min(MAX));
+        let chunk = std::mem::replace(&mut self.pending_output, rest);
+        let mut result = Result::send(0, &chunk);
+        if !self.pending_output.is_empty() {
+            result.flags |= FLAG_MORE_OUTPUT;
+        }
+        Some(result)
+    }
+
     /// Try word boundary shortcuts (triggered by space, punctuation, etc.)
     fn try_word_boundary_shortcut(&mut self) -> Result {
         // Issue #107: Allow shortcuts with special char prefix (like "#fne")
@@ -919,8 +1573,18 @@ impl Engine {
                 .try_match_for_method(&full_trigger, Some(' '), true, input_method)
         {
             let output: Vec<char> = m.output.chars().collect();
+            self.emit(|_| {
+                let mut replacement = m.output.clone();
+                if m.include_trigger_key {
+                    replacement.pop();
+                }
+                Event::ShortcutExpanded {
+                    trigger: full_trigger,
+                    replacement,
+                }
+            });
             // backspace_count = trigger.len() which already includes prefix (e.g., "#fne" = 4)
-            return Result::send(m.backspace_count as u8, &output);
+            return self.send_output(m.backspace_count as u8, output);
         }
 
         Result::none()
@@ -995,7 +1659,7 @@ impl Engine {
         // Use is_valid_with_tones to check modifier requirements (e.g., E+U needs circumflex)
         let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
         let buffer_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
-        if is_valid_with_tones(&buffer_keys, &buffer_tones) {
+        if is_valid_with_tones_in(&buffer_keys, &buffer_tones, self.orth()) {
             self.last_transform = Some(Transform::WAsVowel);
             self.had_any_transform = true;
 
@@ -1010,6 +1674,208 @@ impl Engine {
         None
     }
 
+    /// Letter typed directly by `key`: the method's own (direct layout),
+    /// or ơ/ư on the brackets when the Telex bracket option is on
+    fn direct_letter(&self, key: u16) -> Option<DirectLetter> {
+        if self.bracket_vowels && self.input.w_as_vowel() {
+            match key {
+                keys::LBRACKET => return Some(DirectLetter::vowel(keys::O, tone::HORN)),
+                keys::RBRACKET => return Some(DirectLetter::vowel(keys::U, tone::HORN)),
+                _ => {}
+            }
+        }
+        self.input.direct_letter(key)
+    }
+
+    /// Check a direct letter can extend the word (before validation)
+    ///
+    /// Vowel patterns only check base keys (aơ passes as "ao"), so check the
+    /// modifier: the letter may only follow a vowel in uâ, iê/yê/uê, uô, oă
+    /// and uơ/ươ. The i of a gi initial isn't a vowel here (giữa).
+    /// đ only starts a word.
+    fn direct_letter_fits(&self, letter: DirectLetter) -> bool {
+        if letter.stroke {
+            return self.buf.is_empty();
+        }
+        let Some(prev) = self.buf.last().map(|c| c.key) else {
+            return true;
+        };
+        if !keys::is_vowel(prev) {
+            return true;
+        }
+        let gi_initial = self.buf.len() == 2
+            && self.buf.get(0).is_some_and(|c| c.key == keys::G)
+            && prev == keys::I;
+        gi_initial
+            || matches!(
+                (prev, letter.key, letter.tone),
+                (keys::U, keys::A | keys::O, tone::CIRCUMFLEX)
+                    | (keys::I | keys::Y | keys::U, keys::E, tone::CIRCUMFLEX)
+                    | (keys::O, keys::A, tone::HORN)
+                    | (keys::U, keys::O, tone::HORN)
+            )
+    }
+
+    /// Insert a letter typed by a single key (ă â ê ô ơ ư đ)
+    ///
+    /// The letter is validated and takes the tone like one built with
+    /// modifiers: "t[s" → "tớ", "ng][if" → "người" (Telex brackets),
+    /// "t4i8" → "tối" (direct layout). Returns None if the result isn't
+    /// valid Vietnamese, so the key is typed as usual.
+    fn try_direct_letter(
+        &mut self,
+        key: u16,
+        letter: DirectLetter,
+        caps: bool,
+        shift: bool,
+    ) -> Option<Result> {
+        if !self.free_tone_enabled && !self.direct_letter_fits(letter) {
+            return None;
+        }
+        let was_auto_capitalized = self.pending_capitalize && !caps && !shift;
+        let caps = caps || shift || self.pending_capitalize;
+
+        let mut c = Char::new(letter.key, caps);
+        c.tone = letter.tone;
+        c.stroke = letter.stroke;
+        self.buf.push(c);
+        if !self.free_tone_enabled && !letter.stroke {
+            let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
+            let buffer_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
+            if !is_valid_with_tones_in(&buffer_keys, &buffer_tones, self.orth()) {
+                self.buf.pop();
+                return None;
+            }
+        }
+
+        // Same bookkeeping as a typed letter
+        self.restored_pending_clear = false;
+        if self.pending_capitalize {
+            self.pending_capitalize = false;
+            self.auto_capitalize_used = true;
+            if was_auto_capitalized {
+                self.emit(|e| Event::AutoCapitalize {
+                    letter: e.buf.to_full_string().chars().last().unwrap_or_default(),
+                });
+            }
+        }
+        self.raw_input.push((key, caps, shift));
+        self.last_transform = None;
+        self.had_any_transform = true;
+
+        // A mark typed earlier may move to the new vowel ("thuj[" → "thuợ")
+        let from = match self.reposition_tone_if_needed() {
+            Some((old_pos, new_pos)) => old_pos.min(new_pos),
+            None => self.buf.len() - 1,
+        };
+        let mut result = self.rebuild_from_after_insert(from);
+        result.flags |= FLAG_KEY_CONSUMED;
+        Some(result)
+    }
+
+    /// Add a breve, caron or tilde of the orthography profile (ĕ ŏ ŭ ơ̆ č ñ)
+    ///
+    /// The breve key (`v` in Telex, the method's breve key elsewhere) reaches
+    /// the last vowel if it is e/o/u/ơ; ă stays with the method's own keys.
+    /// c/n take a stroke by doubling ("cc" → "č") where the method strokes
+    /// "dd", or from a stroke key that isn't a letter (VNI 9) once no d is
+    /// left to stroke. Typing the key again undoes it ("evv" → "ev").
+    fn try_orthography_letter(&mut self, key: u16, caps: bool, shift: bool) -> Option<Result> {
+        let orth = self.orth();
+        if !orth.has_letters() {
+            return None;
+        }
+        if let Some(Transform::OrthographyLetter(last_key, pos)) = self.last_transform {
+            if last_key == key {
+                return Some(self.revert_orthography_letter(key, caps, pos));
+            }
+        }
+
+        let m = SharedMethod::clone(&self.input);
+        let is_breve_key = m.tone_ext(key, shift) == Some(ToneType::Breve)
+            || (key == orthography::TELEX_BREVE_KEY && m.w_as_vowel());
+        let pos = if is_breve_key {
+            let pos = *self.buf.find_vowels().last()?;
+            let c = self.buf.get(pos)?;
+            let fits = (orth.breve_vowels.contains(&c.key) && c.tone == tone::NONE)
+                || (c.key == keys::O && c.tone == tone::HORN);
+            if !fits {
+                return None;
+            }
+            pos
+        } else if keys::is_letter(key) {
+            // Doubled letter, but not a third one after an undo ("ccc" stays "cc")
+            if !m.stroke(keys::D) || !orth.stroke_consonants.contains(&key) {
+                return None;
+            }
+            let pos = self.buf.len().checked_sub(1)?;
+            let c = self.buf.get(pos)?;
+            let tripled = pos > 0 && self.buf.get(pos - 1).is_some_and(|p| p.key == key);
+            if c.key != key || c.stroke || tripled {
+                return None;
+            }
+            pos
+        } else if m.stroke(key) {
+            if self.buf.iter().any(|c| c.key == keys::D && !c.stroke) {
+                return None;
+            }
+            self.buf
+                .iter()
+                .rposition(|c| orth.stroke_consonants.contains(&c.key) && !c.stroke)?
+        } else {
+            return None;
+        };
+
+        let before = *self.buf.get(pos)?;
+        if let Some(c) = self.buf.get_mut(pos) {
+            if keys::is_vowel(c.key) {
+                c.tone = if c.tone == tone::HORN {
+                    tone::HORN_SHORT
+                } else {
+                    tone::SHORT
+                };
+            } else {
+                c.stroke = true;
+            }
+        }
+        if !self.free_tone_enabled && self.buf.iter().any(|c| keys::is_vowel(c.key)) {
+            let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
+            let buffer_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
+            if !is_valid_with_tones_in(&buffer_keys, &buffer_tones, orth) {
+                if let Some(c) = self.buf.get_mut(pos) {
+                    *c = before;
+                }
+                return None;
+            }
+        }
+
+        self.last_transform = Some(Transform::OrthographyLetter(key, pos));
+        self.had_any_transform = true;
+        Some(self.rebuild_from(pos))
+    }
+
+    /// Undo `try_orthography_letter` and type its key ("evv" → "ev", "ccc" → "cc")
+    fn revert_orthography_letter(&mut self, key: u16, caps: bool, pos: usize) -> Result {
+        self.last_transform = None;
+        if let Some(c) = self.buf.get_mut(pos) {
+            match c.tone {
+                tone::SHORT => c.tone = tone::NONE,
+                tone::HORN_SHORT => c.tone = tone::HORN,
+                _ => c.stroke = false,
+            }
+        }
+        // Drop the key that made the letter: raw [e, v, v] → [e, v]
+        if self.raw_input.len() >= 2 {
+            let current = self.raw_input.pop();
+            self.raw_input.pop();
+            if let Some(c) = current {
+                self.raw_input.push(c);
+            }
+        }
+        self.buf.push(Char::new(key, caps));
+        self.rebuild_from_after_insert(pos)
+    }
+
     /// Try to apply stroke transformation by scanning buffer
     ///
     /// Issue #51: In Telex mode, only apply stroke when the new 'd' is ADJACENT to
@@ -1096,7 +1962,16 @@ impl Engine {
 
         // Find position of un-stroked 'd' to apply stroke
         // Also track if this is a short pattern stroke (revertible)
-        let (pos, is_short_pattern_stroke) = if self.method == 0 {
+        let stroke_style = self.input.stroke_style(key);
+        let (pos, is_short_pattern_stroke) = if stroke_style == StrokeStyle::Adjacent {
+            // Only adjacent "dd" strokes ("dad" stays English)
+            let last_pos = self.buf.len().checked_sub(1)?;
+            let last_char = self.buf.get(last_pos)?;
+            if last_char.key != keys::D || last_char.stroke {
+                return None;
+            }
+            (last_pos, false)
+        } else if stroke_style == StrokeStyle::Delayed {
             // Telex: First try adjacent 'd' (last char is un-stroked d)
             let last_pos = self.buf.len().checked_sub(1)?;
             let last_char = self.buf.get(last_pos)?;
@@ -1120,7 +1995,7 @@ impl Engine {
                 // Must form valid Vietnamese (including vowel pattern) for delayed stroke
                 // Use is_valid() instead of is_valid_for_transform() to check vowel patterns
                 // This prevents "dea" + "d" → "đea" (invalid "ea" diphthong)
-                if !is_valid(&buffer_keys) {
+                if !is_valid_in(&buffer_keys, self.orth()) {
                     return None;
                 }
 
@@ -1173,7 +2048,10 @@ impl Engine {
         // Only validate if buffer has vowels (complete syllable)
         // Allow stroke on initial consonant before vowel is typed (e.g., "dd" → "đ" then "đi")
         // Skip validation if free_tone mode is enabled
-        if !self.free_tone_enabled && has_vowel && !is_valid_for_transform(&buffer_keys) {
+        if !self.free_tone_enabled
+            && has_vowel
+            && !is_valid_for_transform_in(&buffer_keys, self.orth())
+        {
             return None;
         }
 
@@ -1227,7 +2105,7 @@ impl Engine {
         // Skip validation if free_tone mode is enabled
         let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
 
-        if !self.free_tone_enabled && !is_valid_for_transform(&buffer_keys) {
+        if !self.free_tone_enabled && !is_valid_for_transform_in(&buffer_keys, self.orth()) {
             return None;
         }
 
@@ -1294,7 +2172,7 @@ impl Engine {
                 // 2. No consonants between target and end (delayed diphthong: "oio" → "ôi")
                 // This prevents transformation in words like "teacher" where consonants
                 // (c, h) appear between the two 'e's
-                let is_telex_circumflex = self.method == 0
+                let is_telex_circumflex = self.input.vowel_doubling()
                     && tone_type == ToneType::Circumflex
                     && matches!(key, keys::A | keys::E | keys::O);
 
@@ -1815,7 +2693,7 @@ impl Engine {
         // Telex: Check for delayed stroke pattern (d + vowels + d)
         // When buffer is "dod" and mark key is typed, apply stroke to initial 'd'
         // This enables "dods" → "đó" while preventing "de" + "d" → "đe"
-        let had_delayed_stroke = self.method == 0
+        let had_delayed_stroke = self.input.stroke_style(keys::D) == StrokeStyle::Delayed
             && self.buf.len() >= 2
             && self
                 .buf
@@ -1833,7 +2711,7 @@ impl Engine {
                 has_vowel && {
                     let buffer_without_last: Vec<u16> =
                         self.buf.iter().take(buf_len - 1).map(|c| c.key).collect();
-                    is_valid(&buffer_without_last) && {
+                    is_valid_in(&buffer_without_last, self.orth()) && {
                         // Apply delayed stroke: stroke initial 'd', remove trigger 'd'
                         if let Some(c) = self.buf.get_mut(0) {
                             c.stroke = true;
@@ -1876,7 +2754,7 @@ impl Engine {
         // This enables "totos" → "tốt" while preventing "data" → "dât"
         // Pattern: C₁ + V + C₂ + V where V is same vowel (a, e, o)
         let mut had_delayed_circumflex = false;
-        if self.method == 0 && self.buf.len() >= 3 {
+        if self.input.vowel_doubling() && self.buf.len() >= 3 {
             // Get vowel positions
             let vowel_positions: Vec<(usize, u16)> = self
                 .buf
@@ -1981,7 +2859,7 @@ impl Engine {
         if !self.free_tone_enabled
             && !has_horn_transforms
             && !has_stroke_transforms
-            && !is_valid_for_transform(&buffer_keys)
+            && !is_valid_for_transform_in(&buffer_keys, self.orth())
         {
             return None;
         }
@@ -2002,7 +2880,7 @@ impl Engine {
         if !self.free_tone_enabled
             && !has_horn_transforms
             && !has_stroke_transforms
-            && is_foreign_word_pattern(&buffer_keys, &buffer_tones, key)
+            && is_foreign_word_pattern_in(&buffer_keys, &buffer_tones, key, self.orth())
         {
             return None;
         }
@@ -2407,7 +3285,10 @@ impl Engine {
         // Pattern: After "data" → "dât" (delayed circumflex), typing 'a' again should revert to "data"
         // Buffer ends with: vowel-with-circumflex + non-extending-final (t, m, p)
         // Typed key matches the base of the circumflex vowel (a→â, e→ê, o→ô)
-        if self.method == 0 && matches!(key, keys::A | keys::E | keys::O) && self.buf.len() >= 2 {
+        if self.input.vowel_doubling()
+            && matches!(key, keys::A | keys::E | keys::O)
+            && self.buf.len() >= 2
+        {
             let last_idx = self.buf.len() - 1;
             let vowel_idx = self.buf.len() - 2;
 
@@ -2450,7 +3331,10 @@ impl Engine {
         // The second vowel triggers circumflex on the first vowel (keeping existing mark)
         // IMPORTANT: Must have initial consonant to form valid Vietnamese syllable
         // "expect" (e-x-p-e) should NOT trigger because no initial consonant
-        if self.method == 0 && matches!(key, keys::A | keys::E | keys::O) && self.buf.len() >= 3 {
+        if self.input.vowel_doubling()
+            && matches!(key, keys::A | keys::E | keys::O)
+            && self.buf.len() >= 3
+        {
             let last_idx = self.buf.len() - 1;
             let vowel_idx = self.buf.len() - 2;
 
@@ -2491,9 +3375,9 @@ impl Engine {
         }
 
         self.last_transform = None;
-        // Add letters to buffer, and numbers in VNI mode (for pass-through after revert)
+        // Add letters to buffer, and numbers that are modifiers (VNI) for pass-through after revert
         // This ensures buffer.len() stays in sync with screen chars for correct backspace count
-        if keys::is_letter(key) || (self.method == 1 && keys::is_number(key)) {
+        if keys::is_letter(key) || (keys::is_number(key) && self.input.is_modifier(key, false)) {
             // Add the letter/number to buffer
             self.buf.push(Char::new(key, caps));
 
@@ -2611,7 +3495,7 @@ impl Engine {
                 if self.has_w_as_vowel_transform() && !is_valid_triphthong_ending {
                     let buffer_keys: Vec<u16> = self.buf.iter().map(|c| c.key).collect();
                     let buffer_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
-                    if is_foreign_word_pattern(&buffer_keys, &buffer_tones, key) {
+                    if is_foreign_word_pattern_in(&buffer_keys, &buffer_tones, key, self.orth()) {
                         return self.revert_w_as_vowel_transforms();
                     }
                 }
@@ -2631,8 +3515,7 @@ impl Engine {
             // not true consonants. User typing "đườ" + 's' wants to add sắc mark, not restore.
             //
             // Only run if english_auto_restore is enabled (experimental feature)
-            let im = input::get(self.method);
-            let is_mark_key = im.mark(key).is_some();
+            let is_mark_key = self.input.mark(key).is_some();
             if self.english_auto_restore
                 && keys::is_consonant(key)
                 && !is_mark_key
@@ -2646,6 +3529,10 @@ impl Engine {
                         // Clear English pattern detected - restore to raw
                         if let Some(raw_chars) = self.build_raw_chars() {
                             let backspace = (self.buf.len() - 1) as u8;
+                            self.emit(|e| Event::AutoRestore {
+                                from: e.buf.to_full_string(),
+                                to: raw_chars.iter().collect(),
+                            });
 
                             // Repopulate buffer with restored content (plain chars, no marks)
                             self.buf.clear();
@@ -2749,14 +3636,7 @@ impl Engine {
         for i in from..self.buf.len() {
             if let Some(c) = self.buf.get(i) {
                 backspace += 1;
-
-                if c.key == keys::D && c.stroke {
-                    output.push(chars::get_d(c.caps));
-                } else if let Some(ch) = chars::to_char(c.key, c.caps, c.tone, c.mark) {
-                    output.push(ch);
-                } else if let Some(ch) = utils::key_to_char(c.key, c.caps) {
-                    output.push(ch);
-                }
+                c.push_text(&mut output);
             }
         }
 
@@ -2784,13 +3664,7 @@ impl Engine {
 
         for i in from..self.buf.len() {
             if let Some(c) = self.buf.get(i) {
-                if c.key == keys::D && c.stroke {
-                    output.push(chars::get_d(c.caps));
-                } else if let Some(ch) = chars::to_char(c.key, c.caps, c.tone, c.mark) {
-                    output.push(ch);
-                } else if let Some(ch) = utils::key_to_char(c.key, c.caps) {
-                    output.push(ch);
-                }
+                c.push_text(&mut output);
             }
         }
 
@@ -2834,6 +3708,7 @@ impl Engine {
         self.clear();
         self.word_history.clear();
         self.spaces_after_commit = 0;
+        self.screen.clear();
     }
 
     /// Get the full composed buffer as a Vietnamese string with diacritics.
@@ -2843,6 +3718,12 @@ impl Engine {
         self.buf.to_full_string()
     }
 
+    /// Get the buffer as the host shows it: encoded in the output charset
+    /// (decomposed for NFD, bytes for legacy charsets).
+    pub fn get_buffer_output(&self) -> String {
+        charset::encode(&self.get_buffer_string(), self.charset)
+    }
+
     /// Debug: Check if vowel-triggered circumflex flag is set
     pub fn had_vowel_circumflex(&self) -> bool {
         self.had_vowel_triggered_circumflex
@@ -2862,9 +3743,33 @@ impl Engine {
     ///
     /// Used when native app detects cursor at word boundary and wants to edit.
     /// Parses Vietnamese characters back to buffer components.
+    /// Combining marks after a letter join it (ơ̆, ĕ́). With a legacy
+    /// output charset, `word` is text in that charset.
     pub fn restore_word(&mut self, word: &str) {
         self.clear();
+        // Compose decomposed (NFD) letters: the loop below only joins marks
+        // that have no precomposed form
+        let word = chars::compose(&charset::decode(word, self.charset));
+        if self.multi_unit_letters() {
+            self.screen.clear();
+            self.push_screen(&word.chars().collect::<Vec<_>>());
+        }
         for c in word.chars() {
+            if let Some(last) = self
+                .buf
+                .len()
+                .checked_sub(1)
+                .and_then(|i| self.buf.get_mut(i))
+            {
+                if c == chars::COMBINING_BREVE && last.key == keys::O && last.tone == tone::HORN {
+                    last.tone = tone::HORN_SHORT;
+                    continue;
+                }
+                if let Some(m) = chars::combining_mark(c).filter(|_| keys::is_vowel(last.key)) {
+                    last.mark = m;
+                    continue;
+                }
+            }
             if let Some(parsed) = chars::parse_char(c) {
                 let mut ch = Char::new(parsed.key, parsed.caps);
                 ch.tone = parsed.tone;
@@ -3029,7 +3934,7 @@ impl Engine {
             return false;
         }
 
-        let (last_key, _, _) = self.raw_input[self.raw_input.len() - 1];
+        let (last_key, _, last_shift) = self.raw_input[self.raw_input.len() - 1];
         let (second_last_key, _, _) = self.raw_input[self.raw_input.len() - 2];
 
         // Must be same key pressed twice
@@ -3037,32 +3942,15 @@ impl Engine {
             return false;
         }
 
-        // Check if it's a vowel tone key (Telex: a, e, o for circumflex; w for horn/breve)
-        // These are always intentional reverts - no English words use double vowels like this
-        if self.method == 0 {
-            if matches!(last_key, keys::A | keys::E | keys::O | keys::W) {
-                return true;
-            }
-        } else {
-            // VNI: 6, 7, 8 for vowel tones
-            if matches!(last_key, keys::N6 | keys::N7 | keys::N8) {
-                return true;
-            }
+        // Check if it's a vowel tone key (Telex: a, e, o for circumflex; w for horn/breve;
+        // VNI: 6, 7, 8). These are always intentional reverts - no English words use
+        // double vowels like this
+        if self.input.tone_ext(last_key, last_shift).is_some() {
+            return true;
         }
 
-        // Check if it's a mark key
-        let is_mark_key = if self.method == 0 {
-            // Telex tone modifiers: s, f, r, x, j
-            matches!(last_key, keys::S | keys::F | keys::R | keys::X | keys::J)
-        } else {
-            // VNI tone modifiers: 1, 2, 3, 4, 5
-            matches!(
-                last_key,
-                keys::N1 | keys::N2 | keys::N3 | keys::N4 | keys::N5
-            )
-        };
-
-        if !is_mark_key {
+        // Check if it's a mark key (Telex: s, f, r, x, j; VNI: 1, 2, 3, 4, 5)
+        if self.input.mark_ext(last_key, last_shift).is_none() {
             return false;
         }
 
@@ -3082,13 +3970,7 @@ impl Engine {
         // For longer words (5+ chars), check modifier type:
         // - 'x', 'j' (Telex) or VNI numbers: not common doubles in English → keep
         // - 's', 'f', 'r' (Telex): very common doubles in English (bass, staff, error) → restore
-        if self.method == 0 {
-            // Telex: only keep for uncommon double letters (x, j)
-            matches!(last_key, keys::X | keys::J)
-        } else {
-            // VNI: number modifiers are always intentional → keep
-            true
-        }
+        !keys::is_letter(last_key) || matches!(last_key, keys::X | keys::J)
     }
 
     /// Check if buffer is NOT valid Vietnamese (for unified auto-restore logic)
@@ -3105,7 +3987,7 @@ impl Engine {
         let buffer_tones: Vec<u8> = self.buf.iter().map(|c| c.tone).collect();
 
         // Use full validation with tone info for accurate Vietnamese checking
-        !validation::is_valid_with_tones(&buffer_keys, &buffer_tones)
+        !is_valid_with_tones_in(&buffer_keys, &buffer_tones, self.orth())
     }
 
     /// Check if raw_input is valid English (for unified auto-restore logic)
@@ -3231,7 +4113,7 @@ impl Engine {
             // Example: "tafoo" = t + a + f + o + o → restore to "tàoo"
             // - Keep the tone on first vowel (from 'f' = huyền)
             // - Keep double vowel at end (not collapsed to circumflex)
-            if chars.len() == 5 && self.method == 0 {
+            if chars.len() == 5 && self.input.vowel_doubling() {
                 // Telex only
                 let c0 = chars[0].to_ascii_lowercase();
                 let c1 = chars[1].to_ascii_lowercase();
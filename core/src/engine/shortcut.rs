@@ -3,14 +3,8 @@
 //! Allows users to define shortcuts like "vn" → "Việt Nam"
 //! Shortcuts can be specific to input methods (Telex/VNI) or apply to all.
 
-use super::buffer::MAX;
 use std::collections::HashMap;
 
-/// Maximum replacement length in UTF-32 codepoints (matches Result.chars array size)
-/// This limit ensures replacement fits in the FFI result buffer.
-/// Note: Vietnamese characters with diacritics (ồ, ế, ẫ) count as 1 codepoint each.
-pub const MAX_REPLACEMENT_LEN: usize = MAX - 1; // -1 to leave room for trailing space
-
 /// Input method that shortcut applies to
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum InputMethod {
@@ -21,6 +15,8 @@ pub enum InputMethod {
     Telex,
     /// Apply only to VNI
     Vni,
+    /// Apply only to methods that are neither (VIQR, direct)
+    Other,
 }
 
 /// Trigger condition for shortcut
@@ -59,25 +55,12 @@ pub struct Shortcut {
 }
 
 impl Shortcut {
-    /// Validate and truncate replacement if it exceeds MAX_REPLACEMENT_LEN.
-    /// Counts UTF-32 codepoints (Vietnamese diacritics = 1 codepoint each).
-    fn validate_replacement(replacement: &str) -> String {
-        let char_count = replacement.chars().count();
-        if char_count <= MAX_REPLACEMENT_LEN {
-            replacement.to_string()
-        } else {
-            // Truncate to MAX_REPLACEMENT_LEN codepoints
-            replacement.chars().take(MAX_REPLACEMENT_LEN).collect()
-        }
-    }
-
     /// Create a new shortcut with word boundary trigger (applies to all input methods)
     /// Issue #86: Case-insensitive matching, smart case output (ko→không, KO→KHÔNG, Ko→Không)
-    /// Replacement is truncated to MAX_REPLACEMENT_LEN (63) codepoints if too long.
     pub fn new(trigger: &str, replacement: &str) -> Self {
         Self {
             trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
-            replacement: Self::validate_replacement(replacement),
+            replacement: replacement.to_string(),
             condition: TriggerCondition::OnWordBoundary,
             case_mode: CaseMode::MatchCase, // Smart case transformation
             enabled: true,
@@ -87,11 +70,10 @@ impl Shortcut {
 
     /// Create an immediate trigger shortcut (applies to all input methods).
     /// Issue #86: Case-insensitive matching, smart case output
-    /// Replacement is truncated to MAX_REPLACEMENT_LEN (63) codepoints if too long.
     pub fn immediate(trigger: &str, replacement: &str) -> Self {
         Self {
             trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
-            replacement: Self::validate_replacement(replacement),
+            replacement: replacement.to_string(),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
             enabled: true,
@@ -101,11 +83,10 @@ impl Shortcut {
 
     /// Create a Telex-specific shortcut with immediate trigger.
     /// Issue #86: Case-insensitive matching, smart case output
-    /// Replacement is truncated to MAX_REPLACEMENT_LEN (63) codepoints if too long.
     pub fn telex(trigger: &str, replacement: &str) -> Self {
         Self {
             trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
-            replacement: Self::validate_replacement(replacement),
+            replacement: replacement.to_string(),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
             enabled: true,
@@ -115,11 +96,10 @@ impl Shortcut {
 
     /// Create a VNI-specific shortcut with immediate trigger.
     /// Issue #86: Case-insensitive matching, smart case output
-    /// Replacement is truncated to MAX_REPLACEMENT_LEN (63) codepoints if too long.
     pub fn vni(trigger: &str, replacement: &str) -> Self {
         Self {
             trigger: trigger.to_lowercase(), // Store lowercase for case-insensitive matching
-            replacement: Self::validate_replacement(replacement),
+            replacement: replacement.to_string(),
             condition: TriggerCondition::Immediate,
             case_mode: CaseMode::MatchCase, // Smart case transformation
             enabled: true,
@@ -138,18 +118,13 @@ impl Shortcut {
     /// - If shortcut is for `All`: matches any method
     /// - If shortcut is for `Telex`: matches `Telex` or `All` query
     /// - If shortcut is for `Vni`: matches `Vni` or `All` query
+    /// - If shortcut is for `Other`: matches `Other` or `All` query
     pub fn applies_to(&self, query_method: InputMethod) -> bool {
-        match self.input_method {
-            // Shortcut for All → matches any query
-            InputMethod::All => true,
-            // Shortcut for specific method → matches if query is same method OR query is All
-            InputMethod::Telex => {
-                query_method == InputMethod::Telex || query_method == InputMethod::All
-            }
-            InputMethod::Vni => {
-                query_method == InputMethod::Vni || query_method == InputMethod::All
-            }
-        }
+        // Shortcut for All → matches any query
+        // Shortcut for specific method → matches if query is same method OR query is All
+        self.input_method == InputMethod::All
+            || query_method == InputMethod::All
+            || query_method == self.input_method
     }
 }
 
@@ -577,6 +552,9 @@ mod tests {
         // Should NOT match for VNI
         assert_no_match(&table, "w", None, false, InputMethod::Vni);
 
+        // Should NOT match for VIQR/direct
+        assert_no_match(&table, "w", None, false, InputMethod::Other);
+
         // Should match for All (fallback)
         assert_shortcut_match(&table, "w", None, false, "ư", 1, InputMethod::All);
     }
@@ -682,21 +660,14 @@ mod tests {
     }
 
     #[test]
-    fn test_replacement_validation_truncation() {
-        // Create a very long replacement (100 characters with Vietnamese)
+    fn test_replacement_not_truncated() {
+        // Long replacements (signatures, boilerplate) are kept in full;
+        // the engine delivers them in chunks (FLAG_MORE_OUTPUT)
         let long_text = "Đây là một đoạn văn bản rất dài để kiểm tra việc cắt ngắn. Nó có nhiều ký tự tiếng Việt có dấu như ồ, ế, ẫ, ơ, ư.";
-        let char_count = long_text.chars().count();
-        assert!(
-            char_count > MAX_REPLACEMENT_LEN,
-            "Test text should exceed limit"
-        );
+        assert!(long_text.chars().count() > 64);
 
         let shortcut = Shortcut::new("long", long_text);
-        let result_count = shortcut.replacement.chars().count();
-        assert_eq!(
-            result_count, MAX_REPLACEMENT_LEN,
-            "Should truncate to MAX_REPLACEMENT_LEN"
-        );
+        assert_eq!(shortcut.replacement, long_text);
     }
 
     #[test]
@@ -58,6 +58,11 @@ const FINALS_2: &[[u16; 2]] = &[
 /// Note: This parser is lenient - it will parse invalid initials
 /// and let validation reject them later.
 pub fn parse(buffer_keys: &[u16]) -> Syllable {
+    parse_with_finals(buffer_keys, &[])
+}
+
+/// `parse` also matching `extra_finals` as single finals (orthography profiles)
+pub fn parse_with_finals(buffer_keys: &[u16], extra_finals: &[u16]) -> Syllable {
     let mut syllable = Syllable::default();
     let len = buffer_keys.len();
 
@@ -152,14 +157,14 @@ pub fn parse(buffer_keys: &[u16]) -> Syllable {
 
     // Step 3: Match final consonant
     if vowel_end < len {
-        match_final(buffer_keys, vowel_end, &mut syllable);
+        match_final(buffer_keys, vowel_end, extra_finals, &mut syllable);
     }
 
     syllable
 }
 
 /// Match final consonant
-fn match_final(keys: &[u16], start: usize, syllable: &mut Syllable) {
+fn match_final(keys: &[u16], start: usize, extra_finals: &[u16], syllable: &mut Syllable) {
     let len = keys.len();
     let remaining = len - start;
 
@@ -174,7 +179,9 @@ fn match_final(keys: &[u16], start: usize, syllable: &mut Syllable) {
     }
 
     // Try 1-char finals
-    if remaining >= 1 && constants::VALID_FINALS_1.contains(&keys[start]) {
+    if remaining >= 1
+        && (constants::VALID_FINALS_1.contains(&keys[start]) || extra_finals.contains(&keys[start]))
+    {
         syllable.final_c = vec![start];
     }
 }
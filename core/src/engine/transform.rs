@@ -53,119 +53,6 @@ impl TransformResult {
     }
 }
 
-/// Apply tone diacritic transformation (^, ơ, ư, ă)
-///
-/// Pattern-based: scans buffer for matching vowels
-pub fn apply_tone(buf: &mut Buffer, key: u16, tone_value: u8, method: u8) -> TransformResult {
-    // Find target vowels based on key and method
-    let targets = find_tone_targets(buf, key, tone_value, method);
-
-    if targets.is_empty() {
-        return TransformResult::none();
-    }
-
-    // Apply tone to targets
-    let mut positions = vec![];
-    for pos in &targets {
-        if let Some(c) = buf.get_mut(*pos) {
-            if c.tone == tone::NONE {
-                c.tone = tone_value;
-                positions.push(*pos);
-            }
-        }
-    }
-
-    if positions.is_empty() {
-        TransformResult::none()
-    } else {
-        // After adding tone, reposition mark if needed
-        reposition_mark_if_needed(buf);
-        TransformResult::success(positions)
-    }
-}
-
-/// Find which vowel positions should receive the tone modifier
-fn find_tone_targets(buf: &Buffer, key: u16, tone_value: u8, method: u8) -> Vec<usize> {
-    let mut targets = vec![];
-
-    // Find all vowel positions
-    let vowel_positions: Vec<usize> = buf
-        .iter()
-        .enumerate()
-        .filter(|(_, c)| keys::is_vowel(c.key))
-        .map(|(i, _)| i)
-        .collect();
-
-    if vowel_positions.is_empty() {
-        return targets;
-    }
-
-    // Telex patterns
-    if method == 0 {
-        // aa, ee, oo → circumflex (immediate doubling only)
-        // The target vowel must be at the LAST position in the buffer
-        // This ensures "ee" doubling only works for consecutive presses,
-        // not for words like "teacher" where 'e' appears twice non-adjacently
-        if tone_value == tone::CIRCUMFLEX && matches!(key, keys::A | keys::E | keys::O) {
-            // Issue #312: If ANY vowel in the buffer already has a tone (horn/circumflex/breve),
-            // don't trigger same-vowel circumflex. The typed vowel should append as raw letter.
-            // Example: "chưa" + "a" → "chưaa" (NOT "chưâ")
-            let any_vowel_has_tone = buf
-                .iter()
-                .filter(|c| keys::is_vowel(c.key))
-                .any(|c| c.has_tone());
-
-            if any_vowel_has_tone {
-                // Skip circumflex, return empty targets to append raw vowel
-                return targets;
-            }
-
-            // Find matching vowel (same key) - must be at last position
-            let last_pos = buf.len().saturating_sub(1);
-            for &pos in vowel_positions.iter().rev() {
-                if buf.get(pos).map(|c| c.key) == Some(key) && pos == last_pos {
-                    targets.push(pos);
-                    break;
-                }
-            }
-        }
-        // w → horn/breve
-        else if tone_value == tone::HORN && key == keys::W {
-            let buffer_keys: Vec<u16> = buf.iter().map(|c| c.key).collect();
-            targets = Phonology::find_horn_positions(&buffer_keys, &vowel_positions);
-        }
-    }
-    // VNI patterns
-    else {
-        let buffer_keys: Vec<u16> = buf.iter().map(|c| c.key).collect();
-
-        // 6 → circumflex for a, e, o
-        if tone_value == tone::CIRCUMFLEX && key == keys::N6 {
-            for &pos in vowel_positions.iter().rev() {
-                if matches!(buffer_keys[pos], keys::A | keys::E | keys::O) {
-                    targets.push(pos);
-                    break;
-                }
-            }
-        }
-        // 7 → horn for o, u
-        else if tone_value == tone::HORN && key == keys::N7 {
-            targets = Phonology::find_horn_positions(&buffer_keys, &vowel_positions);
-        }
-        // 8 → breve for a only
-        else if tone_value == tone::HORN && key == keys::N8 {
-            for &pos in vowel_positions.iter().rev() {
-                if buffer_keys[pos] == keys::A {
-                    targets.push(pos);
-                    break;
-                }
-            }
-        }
-    }
-
-    targets
-}
-
 /// Apply mark transformation (sắc, huyền, hỏi, ngã, nặng)
 pub fn apply_mark(buf: &mut Buffer, mark_value: u8, modern: bool) -> TransformResult {
     let vowels = utils::collect_vowels(buf);
@@ -285,40 +172,6 @@ pub fn revert_stroke(buf: &mut Buffer) -> TransformResult {
     TransformResult::none()
 }
 
-/// Reposition mark after tone change if needed
-fn reposition_mark_if_needed(buf: &mut Buffer) {
-    // Find current mark
-    let mark_info: Option<(usize, u8)> = buf
-        .iter()
-        .enumerate()
-        .find(|(_, c)| c.mark > 0)
-        .map(|(i, c)| (i, c.mark));
-
-    if let Some((old_pos, mark_value)) = mark_info {
-        let vowels = utils::collect_vowels(buf);
-        if vowels.is_empty() {
-            return;
-        }
-
-        let last_vowel_pos = vowels.last().map(|v| v.pos).unwrap_or(0);
-        let has_final = utils::has_final_consonant(buf, last_vowel_pos);
-        let has_qu = utils::has_qu_initial(buf);
-        let has_gi = utils::has_gi_initial(buf);
-        let new_pos = Phonology::find_tone_position(&vowels, has_final, true, has_qu, has_gi);
-
-        if new_pos != old_pos {
-            // Clear old mark
-            if let Some(c) = buf.get_mut(old_pos) {
-                c.mark = 0;
-            }
-            // Set new mark
-            if let Some(c) = buf.get_mut(new_pos) {
-                c.mark = mark_value;
-            }
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::super::buffer::Char;
@@ -370,14 +223,4 @@ mod tests {
         assert!(result.applied);
         assert_eq!(buf.get(0).unwrap().mark, mark::SAC);
     }
-
-    #[test]
-    fn test_uo_compound() {
-        let mut buf = setup_buffer("duoc");
-        let result = apply_tone(&mut buf, keys::W, tone::HORN, 0);
-        assert!(result.applied);
-        // Both u and o should have horn
-        assert_eq!(buf.get(1).unwrap().tone, tone::HORN); // u
-        assert_eq!(buf.get(2).unwrap().tone, tone::HORN); // o
-    }
 }
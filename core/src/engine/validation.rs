@@ -3,10 +3,11 @@
 //! Whitelist-based validation for Vietnamese syllables.
 //! Uses valid patterns from docs/vietnamese-language-system.md Section 7.6.1
 
-use super::syllable::{parse, Syllable};
+use super::syllable::{parse_with_finals, Syllable};
 use crate::data::chars::tone;
 use crate::data::constants;
 use crate::data::keys;
+use crate::data::orthography::{self, Orthography};
 
 /// Validation result
 #[derive(Debug, Clone, PartialEq)]
@@ -37,6 +38,8 @@ pub struct BufferSnapshot {
     /// True when tones were explicitly provided (validate modifier requirements)
     /// False when created from keys-only (legacy, skip modifier checks)
     pub has_tone_info: bool,
+    /// Profile adding finals (highlands h/l/r/s)
+    pub orthography: &'static Orthography,
 }
 
 impl BufferSnapshot {
@@ -48,6 +51,7 @@ impl BufferSnapshot {
             keys,
             tones: vec![0; len],
             has_tone_info: false,
+            orthography: &orthography::STANDARD,
         }
     }
 }
@@ -140,7 +144,10 @@ fn rule_valid_final(snap: &BufferSnapshot, syllable: &Syllable) -> Option<Valida
     let final_c: Vec<u16> = syllable.final_c.iter().map(|&i| snap.keys[i]).collect();
 
     let is_valid = match final_c.len() {
-        1 => constants::VALID_FINALS_1.contains(&final_c[0]),
+        1 => {
+            constants::VALID_FINALS_1.contains(&final_c[0])
+                || snap.orthography.finals.contains(&final_c[0])
+        }
         2 => constants::VALID_FINALS_2
             .iter()
             .any(|p| p[0] == final_c[0] && p[1] == final_c[1]),
@@ -264,7 +271,7 @@ pub fn validate(snap: &BufferSnapshot) -> ValidationResult {
         return ValidationResult::NoVowel;
     }
 
-    let syllable = parse(&snap.keys);
+    let syllable = parse_with_finals(&snap.keys, snap.orthography.finals);
 
     for rule in RULES {
         if let Some(error) = rule(snap, &syllable) {
@@ -278,10 +285,16 @@ pub fn validate(snap: &BufferSnapshot) -> ValidationResult {
 /// Quick check if buffer could be valid Vietnamese (with modifier info)
 /// This will fully validate modifier requirements (e.g., E+U requires circumflex)
 pub fn is_valid_with_tones(keys: &[u16], tones: &[u8]) -> bool {
+    is_valid_with_tones_in(keys, tones, &orthography::STANDARD)
+}
+
+/// `is_valid_with_tones` for an orthography profile
+pub fn is_valid_with_tones_in(keys: &[u16], tones: &[u8], orth: &'static Orthography) -> bool {
     let snap = BufferSnapshot {
         keys: keys.to_vec(),
         tones: tones.to_vec(),
         has_tone_info: true, // Enforce modifier requirements
+        orthography: orth,
     };
     validate(&snap).is_valid()
 }
@@ -291,7 +304,13 @@ pub fn is_valid_with_tones(keys: &[u16], tones: &[u8]) -> bool {
 /// NOTE: This cannot fully validate modifier requirements.
 /// Use is_valid_with_tones() for complete validation.
 pub fn is_valid(buffer_keys: &[u16]) -> bool {
-    let snap = BufferSnapshot::from_keys(buffer_keys.to_vec());
+    is_valid_in(buffer_keys, &orthography::STANDARD)
+}
+
+/// `is_valid` for an orthography profile
+pub fn is_valid_in(buffer_keys: &[u16], orth: &'static Orthography) -> bool {
+    let mut snap = BufferSnapshot::from_keys(buffer_keys.to_vec());
+    snap.orthography = orth;
     validate(&snap).is_valid()
 }
 
@@ -312,12 +331,18 @@ const RULES_FOR_TRANSFORM: &[Rule] = &[
 /// Used by try_tone/try_stroke to validate buffer structure before transformation.
 /// Does NOT check vowel patterns since intermediate states like "aa" → "â" are valid.
 pub fn is_valid_for_transform(buffer_keys: &[u16]) -> bool {
+    is_valid_for_transform_in(buffer_keys, &orthography::STANDARD)
+}
+
+/// `is_valid_for_transform` for an orthography profile
+pub fn is_valid_for_transform_in(buffer_keys: &[u16], orth: &'static Orthography) -> bool {
     if buffer_keys.is_empty() {
         return false;
     }
 
-    let snap = BufferSnapshot::from_keys(buffer_keys.to_vec());
-    let syllable = parse(&snap.keys);
+    let mut snap = BufferSnapshot::from_keys(buffer_keys.to_vec());
+    snap.orthography = orth;
+    let syllable = parse_with_finals(&snap.keys, snap.orthography.finals);
 
     for rule in RULES_FOR_TRANSFORM {
         if rule(&snap, &syllable).is_some() {
@@ -341,11 +366,26 @@ pub fn is_valid_for_transform(buffer_keys: &[u16]) -> bool {
 ///
 /// Returns true if the pattern suggests foreign word input.
 pub fn is_foreign_word_pattern(
+    buffer_keys: &[u16],
+    buffer_tones: &[u8],
+    modifier_key: u16,
+) -> bool {
+    is_foreign_word_pattern_in(
+        buffer_keys,
+        buffer_tones,
+        modifier_key,
+        &orthography::STANDARD,
+    )
+}
+
+/// `is_foreign_word_pattern` for an orthography profile (its finals aren't foreign)
+pub fn is_foreign_word_pattern_in(
     buffer_keys: &[u16],
     _buffer_tones: &[u8],
     modifier_key: u16,
+    orth: &Orthography,
 ) -> bool {
-    let syllable = parse(buffer_keys);
+    let syllable = parse_with_finals(buffer_keys, orth.finals);
 
     // Check 1: Invalid vowel patterns (not in whitelist)
     if syllable.vowel.len() >= 2 {
@@ -425,6 +465,7 @@ pub fn is_foreign_word_pattern(
                 // Invalid single finals: X, B, D, G, H, K, L, Q, R, S, V
                 let f = finals[0];
                 !matches!(f, keys::C | keys::M | keys::N | keys::P | keys::T)
+                    && !orth.finals.contains(&f)
             }
             2 => {
                 // Valid double finals: CH, NG, NH
@@ -561,6 +602,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_highlands_finals() {
+        let highlands = &orthography::HIGHLANDS;
+        for w in ["bal", "mah", "nar", "buos"] {
+            let keys = keys_from_str(w);
+            assert!(!is_valid(&keys), "'{}' should be invalid Vietnamese", w);
+            assert!(is_valid_in(&keys, highlands), "'{}' should be valid", w);
+        }
+        assert!(!is_valid_in(&keys_from_str("bax"), highlands));
+        assert!(!is_foreign_word_pattern_in(
+            &keys_from_str("al"),
+            &[0, 0],
+            keys::S,
+            highlands
+        ));
+        assert!(!is_valid_for_transform(&keys_from_str("al")));
+        assert!(is_valid_for_transform_in(&keys_from_str("al"), highlands));
+    }
+
     #[test]
     fn test_breve_followed_by_vowel_invalid() {
         // Issue #44: "taiw" → "tăi" should be invalid
@@ -0,0 +1,386 @@
+//! Key Bindings
+//!
+//! Moves modifier functions of the selected method to other keys, or turns
+//! them off, so e.g. programmers can keep `f` and `j` as plain letters:
+//!
+//! ```text
+//! huyen:q nang:; remove:
+//! ```
+//!
+//! Entries are `function:key`, space-separated; an empty key disables the
+//! function. Functions: `sac huyen hoi nga nang circumflex horn breve
+//! stroke remove`. Keys are written as the character they type (`q`, `;`,
+//! `~`); stroke and remove take unshifted keys only. Functions not listed
+//! keep the method's own keys.
+
+use super::table::{matches, parse_key, Key};
+use super::{
+    DirectLetter, Method, SharedMethod, StrokeStyle, ToneType, BREVE_TARGETS, CIRCUMFLEX_TARGETS,
+    HORN_TARGETS_VNI,
+};
+use crate::data::keys;
+use crate::utils;
+
+/// A modifier function of an input method
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Function {
+    /// 1=sắc, 2=huyền, 3=hỏi, 4=ngã, 5=nặng
+    Mark(u8),
+    Tone(ToneType),
+    Stroke,
+    Remove,
+}
+
+/// Function names used in binding text
+const FUNCTIONS: [(&str, Function); 10] = [
+    ("sac", Function::Mark(1)),
+    ("huyen", Function::Mark(2)),
+    ("hoi", Function::Mark(3)),
+    ("nga", Function::Mark(4)),
+    ("nang", Function::Mark(5)),
+    ("circumflex", Function::Tone(ToneType::Circumflex)),
+    ("horn", Function::Tone(ToneType::Horn)),
+    ("breve", Function::Tone(ToneType::Breve)),
+    ("stroke", Function::Stroke),
+    ("remove", Function::Remove),
+];
+
+/// Keycodes scanned for a method's own modifier keys
+const KEYCODES: std::ops::Range<u16> = 0..128;
+
+/// Function of a key in a method (None if the key isn't a modifier)
+pub fn function_of(m: &dyn Method, key: u16, shift: bool) -> Option<Function> {
+    if let Some(mark) = m.mark_ext(key, shift) {
+        Some(Function::Mark(mark))
+    } else if let Some(tone_type) = m.tone_ext(key, shift) {
+        Some(Function::Tone(tone_type))
+    } else if m.stroke(key) {
+        Some(Function::Stroke)
+    } else if m.remove(key) {
+        Some(Function::Remove)
+    } else {
+        None
+    }
+}
+
+/// Rebound functions: each moved to a key, or disabled (None)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyBindings {
+    entries: Vec<(Function, Option<Key>)>,
+}
+
+impl KeyBindings {
+    /// Parse binding text (None on unknown functions, bad keys, a function
+    /// listed twice or two functions on one key)
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut bindings = Self::default();
+        for entry in text.split_whitespace() {
+            let (name, key) = entry.split_once(':')?;
+            let function = FUNCTIONS.iter().find(|(n, _)| *n == name)?.1;
+            let mut chars = key.chars();
+            let key = match chars.next() {
+                Some(c) => Some(parse_key(c)?),
+                None => None,
+            };
+            if chars.next().is_some() {
+                return None;
+            }
+            bindings.bind(function, key)?;
+        }
+        Some(bindings)
+    }
+
+    /// Move `function` to `key` (None disables it)
+    ///
+    /// Returns None if the function is already bound, the key is taken, or
+    /// a shifted key is given for stroke or remove (methods check those
+    /// without Shift).
+    pub fn bind(&mut self, function: Function, key: Option<Key>) -> Option<()> {
+        let shifted = key.is_some_and(|(_, shift)| shift);
+        if shifted && matches!(function, Function::Stroke | Function::Remove) {
+            return None;
+        }
+        let taken = |&(f, k): &(Function, Option<Key>)| {
+            f == function || key.is_some_and(|key| k.is_some_and(|k| same_key(k, key)))
+        };
+        if self.entries.iter().any(taken) {
+            return None;
+        }
+        self.entries.push((function, key));
+        Some(())
+    }
+
+    /// Binding text (`function:key` entries, as accepted by `parse`)
+    pub fn to_text(&self) -> String {
+        self.entries
+            .iter()
+            .map(|&(function, key)| {
+                let name = FUNCTIONS.iter().find(|(_, f)| *f == function).unwrap().0;
+                let key = key.and_then(|(k, shift)| utils::key_to_char_ext(k, false, shift));
+                format!("{}:{}", name, key.map(String::from).unwrap_or_default())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check that no key of `m` that keeps its function is also bound here
+    pub fn fits(&self, m: &dyn Method) -> bool {
+        self.entries.iter().all(|&(_, key)| {
+            let Some((k, shift)) = key else {
+                return true;
+            };
+            match function_of(m, k, shift) {
+                Some(f) => self.entries.iter().any(|&(bound, _)| bound == f),
+                None => true,
+            }
+        })
+    }
+
+    fn rebound(&self, function: Function) -> bool {
+        self.entries.iter().any(|&(f, _)| f == function)
+    }
+
+    fn bound_function(&self, key: u16, shift: bool) -> Option<Function> {
+        self.entries
+            .iter()
+            .find(|&&(_, k)| k.is_some_and(|k| matches(k, key, shift)))
+            .map(|&(f, _)| f)
+    }
+}
+
+/// Letters bind regardless of Shift, so `s` and `S` are the same key
+fn same_key(a: Key, b: Key) -> bool {
+    a.0 == b.0 && (keys::is_letter(a.0) || a.1 == b.1)
+}
+
+/// A method with key bindings applied
+///
+/// Bound keys win over the method's own keys; the method's keys for a
+/// rebound function no longer act.
+pub struct Rebound {
+    base: SharedMethod,
+    bindings: KeyBindings,
+    /// Targets of moved tone keys (all targets the method's keys had)
+    tone_targets: Vec<(ToneType, Vec<u16>)>,
+    /// Stroke style of the method's own stroke key (for a letter moved there)
+    stroke_style: StrokeStyle,
+}
+
+impl Rebound {
+    pub fn new(base: SharedMethod, bindings: KeyBindings) -> Self {
+        let mut tone_targets: Vec<(ToneType, Vec<u16>)> = Vec::new();
+        let mut stroke_style = None;
+        for key in KEYCODES {
+            for shift in [false, true] {
+                match function_of(base.as_ref(), key, shift) {
+                    Some(Function::Tone(tone_type)) => {
+                        let i = match tone_targets.iter().position(|(t, _)| *t == tone_type) {
+                            Some(i) => i,
+                            None => {
+                                tone_targets.push((tone_type, Vec::new()));
+                                tone_targets.len() - 1
+                            }
+                        };
+                        for &target in base.tone_targets(key) {
+                            if !tone_targets[i].1.contains(&target) {
+                                tone_targets[i].1.push(target);
+                            }
+                        }
+                    }
+                    Some(Function::Stroke) => {
+                        stroke_style.get_or_insert(base.stroke_style(key));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        // Tones the method doesn't have get their usual targets
+        for (tone_type, targets) in [
+            (ToneType::Circumflex, CIRCUMFLEX_TARGETS),
+            (ToneType::Horn, HORN_TARGETS_VNI),
+            (ToneType::Breve, BREVE_TARGETS),
+        ] {
+            if !tone_targets.iter().any(|(t, _)| *t == tone_type) {
+                tone_targets.push((tone_type, targets.to_vec()));
+            }
+        }
+        Self {
+            base,
+            bindings,
+            tone_targets,
+            stroke_style: stroke_style.unwrap_or(StrokeStyle::Adjacent),
+        }
+    }
+
+    fn function(&self, key: u16, shift: bool) -> Option<Function> {
+        if let Some(f) = self.bindings.bound_function(key, shift) {
+            return Some(f);
+        }
+        function_of(self.base.as_ref(), key, shift).filter(|&f| !self.bindings.rebound(f))
+    }
+}
+
+impl Method for Rebound {
+    fn mark(&self, key: u16) -> Option<u8> {
+        self.mark_ext(key, false)
+    }
+
+    fn mark_ext(&self, key: u16, shift: bool) -> Option<u8> {
+        match self.function(key, shift) {
+            Some(Function::Mark(mark)) => Some(mark),
+            _ => None,
+        }
+    }
+
+    fn tone(&self, key: u16) -> Option<ToneType> {
+        self.tone_ext(key, false)
+    }
+
+    fn tone_ext(&self, key: u16, shift: bool) -> Option<ToneType> {
+        match self.function(key, shift) {
+            Some(Function::Tone(tone_type)) => Some(tone_type),
+            _ => None,
+        }
+    }
+
+    fn tone_targets(&self, key: u16) -> &[u16] {
+        let bound = [false, true]
+            .into_iter()
+            .find_map(|shift| self.bindings.bound_function(key, shift));
+        match bound {
+            Some(Function::Tone(tone_type)) => self
+                .tone_targets
+                .iter()
+                .find(|(t, _)| *t == tone_type)
+                .map_or(&[], |(_, targets)| targets.as_slice()),
+            Some(_) => &[],
+            None => self.base.tone_targets(key),
+        }
+    }
+
+    fn stroke(&self, key: u16) -> bool {
+        self.function(key, false) == Some(Function::Stroke)
+    }
+
+    fn remove(&self, key: u16) -> bool {
+        self.function(key, false) == Some(Function::Remove)
+    }
+
+    /// aa → â needs the vowels to still be circumflex keys
+    fn vowel_doubling(&self) -> bool {
+        self.base.vowel_doubling() && !self.bindings.rebound(Function::Tone(ToneType::Circumflex))
+    }
+
+    /// w → ư needs `w` to still be the horn key
+    fn w_as_vowel(&self) -> bool {
+        self.base.w_as_vowel() && !self.bindings.rebound(Function::Tone(ToneType::Horn))
+    }
+
+    /// A moved stroke key keeps the method's style when it's a letter;
+    /// any other key can't be typed as text, so it finds `d` anywhere
+    fn stroke_style(&self, key: u16) -> StrokeStyle {
+        if self.bindings.bound_function(key, false) == Some(Function::Stroke) {
+            if keys::is_letter(key) {
+                self.stroke_style
+            } else {
+                StrokeStyle::Anywhere
+            }
+        } else {
+            self.base.stroke_style(key)
+        }
+    }
+
+    /// Bound keys lose the letter they typed
+    fn direct_letter(&self, key: u16) -> Option<DirectLetter> {
+        let bound = [false, true]
+            .into_iter()
+            .any(|shift| self.bindings.bound_function(key, shift).is_some());
+        if bound {
+            None
+        } else {
+            self.base.direct_letter(key)
+        }
+    }
+
+    fn symbol_modifiers(&self) -> bool {
+        self.base.symbol_modifiers()
+            || self
+                .bindings
+                .entries
+                .iter()
+                .any(|&(_, key)| key.is_some_and(|(k, shift)| keys::is_break_ext(k, shift)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{self, id};
+    use std::sync::Arc;
+
+    fn telex_with(text: &str) -> Rebound {
+        Rebound::new(input::shared(id::TELEX), KeyBindings::parse(text).unwrap())
+    }
+
+    #[test]
+    fn test_parse_and_text() {
+        let b = KeyBindings::parse("huyen:q nang: horn:]").unwrap();
+        assert_eq!(b.to_text(), "huyen:q nang: horn:]");
+        assert_eq!(KeyBindings::parse(""), Some(KeyBindings::default()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // Same key twice (letters ignore case), same function twice
+        assert_eq!(KeyBindings::parse("sac:q huyen:q"), None);
+        assert_eq!(KeyBindings::parse("sac:q huyen:Q"), None);
+        assert_eq!(KeyBindings::parse("sac:q sac:w"), None);
+        // Unknown function, bad key, missing colon
+        assert_eq!(KeyBindings::parse("magic:q"), None);
+        assert_eq!(KeyBindings::parse("sac:qq"), None);
+        assert_eq!(KeyBindings::parse("sac"), None);
+        // Stroke and remove can't fire on shifted symbols
+        assert_eq!(KeyBindings::parse("stroke:@"), None);
+        assert_eq!(KeyBindings::parse("remove:~"), None);
+        assert!(KeyBindings::parse("stroke:2 remove:` sac:@").is_some());
+    }
+
+    #[test]
+    fn test_moved_and_disabled() {
+        let m = telex_with("huyen:q nang:");
+        assert_eq!(m.mark(keys::Q), Some(2));
+        assert_eq!(m.mark(keys::F), None);
+        assert_eq!(m.mark(keys::J), None);
+        assert_eq!(m.mark(keys::S), Some(1));
+    }
+
+    #[test]
+    fn test_moved_tone_keeps_targets() {
+        let m = telex_with("horn:] circumflex:6");
+        assert_eq!(m.tone(keys::RBRACKET), Some(ToneType::Horn));
+        assert_eq!(m.tone_targets(keys::RBRACKET), &[keys::A, keys::O, keys::U]);
+        assert_eq!(m.tone_targets(keys::N6), &[keys::A, keys::E, keys::O]);
+        assert_eq!(m.tone(keys::W), None);
+        assert!(!m.vowel_doubling());
+        assert!(!m.w_as_vowel());
+        assert!(m.symbol_modifiers());
+    }
+
+    #[test]
+    fn test_fits() {
+        let telex = input::shared(id::TELEX);
+        // f still types huyền
+        assert!(!KeyBindings::parse("sac:f").unwrap().fits(telex.as_ref()));
+        // Swapping is fine
+        assert!(KeyBindings::parse("sac:f huyen:s")
+            .unwrap()
+            .fits(telex.as_ref()));
+        assert!(KeyBindings::parse("sac:q").unwrap().fits(telex.as_ref()));
+        let vni: SharedMethod = Arc::new(input::Vni);
+        assert!(KeyBindings::parse("sac:f").unwrap().fits(vni.as_ref()));
+    }
+}
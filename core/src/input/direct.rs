@@ -0,0 +1,84 @@
+//! Direct Input Method (Vietnamese keyboard layout)
+//!
+//! Emulates the Windows "Vietnamese" layout, where the number row types
+//! letters and marks directly:
+//! - Letters: 1=ă, 2=â, 3=ê, 4=ô, 0=đ, [=ư, ]=ơ
+//! - Marks (typed after the vowel): 5=huyền, 6=hỏi, 7=ngã, 8=sắc, 9=nặng
+//!
+//! Letters take part in validation and tone placement like the Telex ones
+//! ("t4i8" → "tối"); where no letter fits, the key types its own character.
+
+use super::{DirectLetter, Method, ToneType};
+use crate::data::chars::tone;
+use crate::data::keys;
+
+pub struct Direct;
+
+impl Method for Direct {
+    fn mark(&self, key: u16) -> Option<u8> {
+        match key {
+            keys::N8 => Some(1), // sắc
+            keys::N5 => Some(2), // huyền
+            keys::N6 => Some(3), // hỏi
+            keys::N7 => Some(4), // ngã
+            keys::N9 => Some(5), // nặng
+            _ => None,
+        }
+    }
+
+    fn tone(&self, _key: u16) -> Option<ToneType> {
+        None
+    }
+
+    fn tone_targets(&self, _key: u16) -> &[u16] {
+        &[]
+    }
+
+    fn stroke(&self, _key: u16) -> bool {
+        false
+    }
+
+    fn remove(&self, _key: u16) -> bool {
+        false
+    }
+
+    fn direct_letter(&self, key: u16) -> Option<DirectLetter> {
+        match key {
+            keys::N1 => Some(DirectLetter::vowel(keys::A, tone::HORN)), // ă
+            keys::N2 => Some(DirectLetter::vowel(keys::A, tone::CIRCUMFLEX)), // â
+            keys::N3 => Some(DirectLetter::vowel(keys::E, tone::CIRCUMFLEX)), // ê
+            keys::N4 => Some(DirectLetter::vowel(keys::O, tone::CIRCUMFLEX)), // ô
+            keys::N0 => Some(DirectLetter::d()),                        // đ
+            keys::LBRACKET => Some(DirectLetter::vowel(keys::U, tone::HORN)), // ư
+            keys::RBRACKET => Some(DirectLetter::vowel(keys::O, tone::HORN)), // ơ
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks() {
+        let d = Direct;
+        assert_eq!(d.mark(keys::N8), Some(1));
+        assert_eq!(d.mark(keys::N5), Some(2));
+        assert_eq!(d.mark(keys::N9), Some(5));
+        assert_eq!(d.mark(keys::N1), None);
+        assert_eq!(d.mark(keys::S), None);
+    }
+
+    #[test]
+    fn test_letters() {
+        let d = Direct;
+        assert_eq!(
+            d.direct_letter(keys::N2),
+            Some(DirectLetter::vowel(keys::A, tone::CIRCUMFLEX))
+        );
+        assert_eq!(d.direct_letter(keys::N0), Some(DirectLetter::d()));
+        assert_eq!(d.direct_letter(keys::N5), None);
+        assert!(!d.is_modifier(keys::N1, false));
+    }
+}
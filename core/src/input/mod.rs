@@ -2,15 +2,48 @@
 //!
 //! Defines key mappings for Vietnamese input methods.
 //! Engine handles all pattern matching based on buffer scan.
+//! Method-specific engine behaviour (vowel doubling, `w` as ư, where the
+//! stroke key looks for `d`) is exposed as capabilities on `Method`, so
+//! user-defined table methods get it too. Key bindings (`bindings`) wrap
+//! any method to move or disable its modifier keys.
 
+pub mod bindings;
+pub mod direct;
+pub mod table;
 pub mod telex;
+pub mod telex_vni;
+pub mod viqr;
 pub mod vni;
 
+pub use bindings::{KeyBindings, Rebound};
+pub use direct::Direct;
+pub use table::TableMethod;
 pub use telex::Telex;
+pub use telex_vni::TelexVni;
+pub use viqr::Viqr;
 pub use vni::Vni;
 
 use crate::data::chars::tone;
 use crate::data::keys;
+use std::sync::Arc;
+
+/// Method ids (`Engine::set_method`, FFI `ime_method`)
+pub mod id {
+    pub const TELEX: u8 = 0;
+    pub const VNI: u8 = 1;
+    pub const VIQR: u8 = 2;
+    /// User-defined table (`Engine::set_method_table`)
+    pub const CUSTOM: u8 = 3;
+    /// Telex and VNI keys together
+    pub const TELEX_VNI: u8 = 4;
+    /// Vietnamese keyboard layout (number row types ă â ê ô đ and marks)
+    pub const DIRECT: u8 = 5;
+}
+
+/// Whether `method_id` is a known method id
+pub fn is_known(method_id: u8) -> bool {
+    method_id <= id::DIRECT
+}
 
 /// Shared tone target constants
 pub const CIRCUMFLEX_TARGETS: &[u16] = &[keys::A, keys::E, keys::O];
@@ -25,7 +58,7 @@ pub enum ToneType {
     Circumflex,
     /// Horn: ơ, ư (and ă for Telex)
     Horn,
-    /// Breve: ă (VNI only)
+    /// Breve: ă (VNI, VIQR)
     Breve,
 }
 
@@ -39,35 +72,126 @@ impl ToneType {
     }
 }
 
-/// Input method trait - defines key mappings only
-pub trait Method {
+/// Where the stroke key looks for the `d` to turn into đ
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StrokeStyle {
+    /// Only right after it: "dd" → "đ" (VIQR)
+    Adjacent,
+    /// Right after it, or the initial `d` once the syllable is valid:
+    /// "did" → "đi" (Telex)
+    Delayed,
+    /// First `d` anywhere in the word: "di9" → "đi" (VNI)
+    Anywhere,
+}
+
+/// Letter typed by a single key (direct layout: 2 → â, 0 → đ)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectLetter {
+    /// Base letter key
+    pub key: u16,
+    /// Vowel diacritic (`tone::CIRCUMFLEX`, `tone::HORN` for ơ ư ă)
+    pub tone: u8,
+    /// đ
+    pub stroke: bool,
+}
+
+impl DirectLetter {
+    pub const fn vowel(key: u16, tone: u8) -> Self {
+        Self {
+            key,
+            tone,
+            stroke: false,
+        }
+    }
+
+    pub const fn d() -> Self {
+        Self {
+            key: keys::D,
+            tone: tone::NONE,
+            stroke: true,
+        }
+    }
+}
+
+/// Input method trait - key mappings plus engine capabilities
+pub trait Method: Send + Sync {
     /// Check if key is a mark modifier
     /// Returns: 1=sắc, 2=huyền, 3=hỏi, 4=ngã, 5=nặng
     fn mark(&self, key: u16) -> Option<u8>;
 
+    /// Mark modifier with shift state
+    /// Symbol methods (VIQR) tell `~` from `` ` ``; default ignores shift
+    fn mark_ext(&self, key: u16, _shift: bool) -> Option<u8> {
+        self.mark(key)
+    }
+
     /// Check if key is a tone modifier
     /// Returns tone type if this key can modify vowels
     fn tone(&self, key: u16) -> Option<ToneType>;
 
+    /// Tone modifier with shift state (VIQR `^` is Shift+6, plain 6 is a digit)
+    fn tone_ext(&self, key: u16, _shift: bool) -> Option<ToneType> {
+        self.tone(key)
+    }
+
     /// Get valid targets for tone key
     /// Returns list of vowel keys this tone can apply to
-    fn tone_targets(&self, key: u16) -> &'static [u16];
+    fn tone_targets(&self, key: u16) -> &[u16];
 
     /// Check if key is stroke modifier (d → đ)
     fn stroke(&self, key: u16) -> bool;
 
     /// Check if key removes diacritics
     fn remove(&self, key: u16) -> bool;
+
+    /// Check if key is any modifier of this method
+    fn is_modifier(&self, key: u16, shift: bool) -> bool {
+        self.mark_ext(key, shift).is_some()
+            || self.tone_ext(key, shift).is_some()
+            || self.stroke(key)
+            || self.remove(key)
+    }
+
+    /// Vowel keys are their own circumflex: aa → â (Telex)
+    /// Enables the delayed patterns built on it ("data" → "dât", "xeps" + e → "xếp")
+    fn vowel_doubling(&self) -> bool {
+        false
+    }
+
+    /// `w` alone types ư: "nhw" → "như" (Telex)
+    fn w_as_vowel(&self) -> bool {
+        false
+    }
+
+    /// Where the stroke key finds its `d`
+    /// Per key, so a method can mix styles (Telex `d`, VNI `9`)
+    fn stroke_style(&self, _key: u16) -> StrokeStyle {
+        StrokeStyle::Adjacent
+    }
+
+    /// Letter typed by `key` itself, taking part in validation and tone
+    /// placement like one built with modifiers (direct layout)
+    fn direct_letter(&self, _key: u16) -> Option<DirectLetter> {
+        None
+    }
+
+    /// Modifiers are punctuation typed after the letter (VIQR)
+    /// They only act inside a word; `\` keeps the next symbol literal
+    fn symbol_modifiers(&self) -> bool {
+        false
+    }
 }
 
-/// Static method instances (zero-sized types, no heap allocation)
-static TELEX: Telex = Telex;
-static VNI: Vni = Vni;
+/// Method shared with an engine (built-in or loaded table)
+pub type SharedMethod = Arc<dyn Method>;
 
-/// Get method by id (returns static reference, no allocation)
-pub fn get(id: u8) -> &'static dyn Method {
+/// Built-in method by id as a `SharedMethod` (Telex for unknown ids)
+pub fn shared(id: u8) -> SharedMethod {
     match id {
-        1 => &VNI,
-        _ => &TELEX,
+        id::VNI => Arc::new(Vni),
+        id::VIQR => Arc::new(Viqr),
+        id::TELEX_VNI => Arc::new(TelexVni),
+        id::DIRECT => Arc::new(Direct),
+        _ => Arc::new(Telex),
     }
 }
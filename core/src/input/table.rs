@@ -0,0 +1,362 @@
+//! Table Input Methods
+//!
+//! User-defined input methods loaded from a text table, one rule per line
+//! (`#` at the start of a line or after a space starts a comment):
+//!
+//! ```text
+//! name = Telex (table)
+//! sac = s
+//! huyen = f
+//! hoi = r
+//! nga = x
+//! nang = j
+//! circumflex = a:a e:e o:o
+//! horn = w:aou
+//! stroke = d
+//! remove = z
+//! stroke_style = delayed
+//! w_as_vowel = true
+//! ```
+//!
+//! Values are space-separated keys, written as the character they type
+//! (`s`, `1`, `'`, `~`, `\#` for `#`); stroke and remove take unshifted
+//! keys only, and a keycode has one tone rule at most (shifted or not).
+//! A tone key may list its target vowels after `:`
+//! (default: aeo for circumflex, ou for horn, a for breve). Vowel doubling
+//! (aa → â) and punctuation modifiers (VIQR) are detected from the rules.
+//! Rules: `sac huyen hoi nga nang circumflex horn breve stroke remove`,
+//! options: `name`, `stroke_style` (adjacent, delayed, anywhere),
+//! `w_as_vowel` (true/false).
+
+use super::{Method, StrokeStyle, ToneType, BREVE_TARGETS, CIRCUMFLEX_TARGETS, HORN_TARGETS_VNI};
+use crate::data::keymap::KeyEvent;
+use crate::data::keys;
+use std::path::Path;
+
+/// Typed key: (keycode, shift)
+pub(super) type Key = (u16, bool);
+
+/// Mark rule names (index + 1 = mark value)
+const MARK_NAMES: [&str; 5] = ["sac", "huyen", "hoi", "nga", "nang"];
+
+/// Input method defined by a table
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableMethod {
+    name: String,
+    marks: Vec<(Key, u8)>,
+    tones: Vec<(Key, ToneType, Vec<u16>)>,
+    strokes: Vec<Key>,
+    removes: Vec<Key>,
+    stroke_style: StrokeStyle,
+    w_as_vowel: bool,
+}
+
+/// Whether a rule key matches a typed key (Shift only matters for symbols)
+pub(super) fn matches(rule: Key, key: u16, shift: bool) -> bool {
+    rule.0 == key && (keys::is_letter(key) || rule.1 == shift)
+}
+
+/// Parse one key character (printable ASCII, letters case-insensitive)
+pub(super) fn parse_key(c: char) -> Option<Key> {
+    if !c.is_ascii_graphic() {
+        return None;
+    }
+    let ev = KeyEvent::from_char(c.to_ascii_lowercase())?;
+    Some((ev.key, ev.shift))
+}
+
+/// Parse a key entry: one key character, or `\#`
+fn parse_entry_key(entry: &str) -> Option<Key> {
+    let entry = if entry == "\\#" { "#" } else { entry };
+    let mut chars = entry.chars();
+    let key = parse_key(chars.next()?)?;
+    if chars.next().is_some() {
+        return None;
+    }
+    Some(key)
+}
+
+/// `line` without its comment: `#` at the start or after whitespace
+fn strip_comment(line: &str) -> &str {
+    let mut prev = ' ';
+    for (i, c) in line.char_indices() {
+        if c == '#' && prev.is_whitespace() {
+            return &line[..i];
+        }
+        prev = c;
+    }
+    line
+}
+
+/// Parse one tone entry: `key` or `key:targets`
+fn parse_tone(entry: &str, tone_type: ToneType) -> Option<(Key, Vec<u16>)> {
+    let allowed: &[u16] = match tone_type {
+        ToneType::Circumflex => CIRCUMFLEX_TARGETS,
+        // Horn on a is ă (Telex "aw")
+        ToneType::Horn => &[keys::A, keys::O, keys::U],
+        ToneType::Breve => BREVE_TARGETS,
+    };
+    let (key, targets) = match entry.split_once(':') {
+        Some((key, targets)) => (key, Some(targets)),
+        None => (entry, None),
+    };
+    let key = parse_entry_key(key)?;
+    let targets = match targets {
+        Some(targets) => targets
+            .chars()
+            .map(|c| parse_key(c).map(|(k, _)| k).filter(|k| allowed.contains(k)))
+            .collect::<Option<Vec<_>>>()?,
+        None => match tone_type {
+            ToneType::Horn => HORN_TARGETS_VNI.to_vec(),
+            _ => allowed.to_vec(),
+        },
+    };
+    if targets.is_empty() {
+        return None;
+    }
+    Some((key, targets))
+}
+
+impl TableMethod {
+    /// Parse a method table (None on unknown rules, bad keys or a key
+    /// bound to two rules)
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut method = Self {
+            name: String::from("Custom"),
+            marks: Vec::new(),
+            tones: Vec::new(),
+            strokes: Vec::new(),
+            removes: Vec::new(),
+            stroke_style: StrokeStyle::Adjacent,
+            w_as_vowel: false,
+        };
+        let mut used: Vec<Key> = Vec::new();
+        let mut bind = |key: Key| -> Option<Key> {
+            if used.contains(&key) {
+                return None;
+            }
+            used.push(key);
+            Some(key)
+        };
+
+        for line in text.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (rule, value) = line.split_once('=')?;
+            let (rule, value) = (rule.trim(), value.trim());
+            let entries = value.split_whitespace();
+
+            if let Some(i) = MARK_NAMES.iter().position(|&n| n == rule) {
+                for entry in entries {
+                    let key = parse_entry_key(entry)?;
+                    method.marks.push((bind(key)?, i as u8 + 1));
+                }
+                continue;
+            }
+            let tone_type = match rule {
+                "circumflex" => Some(ToneType::Circumflex),
+                "horn" => Some(ToneType::Horn),
+                "breve" => Some(ToneType::Breve),
+                _ => None,
+            };
+            if let Some(tone_type) = tone_type {
+                for entry in entries {
+                    let (key, targets) = parse_tone(entry, tone_type)?;
+                    // `tone_targets` looks tones up by keycode alone
+                    if method.tones.iter().any(|((k, _), _, _)| *k == key.0) {
+                        return None;
+                    }
+                    method.tones.push((bind(key)?, tone_type, targets));
+                }
+                continue;
+            }
+            match rule {
+                "stroke" | "remove" => {
+                    for entry in entries {
+                        // Methods check stroke and remove without Shift
+                        let key = parse_entry_key(entry).filter(|&(_, shift)| !shift)?;
+                        let key = bind(key)?;
+                        if rule == "stroke" {
+                            method.strokes.push(key);
+                        } else {
+                            method.removes.push(key);
+                        }
+                    }
+                }
+                "name" => method.name = value.to_string(),
+                "stroke_style" => {
+                    method.stroke_style = match value {
+                        "adjacent" => StrokeStyle::Adjacent,
+                        "delayed" => StrokeStyle::Delayed,
+                        "anywhere" => StrokeStyle::Anywhere,
+                        _ => return None,
+                    }
+                }
+                "w_as_vowel" => method.w_as_vowel = value.parse().ok()?,
+                _ => return None,
+            }
+        }
+
+        if method.marks.is_empty() && method.tones.is_empty() {
+            return None;
+        }
+        Some(method)
+    }
+
+    /// Read and parse a method table file
+    pub fn load(path: &Path) -> Option<Self> {
+        Self::parse(&std::fs::read_to_string(path).ok()?)
+    }
+
+    /// Display name (`name` rule)
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn modifier_keys(&self) -> impl Iterator<Item = Key> + '_ {
+        self.marks
+            .iter()
+            .map(|&(k, _)| k)
+            .chain(self.tones.iter().map(|&(k, _, _)| k))
+            .chain(self.strokes.iter().copied())
+            .chain(self.removes.iter().copied())
+    }
+}
+
+impl Method for TableMethod {
+    fn mark(&self, key: u16) -> Option<u8> {
+        self.mark_ext(key, false)
+    }
+
+    fn mark_ext(&self, key: u16, shift: bool) -> Option<u8> {
+        self.marks
+            .iter()
+            .find(|&&(k, _)| matches(k, key, shift))
+            .map(|&(_, mark)| mark)
+    }
+
+    fn tone(&self, key: u16) -> Option<ToneType> {
+        self.tone_ext(key, false)
+    }
+
+    fn tone_ext(&self, key: u16, shift: bool) -> Option<ToneType> {
+        self.tones
+            .iter()
+            .find(|(k, _, _)| matches(*k, key, shift))
+            .map(|&(_, tone_type, _)| tone_type)
+    }
+
+    fn tone_targets(&self, key: u16) -> &[u16] {
+        self.tones
+            .iter()
+            .find(|((k, _), _, _)| *k == key)
+            .map_or(&[], |(_, _, targets)| targets.as_slice())
+    }
+
+    fn stroke(&self, key: u16) -> bool {
+        self.strokes.iter().any(|&k| matches(k, key, false))
+    }
+
+    fn remove(&self, key: u16) -> bool {
+        self.removes.iter().any(|&k| matches(k, key, false))
+    }
+
+    fn vowel_doubling(&self) -> bool {
+        self.tones.iter().any(|&((k, _), tone_type, ref targets)| {
+            tone_type == ToneType::Circumflex && targets.as_slice() == [k]
+        })
+    }
+
+    fn w_as_vowel(&self) -> bool {
+        self.w_as_vowel
+    }
+
+    fn stroke_style(&self, _key: u16) -> StrokeStyle {
+        self.stroke_style
+    }
+
+    fn symbol_modifiers(&self) -> bool {
+        self.modifier_keys()
+            .any(|(k, shift)| keys::is_break_ext(k, shift))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TELEX_TABLE: &str = "\
+        # Telex as a table
+        name = Telex (table)
+        sac = s
+        huyen = f
+        hoi = r
+        nga = x
+        nang = j
+        circumflex = a:a e:e o:o
+        horn = w:aou
+        stroke = d
+        remove = z
+        stroke_style = delayed
+        w_as_vowel = true
+    ";
+
+    #[test]
+    fn test_parse_telex_table() {
+        let m = TableMethod::parse(TELEX_TABLE).unwrap();
+        assert_eq!(m.name(), "Telex (table)");
+        assert_eq!(m.mark(keys::S), Some(1));
+        assert_eq!(m.mark(keys::J), Some(5));
+        assert_eq!(m.tone(keys::A), Some(ToneType::Circumflex));
+        assert_eq!(m.tone_targets(keys::A), &[keys::A]);
+        assert_eq!(m.tone_targets(keys::W), &[keys::A, keys::O, keys::U]);
+        assert!(m.stroke(keys::D));
+        assert!(m.remove(keys::Z));
+        assert!(m.vowel_doubling());
+        assert!(m.w_as_vowel());
+        assert!(!m.symbol_modifiers());
+        assert_eq!(m.stroke_style(keys::D), StrokeStyle::Delayed);
+    }
+
+    #[test]
+    fn test_parse_symbol_table() {
+        let m = TableMethod::parse("huyen = `\nnga = ~\nhorn = +").unwrap();
+        assert_eq!(m.mark_ext(keys::BACKQUOTE, false), Some(2));
+        assert_eq!(m.mark_ext(keys::BACKQUOTE, true), Some(4));
+        assert_eq!(m.tone_ext(keys::EQUAL, true), Some(ToneType::Horn));
+        assert_eq!(m.tone_ext(keys::EQUAL, false), None);
+        assert_eq!(m.tone_targets(keys::EQUAL), HORN_TARGETS_VNI);
+        assert!(m.symbol_modifiers());
+        assert!(!m.vowel_doubling());
+    }
+
+    #[test]
+    fn test_hash_key_and_comments() {
+        let m = TableMethod::parse("# marks\nsac = \\# # Shift+3\nhuyen = f").unwrap();
+        assert_eq!(m.mark_ext(keys::N3, true), Some(1));
+        assert_eq!(m.mark_ext(keys::N3, false), None);
+        // Not after whitespace: part of the value (a two-char key)
+        assert_eq!(TableMethod::parse("sac = s#"), None);
+        assert!(TableMethod::parse("sac = s\nstroke = d # dd → đ").is_some());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        // Key bound twice
+        assert_eq!(TableMethod::parse("sac = s\nhoi = s"), None);
+        // Unknown rule, bad option, bad target, multi-char key
+        assert_eq!(TableMethod::parse("sac = s\nmagic = q"), None);
+        assert_eq!(TableMethod::parse("sac = s\nstroke_style = sideways"), None);
+        assert_eq!(TableMethod::parse("breve = w:o"), None);
+        assert_eq!(TableMethod::parse("sac = ss"), None);
+        // Shifted stroke or remove would never fire
+        assert_eq!(TableMethod::parse("sac = s\nstroke = @"), None);
+        assert_eq!(TableMethod::parse("sac = s\nremove = ~"), None);
+        // One tone rule per keycode: tone targets ignore Shift
+        assert_eq!(TableMethod::parse("circumflex = 6\nhorn = ^"), None);
+        // No marks or tones
+        assert_eq!(TableMethod::parse("stroke = d"), None);
+    }
+}
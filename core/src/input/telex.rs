@@ -6,7 +6,7 @@
 //! - Stroke: d
 //! - Remove: z
 
-use super::{Method, ToneType, HORN_TARGETS_TELEX};
+use super::{Method, StrokeStyle, ToneType, HORN_TARGETS_TELEX};
 use crate::data::keys;
 
 pub struct Telex;
@@ -31,7 +31,7 @@ impl Method for Telex {
         }
     }
 
-    fn tone_targets(&self, key: u16) -> &'static [u16] {
+    fn tone_targets(&self, key: u16) -> &[u16] {
         match key {
             keys::A => &[keys::A],
             keys::E => &[keys::E],
@@ -48,6 +48,18 @@ impl Method for Telex {
     fn remove(&self, key: u16) -> bool {
         key == keys::Z
     }
+
+    fn vowel_doubling(&self) -> bool {
+        true
+    }
+
+    fn w_as_vowel(&self) -> bool {
+        true
+    }
+
+    fn stroke_style(&self, _key: u16) -> StrokeStyle {
+        StrokeStyle::Delayed
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,83 @@
+//! Telex + VNI Input Method
+//!
+//! Accepts both schemes at once, for users who mix habits:
+//! - Telex letters: s f r x j, aa ee oo w, dd, z
+//! - VNI digits: 1-5 marks, 6 7 8 tones, 9 stroke, 0 remove
+//!
+//! The key sets don't overlap, so each key keeps its own method's meaning
+//! (including where the stroke key finds its `d`). Shift+digit still types
+//! the symbol ("a@" stays "a@").
+
+use super::{Method, StrokeStyle, Telex, ToneType, Vni};
+use crate::data::keys;
+
+pub struct TelexVni;
+
+impl Method for TelexVni {
+    fn mark(&self, key: u16) -> Option<u8> {
+        Telex.mark(key).or_else(|| Vni.mark(key))
+    }
+
+    fn tone(&self, key: u16) -> Option<ToneType> {
+        Telex.tone(key).or_else(|| Vni.tone(key))
+    }
+
+    fn tone_targets(&self, key: u16) -> &[u16] {
+        if keys::is_number(key) {
+            Vni.tone_targets(key)
+        } else {
+            Telex.tone_targets(key)
+        }
+    }
+
+    fn stroke(&self, key: u16) -> bool {
+        Telex.stroke(key) || Vni.stroke(key)
+    }
+
+    fn remove(&self, key: u16) -> bool {
+        Telex.remove(key) || Vni.remove(key)
+    }
+
+    fn vowel_doubling(&self) -> bool {
+        true
+    }
+
+    fn w_as_vowel(&self) -> bool {
+        true
+    }
+
+    fn stroke_style(&self, key: u16) -> StrokeStyle {
+        if keys::is_number(key) {
+            Vni.stroke_style(key)
+        } else {
+            Telex.stroke_style(key)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_both_schemes() {
+        let m = TelexVni;
+        assert_eq!(m.mark(keys::S), Some(1));
+        assert_eq!(m.mark(keys::N1), Some(1));
+        assert_eq!(m.mark(keys::N5), Some(5));
+        assert_eq!(m.tone(keys::W), Some(ToneType::Horn));
+        assert_eq!(m.tone(keys::N8), Some(ToneType::Breve));
+        assert!(m.stroke(keys::D) && m.stroke(keys::N9));
+        assert!(m.remove(keys::Z) && m.remove(keys::N0));
+        assert_eq!(m.mark(keys::B), None);
+    }
+
+    #[test]
+    fn test_per_key_behaviour() {
+        let m = TelexVni;
+        assert_eq!(m.tone_targets(keys::A), &[keys::A]);
+        assert_eq!(m.tone_targets(keys::N7), &[keys::O, keys::U]);
+        assert_eq!(m.stroke_style(keys::D), StrokeStyle::Delayed);
+        assert_eq!(m.stroke_style(keys::N9), StrokeStyle::Anywhere);
+    }
+}
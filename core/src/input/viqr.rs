@@ -0,0 +1,109 @@
+//! VIQR Input Method
+//!
+//! Key mappings (typed after the letter):
+//! - Marks: '=sắc, `=huyền, ?=hỏi, ~=ngã, .=nặng
+//! - Tones: ^=circumflex, +=horn, (=breve
+//! - Stroke: dd
+//!
+//! Modifiers are punctuation, so they only act inside a word; typing the
+//! same modifier twice gives the literal symbol ("a''" → "a'").
+
+use super::{Method, ToneType, BREVE_TARGETS, CIRCUMFLEX_TARGETS, HORN_TARGETS_VNI};
+use crate::data::keys;
+
+pub struct Viqr;
+
+impl Method for Viqr {
+    fn mark(&self, key: u16) -> Option<u8> {
+        match key {
+            keys::QUOTE => Some(1),     // sắc
+            keys::BACKQUOTE => Some(2), // huyền
+            keys::DOT => Some(5),       // nặng
+            _ => None,
+        }
+    }
+
+    fn mark_ext(&self, key: u16, shift: bool) -> Option<u8> {
+        if !shift {
+            return self.mark(key);
+        }
+        match key {
+            keys::SLASH => Some(3),     // hỏi (?)
+            keys::BACKQUOTE => Some(4), // ngã (~)
+            _ => None,
+        }
+    }
+
+    fn tone(&self, key: u16) -> Option<ToneType> {
+        match key {
+            keys::N6 => Some(ToneType::Circumflex), // ^
+            keys::EQUAL => Some(ToneType::Horn),    // +
+            keys::N9 => Some(ToneType::Breve),      // (
+            _ => None,
+        }
+    }
+
+    fn tone_ext(&self, key: u16, shift: bool) -> Option<ToneType> {
+        if shift {
+            self.tone(key)
+        } else {
+            None
+        }
+    }
+
+    fn tone_targets(&self, key: u16) -> &[u16] {
+        match key {
+            keys::N6 => CIRCUMFLEX_TARGETS,
+            keys::EQUAL => HORN_TARGETS_VNI,
+            keys::N9 => BREVE_TARGETS,
+            _ => &[],
+        }
+    }
+
+    fn stroke(&self, key: u16) -> bool {
+        key == keys::D
+    }
+
+    fn remove(&self, _key: u16) -> bool {
+        false
+    }
+
+    fn symbol_modifiers(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks() {
+        let v = Viqr;
+        assert_eq!(v.mark_ext(keys::QUOTE, false), Some(1));
+        assert_eq!(v.mark_ext(keys::BACKQUOTE, false), Some(2));
+        assert_eq!(v.mark_ext(keys::SLASH, true), Some(3));
+        assert_eq!(v.mark_ext(keys::BACKQUOTE, true), Some(4));
+        assert_eq!(v.mark_ext(keys::DOT, false), Some(5));
+        assert_eq!(v.mark_ext(keys::SLASH, false), None);
+        assert_eq!(v.mark_ext(keys::S, false), None);
+    }
+
+    #[test]
+    fn test_tones() {
+        let v = Viqr;
+        assert_eq!(v.tone_ext(keys::N6, true), Some(ToneType::Circumflex));
+        assert_eq!(v.tone_ext(keys::EQUAL, true), Some(ToneType::Horn));
+        assert_eq!(v.tone_ext(keys::N9, true), Some(ToneType::Breve));
+        // Unshifted 6, =, 9 are plain characters
+        assert_eq!(v.tone_ext(keys::N6, false), None);
+        assert_eq!(v.tone_ext(keys::EQUAL, false), None);
+    }
+
+    #[test]
+    fn test_stroke() {
+        let v = Viqr;
+        assert!(v.stroke(keys::D));
+        assert!(!v.stroke(keys::N9));
+    }
+}
@@ -6,7 +6,7 @@
 //! - Stroke: 9
 //! - Remove: 0
 
-use super::{Method, ToneType, BREVE_TARGETS, CIRCUMFLEX_TARGETS, HORN_TARGETS_VNI};
+use super::{Method, StrokeStyle, ToneType, BREVE_TARGETS, CIRCUMFLEX_TARGETS, HORN_TARGETS_VNI};
 use crate::data::keys;
 
 pub struct Vni;
@@ -32,7 +32,7 @@ impl Method for Vni {
         }
     }
 
-    fn tone_targets(&self, key: u16) -> &'static [u16] {
+    fn tone_targets(&self, key: u16) -> &[u16] {
         match key {
             keys::N6 => CIRCUMFLEX_TARGETS,
             keys::N7 => HORN_TARGETS_VNI,
@@ -48,6 +48,10 @@ impl Method for Vni {
     fn remove(&self, key: u16) -> bool {
         key == keys::N0
     }
+
+    fn stroke_style(&self, _key: u16) -> StrokeStyle {
+        StrokeStyle::Anywhere
+    }
 }
 
 #[cfg(test)]
@@ -7,7 +7,7 @@
 //! ```c
 //! // Initialize once at app start
 //! ime_init();
-//! ime_method(0);  // 0=Telex, 1=VNI
+//! ime_method(0);  // 0=Telex, 1=VNI, 2=VIQR, 4=Telex+VNI, 5=direct
 //!
 //! // Process each keystroke
 //! ImeResult* r = ime_key(keycode, is_shift, is_ctrl);
@@ -19,28 +19,437 @@
 //! // Clean up on word boundary
 //! ime_clear();
 //! ```
+//!
+//! # Multiple Instances
+//!
+//! The `ime_*` functions above drive one global engine. Hosts with several
+//! independent typing contexts create one handle per context instead:
+//!
+//! ```c
+//! ImeEngine* h = ime_engine_new();
+//! ime_engine_method(h, 0);
+//! ImeResult* r = ime_engine_key_ext(h, keycode, caps, ctrl, shift);
+//! ime_free(r);
+//! ime_engine_free(h);
+//! ```
+//!
+//! # ABI
+//!
+//! All exports are declared in `include/gonhanh.h` (checked against this file
+//! by `tests/abi_test.rs`). Hosts should compare `ime_abi_version()` with
+//! `GONHANH_ABI_VERSION` at startup and refuse a mismatched library.
 
 pub mod data;
 pub mod engine;
 pub mod input;
+pub mod text;
 pub mod updater;
 pub mod utils;
 
+use data::keymap::KeyNamespace;
+use data::layout::KeyboardLayout;
+use engine::config::EngineConfig;
+use engine::convert;
+use engine::encoding::{self, OutputEncoding};
+use engine::event::Event;
 use engine::{Engine, Result};
+use input::{KeyBindings, TableMethod};
+use std::ffi::CString;
+use std::os::raw::{c_char, c_void};
 use std::sync::Mutex;
+use text::{case, slug};
+
+/// C ABI version, bumped on any incompatible change to exported
+/// functions or the `Result` layout
+pub const ABI_VERSION: u32 = 1;
+
+/// Feature flags returned by `ime_capabilities`
+pub mod capability {
+    /// User shortcuts (`ime_add_shortcut`)
+    pub const SHORTCUTS: u32 = 1 << 0;
+    /// English auto-restore on space (`ime_english_auto_restore`)
+    pub const AUTO_RESTORE: u32 = 1 << 1;
+    /// ESC restores raw ASCII (`ime_esc_restore`)
+    pub const ESC_RESTORE: u32 = 1 << 2;
+    /// Capitalize after sentence end (`ime_auto_capitalize`)
+    pub const AUTO_CAPITALIZE: u32 = 1 << 3;
+    /// Composing text query (`ime_get_buffer`)
+    pub const PREEDIT: u32 = 1 << 4;
+    /// Restore a committed word for editing (`ime_restore_word`)
+    pub const RESTORE_WORD: u32 = 1 << 5;
+    /// Independent engine instances (`ime_engine_*`)
+    pub const ENGINE_HANDLES: u32 = 1 << 6;
+    /// Native keycode namespaces (`ime_key_platform`)
+    pub const PLATFORM_KEYS: u32 = 1 << 7;
+    /// Character input (`ime_char`)
+    pub const CHAR_INPUT: u32 = 1 << 8;
+    /// Logical keyboard layouts (`ime_layout`)
+    pub const LAYOUTS: u32 = 1 << 9;
+    /// Config text (`ime_get_config`/`ime_set_config`)
+    pub const CONFIG: u32 = 1 << 10;
+    /// Output longer than one Result (`ime_result_next_chunk`)
+    pub const OUTPUT_CHUNKS: u32 = 1 << 11;
+    /// UTF-8/UTF-16 result text (`ime_result_utf8`/`ime_result_utf16`)
+    pub const OUTPUT_ENCODING: u32 = 1 << 12;
+    /// Typed event callbacks (`ime_set_event_callback`)
+    pub const EVENTS: u32 = 1 << 13;
+    /// Per-focus typing state (`ime_focus`)
+    pub const FOCUS_CONTEXTS: u32 = 1 << 14;
+    /// User-defined input method tables (`ime_method_table`)
+    pub const METHOD_TABLES: u32 = 1 << 15;
+    /// Telex `[`/`]` type ơ/ư (`ime_bracket_vowels`)
+    pub const BRACKET_VOWELS: u32 = 1 << 16;
+    /// Rebindable modifier keys (`ime_key_bindings`)
+    pub const KEY_BINDINGS: u32 = 1 << 17;
+    /// Central Highlands orthography profile (`ime_orthography`)
+    pub const ORTHOGRAPHY: u32 = 1 << 18;
+    /// Legacy output charsets: TCVN3, VNI-Windows, VISCII (`ime_charset`)
+    pub const CHARSETS: u32 = 1 << 19;
+    /// Decomposed Unicode output and NFD-aware `ime_restore_word` (`IME_CHARSET_NFD`)
+    pub const NFD: u32 = 1 << 20;
+    /// Offline conversion of typed text (`ime_convert`)
+    pub const CONVERT: u32 = 1 << 21;
+    /// VIQR output charset and restore input (`IME_CHARSET_VIQR`)
+    pub const VIQR_CHARSET: u32 = 1 << 22;
+    /// Diacritic stripping and slugs (`ime_strip_diacritics`, `ime_slugify`)
+    pub const STRIP_DIACRITICS: u32 = 1 << 23;
+    /// Case transformation of text (`ime_change_case`)
+    pub const CHANGE_CASE: u32 = 1 << 24;
+
+    /// Everything this build supports
+    pub const ALL: u32 = SHORTCUTS
+        | AUTO_RESTORE
+        | ESC_RESTORE
+        | AUTO_CAPITALIZE
+        | PREEDIT
+        | RESTORE_WORD
+        | ENGINE_HANDLES
+        | PLATFORM_KEYS
+        | CHAR_INPUT
+        | LAYOUTS
+        | CONFIG
+        | OUTPUT_CHUNKS
+        | OUTPUT_ENCODING
+        | EVENTS
+        | FOCUS_CONTEXTS
+        | METHOD_TABLES
+        | BRACKET_VOWELS
+        | KEY_BINDINGS
+        | ORTHOGRAPHY
+        | CHARSETS
+        | NFD
+        | CONVERT
+        | VIQR_CHARSET
+        | STRIP_DIACRITICS
+        | CHANGE_CASE;
+}
 
 // Global engine instance (thread-safe via Mutex)
+// Backs the legacy `ime_*` functions; `ime_engine_*` functions take explicit handles.
 static ENGINE: Mutex<Option<Engine>> = Mutex::new(None);
 
+/// Event passed to callbacks registered with `ime_set_event_callback`
+///
+/// Strings are UTF-8 and only valid during the callback.
+#[repr(C)]
+pub struct ImeEvent {
+    /// Event kind (see `engine::event::kind`)
+    pub kind: u8,
+    /// Commit: word text; restores: restored text;
+    /// shortcut: replacement; auto-capitalize: the uppercased letter
+    pub text: *const c_char,
+    /// Commit: raw keystrokes; restores: replaced text;
+    /// shortcut: trigger; auto-capitalize: empty
+    pub detail: *const c_char,
+}
+
+/// Event callback: `user_data` is the pointer given at registration
+pub type ImeEventCallback = extern "C" fn(event: *const ImeEvent, user_data: *mut c_void);
+
+/// Registered callback (user data stored as address so the statics are `Send`)
+#[derive(Clone, Copy)]
+struct EventListener {
+    callback: ImeEventCallback,
+    user_data: usize,
+}
+
+// Event listeners: one for the global engine, one per handle (keyed by address)
+static GLOBAL_LISTENER: Mutex<Option<EventListener>> = Mutex::new(None);
+static HANDLE_LISTENERS: Mutex<Vec<(usize, EventListener)>> = Mutex::new(Vec::new());
+
+fn global_listener() -> Option<EventListener> {
+    *GLOBAL_LISTENER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn handle_listener(handle: *mut Engine) -> Option<EventListener> {
+    let listeners = HANDLE_LISTENERS.lock().unwrap_or_else(|e| e.into_inner());
+    listeners
+        .iter()
+        .find(|(h, _)| *h == handle as usize)
+        .map(|&(_, l)| l)
+}
+
+fn set_handle_listener(handle: *mut Engine, listener: Option<EventListener>) {
+    let mut listeners = HANDLE_LISTENERS.lock().unwrap_or_else(|e| e.into_inner());
+    listeners.retain(|(h, _)| *h != handle as usize);
+    if let Some(l) = listener {
+        listeners.push((handle as usize, l));
+    }
+}
+
+/// Deliver events to a listener (called with no engine lock held, so
+/// callbacks may call back into the FFI)
+fn dispatch(listener: Option<EventListener>, events: Vec<Event>) {
+    let Some(l) = listener else {
+        return;
+    };
+    for event in events {
+        let (text, detail) = event.texts();
+        let text = CString::new(text).unwrap_or_default();
+        let detail = CString::new(detail).unwrap_or_default();
+        let ffi_event = ImeEvent {
+            kind: event.kind(),
+            text: text.as_ptr(),
+            detail: detail.as_ptr(),
+        };
+        (l.callback)(&ffi_event, l.user_data as *mut c_void);
+    }
+}
+
+/// Process a key on the global engine and deliver the events it produced
+fn global_key(f: impl FnOnce(&mut Engine) -> Result) -> *mut Result {
+    let (result, events) = with_engine((std::ptr::null_mut(), Vec::new()), |e| {
+        (into_ffi(f(e)), e.take_events())
+    });
+    dispatch(global_listener(), events);
+    result
+}
+
+/// Process a key on a handle and deliver the events it produced
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+unsafe fn handle_key(handle: *mut Engine, f: impl FnOnce(&mut Engine) -> Result) -> *mut Result {
+    let (result, events) = with_handle(handle, (std::ptr::null_mut(), Vec::new()), |e| {
+        (into_ffi(f(e)), e.take_events())
+    });
+    dispatch(handle_listener(handle), events);
+    result
+}
+
 /// Lock the engine mutex, recovering from poisoned state if needed (for tests)
 fn lock_engine() -> std::sync::MutexGuard<'static, Option<Engine>> {
     ENGINE.lock().unwrap_or_else(|e| e.into_inner())
 }
 
+/// Run `f` on the global engine, or return `default` if not initialized
+fn with_engine<T>(default: T, f: impl FnOnce(&mut Engine) -> T) -> T {
+    let mut guard = lock_engine();
+    match guard.as_mut() {
+        Some(e) => f(e),
+        None => default,
+    }
+}
+
+/// Run `f` on the engine behind `handle`, or return `default` if null
+///
+/// # Safety
+/// `handle` must be null or a live pointer returned by `ime_engine_new`.
+unsafe fn with_handle<T>(handle: *mut Engine, default: T, f: impl FnOnce(&mut Engine) -> T) -> T {
+    match handle.as_mut() {
+        Some(e) => f(e),
+        None => default,
+    }
+}
+
+/// Borrow a C string as UTF-8 (None if null or invalid UTF-8)
+///
+/// # Safety
+/// `s` must be null or a valid null-terminated string.
+unsafe fn c_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    std::ffi::CStr::from_ptr(s).to_str().ok()
+}
+
+/// Move a result to the heap for FFI (caller frees with `ime_free`)
+fn into_ffi(r: Result) -> *mut Result {
+    Box::into_raw(Box::new(r))
+}
+
+/// Add a shortcut, auto-detecting its trigger type:
+/// - If trigger contains only non-letter chars (like "->", "=>"), use immediate trigger
+/// - Otherwise use word boundary trigger (traditional abbreviations like "vn" → "Việt Nam")
+fn add_shortcut(e: &mut Engine, trigger: &str, replacement: &str) {
+    let is_symbol_trigger = trigger.chars().all(|c| !c.is_alphabetic());
+    let shortcut = if is_symbol_trigger {
+        engine::shortcut::Shortcut::immediate(trigger, replacement)
+    } else {
+        engine::shortcut::Shortcut::new(trigger, replacement)
+    };
+    e.shortcuts_mut().add(shortcut);
+}
+
+/// Dispatch a platform key event (unknown namespace passes through)
+fn key_platform(e: &mut Engine, ns: u8, code: u32, mods: u8) -> Result {
+    match KeyNamespace::from_u8(ns) {
+        Some(ns) => e.on_key_platform(ns, code, mods),
+        None => Result::none(),
+    }
+}
+
+/// Dispatch a character event (invalid codepoints end the word)
+fn char_input(e: &mut Engine, codepoint: u32) -> Result {
+    match char::from_u32(codepoint) {
+        Some(c) => e.on_char(c),
+        None => {
+            e.clear_all();
+            Result::none()
+        }
+    }
+}
+
+/// Apply a built-in layout, returning false for unknown ids
+fn set_builtin_layout(e: &mut Engine, layout: u8) -> bool {
+    match KeyboardLayout::builtin(layout) {
+        Some(l) => {
+            e.set_layout(l);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Apply a user-defined layout, returning false if the map is invalid
+fn set_custom_layout(e: &mut Engine, map: &str) -> bool {
+    match KeyboardLayout::from_qwerty_map(map) {
+        Some(l) => {
+            e.set_layout(l);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Parse and apply config text, returning false if invalid
+fn apply_config_text(e: &mut Engine, text: &str) -> bool {
+    match EngineConfig::parse(text) {
+        Some(config) => e.apply_config(&config),
+        None => false,
+    }
+}
+
+/// Parse a method table and select it, returning false if invalid
+fn set_method_table(e: &mut Engine, text: &str) -> bool {
+    match TableMethod::parse(text) {
+        Some(table) => {
+            e.set_method_table(table);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Parse key bindings and apply them, returning false if invalid
+fn set_key_bindings(e: &mut Engine, text: &str) -> bool {
+    KeyBindings::parse(text).is_some_and(|bindings| e.set_key_bindings(bindings))
+}
+
+/// Copy code units into a C buffer (truncated at a character boundary,
+/// always null-terminated), returning the full length in units like `snprintf`
+///
+/// # Safety
+/// `out` must be null or point to valid memory of at least `max_len` units.
+unsafe fn copy_units<T: Copy + Default>(
+    units: &[T],
+    out: *mut T,
+    max_len: i64,
+    is_boundary: impl Fn(usize) -> bool,
+) -> i64 {
+    if !out.is_null() && max_len > 0 {
+        let mut len = units.len().min(max_len as usize - 1);
+        while !is_boundary(len) {
+            len -= 1;
+        }
+        std::ptr::copy_nonoverlapping(units.as_ptr(), out, len);
+        *out.add(len) = T::default();
+    }
+    units.len() as i64
+}
+
+/// Copy a string into a C buffer, returning its full byte length
+///
+/// # Safety
+/// `out` must be null or point to valid memory of at least `max_len` bytes.
+unsafe fn copy_str(s: &str, out: *mut c_char, max_len: i64) -> i64 {
+    copy_units(s.as_bytes(), out.cast(), max_len, |i| s.is_char_boundary(i))
+}
+
+/// Copy result text into a C buffer in the given encoding, returning its
+/// full length in code units (0 for a null result)
+///
+/// # Safety
+/// * `result` must be null or a pointer returned by `ime_key*`/`ime_char`
+/// * `out` must be null or point to valid memory of at least `max_len` units
+unsafe fn copy_result(
+    result: *const Result,
+    enc: OutputEncoding,
+    out: *mut c_void,
+    max_len: i64,
+) -> i64 {
+    let Some(r) = result.as_ref() else {
+        return 0;
+    };
+    let units = enc.encode(&r.chars[..(r.count as usize).min(r.chars.len())]);
+    let boundary = |i| encoding::is_boundary(enc, &units, i);
+    match enc {
+        OutputEncoding::Utf32 => copy_units(&units, out.cast(), max_len, boundary),
+        OutputEncoding::Utf8 => {
+            let bytes: Vec<u8> = units.iter().map(|&u| u as u8).collect();
+            copy_units(&bytes, out.cast(), max_len, boundary)
+        }
+        OutputEncoding::Utf16 => {
+            let words: Vec<u16> = units.iter().map(|&u| u as u16).collect();
+            copy_units(&words, out.cast(), max_len, boundary)
+        }
+    }
+}
+
+/// Copy the buffer, in the output charset, into `out` as UTF-32, returning
+/// codepoints written
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+unsafe fn copy_buffer(e: &Engine, out: *mut u32, max_len: i64) -> i64 {
+    let utf32: Vec<u32> = e.get_buffer_output().chars().map(|c| c as u32).collect();
+    let len = utf32.len().min(max_len as usize);
+    std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
+    len as i64
+}
+
 // ============================================================
 // FFI Interface
 // ============================================================
 
+/// Get the C ABI version of this library.
+///
+/// Hosts should refuse to run if this differs from the `GONHANH_ABI_VERSION`
+/// they were built against: signatures or the `Result` layout changed.
+#[no_mangle]
+pub extern "C" fn ime_abi_version() -> u32 {
+    ABI_VERSION
+}
+
+/// Get the features supported by this library.
+///
+/// # Returns
+/// Bitwise OR of `IME_CAP_*` flags (see `capability`).
+#[no_mangle]
+pub extern "C" fn ime_capabilities() -> u32 {
+    capability::ALL
+}
+
 /// Initialize the IME engine.
 ///
 /// Must be called exactly once before any other `ime_*` functions.
@@ -50,8 +459,10 @@ fn lock_engine() -> std::sync::MutexGuard<'static, Option<Engine>> {
 /// Panics if mutex is poisoned (only if previous call panicked).
 #[no_mangle]
 pub extern "C" fn ime_init() {
+    let mut engine = Engine::new();
+    engine.set_events_enabled(global_listener().is_some());
     let mut guard = lock_engine();
-    *guard = Some(Engine::new());
+    *guard = Some(engine);
 }
 
 /// Process a key event and return the result.
@@ -70,19 +481,15 @@ pub extern "C" fn ime_init() {
 /// * `backspace`: number of characters to delete
 /// * `chars`: UTF-32 codepoints to insert
 /// * `count`: number of valid chars
+/// * `flags`: 0x01=key consumed (don't pass the key through),
+///   0x02=more output follows (see `ime_result_next_chunk`)
 ///
 /// # Note
 /// For VNI mode with Shift+number keys (to type @, #, $ etc.),
 /// use `ime_key_ext` with the shift parameter.
 #[no_mangle]
 pub extern "C" fn ime_key(key: u16, caps: bool, ctrl: bool) -> *mut Result {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        let r = e.on_key(key, caps, ctrl);
-        Box::into_raw(Box::new(r))
-    } else {
-        std::ptr::null_mut()
-    }
+    global_key(|e| e.on_key(key, caps, ctrl))
 }
 
 /// Process a key event with extended parameters.
@@ -105,27 +512,268 @@ pub extern "C" fn ime_key(key: u16, caps: bool, ctrl: bool) -> *mut Result {
 /// - etc.
 #[no_mangle]
 pub extern "C" fn ime_key_ext(key: u16, caps: bool, ctrl: bool, shift: bool) -> *mut Result {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        let r = e.on_key_ext(key, caps, ctrl, shift);
-        Box::into_raw(Box::new(r))
-    } else {
-        std::ptr::null_mut()
-    }
+    global_key(|e| e.on_key_ext(key, caps, ctrl, shift))
+}
+
+/// Process a key event given in a platform keycode namespace.
+///
+/// Lets every frontend pass its native keycodes instead of translating
+/// them to macOS virtual keycodes itself.
+///
+/// # Arguments
+/// * `ns` - 0=macOS VK, 1=Windows VK, 2=X11 keysym, 3=Linux evdev
+/// * `code` - Keycode in that namespace
+/// * `mods` - Modifier bits: 0x01=CapsLock, 0x02=Cmd/Ctrl/Alt, 0x04=Shift
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+///
+/// Unknown namespaces and unmapped keys return action=0 (pass through).
+#[no_mangle]
+pub extern "C" fn ime_key_platform(ns: u8, code: u32, mods: u8) -> *mut Result {
+    global_key(|e| key_platform(e, ns, code, mods))
+}
+
+/// Process an already-resolved character instead of a physical key.
+///
+/// For hosts that receive characters rather than keycodes (IBus, Wayland
+/// text-input, browser extensions, editor plugins).
+///
+/// # Arguments
+/// * `codepoint` - Unicode scalar value of the typed character.
+///   Control characters 0x08 (Backspace), 0x09 (Tab), 0x0A/0x0D (Enter)
+///   and 0x1B (ESC) act as the corresponding keys.
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if engine not initialized
+///
+/// Characters the engine doesn't handle end the current word and return
+/// action=0 (pass through).
+#[no_mangle]
+pub extern "C" fn ime_char(codepoint: u32) -> *mut Result {
+    global_key(|e| char_input(e, codepoint))
+}
+
+/// Fetch the next chunk of output that didn't fit in the last result.
+///
+/// Output longer than 64 codepoints (e.g. long shortcut replacements) is
+/// split: the result from `ime_key*`/`ime_char` has flag 0x02 set, and the
+/// remaining text comes from this function in order. Each chunk is a Send
+/// result with `backspace = 0`; the last chunk has flag 0x02 clear.
+/// Pending chunks are dropped on the next key event.
+///
+/// # Returns
+/// * Pointer to `Result` struct (caller must free with `ime_free`)
+/// * `null` if no output is pending or engine not initialized
+#[no_mangle]
+pub extern "C" fn ime_result_next_chunk() -> *mut Result {
+    with_engine(std::ptr::null_mut(), |e| {
+        e.next_output_chunk().map_or(std::ptr::null_mut(), into_ffi)
+    })
+}
+
+/// Get result text as UTF-8.
+///
+/// # Arguments
+/// * `result` - Result from `ime_key*`/`ime_char`/`ime_result_next_chunk`
+/// * `out` - Buffer for the text (null-terminated if `max_len > 0`)
+/// * `max_len` - Size of `out` in bytes
+///
+/// # Returns
+/// Full length in bytes (excluding the terminator), like `snprintf`.
+/// Truncation never splits a character. Returns 0 if `result` is null.
+///
+/// # Safety
+/// * `result` must be null or a live pointer not yet passed to `ime_free`
+/// * `out` must be null or point to valid memory of at least `max_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_result_utf8(
+    result: *const Result,
+    out: *mut c_char,
+    max_len: i64,
+) -> i64 {
+    copy_result(result, OutputEncoding::Utf8, out.cast(), max_len)
+}
+
+/// Get result text as UTF-16 (ready for Windows `SendInput`).
+///
+/// Characters above U+FFFF (emoji) become surrogate pairs; truncation
+/// never splits a pair.
+///
+/// # Arguments
+/// * `result` - Result from `ime_key*`/`ime_char`/`ime_result_next_chunk`
+/// * `out` - Buffer for the text (null-terminated if `max_len > 0`)
+/// * `max_len` - Size of `out` in UTF-16 code units
+///
+/// # Returns
+/// Full length in code units (excluding the terminator). 0 if `result` is null.
+///
+/// # Safety
+/// * `result` must be null or a live pointer not yet passed to `ime_free`
+/// * `out` must be null or point to valid memory of at least `max_len` code units
+#[no_mangle]
+pub unsafe extern "C" fn ime_result_utf16(
+    result: *const Result,
+    out: *mut u16,
+    max_len: i64,
+) -> i64 {
+    copy_result(result, OutputEncoding::Utf16, out.cast(), max_len)
+}
+
+/// Set the encoding used by `ime_result_text`.
+///
+/// # Arguments
+/// * `encoding` - 0=UTF-32, 1=UTF-8, 2=UTF-16
+///
+/// # Returns
+/// `false` if the encoding is unknown or engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_output_encoding(encoding: u8) -> bool {
+    let Some(enc) = OutputEncoding::from_u8(encoding) else {
+        return false;
+    };
+    with_engine(false, |e| {
+        e.set_output_encoding(enc);
+        true
+    })
+}
+
+/// Get result text in the encoding set by `ime_output_encoding`.
+///
+/// `out` and `max_len` are in code units of that encoding (u8/u16/u32);
+/// otherwise behaves like `ime_result_utf8`.
+///
+/// # Safety
+/// * `result` must be null or a live pointer not yet passed to `ime_free`
+/// * `out` must be null or point to valid memory of at least `max_len` code units
+#[no_mangle]
+pub unsafe extern "C" fn ime_result_text(
+    result: *const Result,
+    out: *mut c_void,
+    max_len: i64,
+) -> i64 {
+    let enc = with_engine(OutputEncoding::Utf32, |e| e.output_encoding());
+    copy_result(result, enc, out, max_len)
+}
+
+/// Register a callback for engine events (pass null to unregister).
+///
+/// Called after `ime_key*`/`ime_char` returns its result, once per event,
+/// in order. Events:
+/// * 1 = word committed (`text` = word, `detail` = raw keystrokes)
+/// * 2 = auto-restore (`text` = restored English, `detail` = replaced text)
+/// * 3 = ESC restore (`text` = raw keystrokes, `detail` = replaced text)
+/// * 4 = shortcut expanded (`text` = replacement, `detail` = trigger)
+/// * 5 = auto-capitalize (`text` = uppercased letter)
+///
+/// The callback runs on the thread that called `ime_key*` and may call
+/// other `ime_*` functions.
+///
+/// # Arguments
+/// * `callback` - Function to call, or null to unregister
+/// * `user_data` - Passed back to the callback unchanged
+#[no_mangle]
+pub extern "C" fn ime_set_event_callback(
+    callback: Option<ImeEventCallback>,
+    user_data: *mut c_void,
+) {
+    let listener = callback.map(|callback| EventListener {
+        callback,
+        user_data: user_data as usize,
+    });
+    *GLOBAL_LISTENER.lock().unwrap_or_else(|e| e.into_inner()) = listener;
+    with_engine((), |e| e.set_events_enabled(listener.is_some()));
 }
 
 /// Set the input method.
 ///
 /// # Arguments
-/// * `method` - 0 for Telex, 1 for VNI
+/// * `method` - 0 for Telex, 1 for VNI, 2 for VIQR, 3 for the table
+///   loaded with `ime_method_table`, 4 for Telex and VNI keys together,
+///   5 for the Vietnamese keyboard layout (number row types ă â ê ô đ)
 ///
-/// No-op if engine not initialized.
+/// No-op if engine not initialized, for unknown ids, or for 3 with no
+/// table loaded.
 #[no_mangle]
 pub extern "C" fn ime_method(method: u8) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
+    with_engine((), |e| {
         e.set_method(method);
-    }
+    })
+}
+
+/// Load a user-defined input method table and select it (method 3).
+///
+/// See `input::table` for the format. The table stays loaded when
+/// switching to another method, so `ime_method(3)` selects it again.
+///
+/// # Returns
+/// `false` if the table is invalid (method unchanged) or engine not initialized.
+///
+/// # Safety
+/// `table` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_method_table(table: *const c_char) -> bool {
+    let Some(text) = c_str(table) else {
+        return false;
+    };
+    with_engine(false, |e| set_method_table(e, text))
+}
+
+/// Move or disable modifier keys of the input method.
+///
+/// `bindings` is space-separated `function:key` entries (see
+/// `input::bindings`), e.g. `"huyen:q nang:"` moves huyền from `f` to `q`
+/// and turns nặng off in Telex. An empty string restores the method's keys.
+/// Bindings stay applied when switching methods.
+///
+/// # Returns
+/// `false` if the text is invalid or a bound key is still used by another
+/// function of the current method (bindings unchanged), or engine not initialized.
+///
+/// # Safety
+/// `bindings` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_key_bindings(bindings: *const c_char) -> bool {
+    let Some(text) = c_str(bindings) else {
+        return false;
+    };
+    with_engine(false, |e| set_key_bindings(e, text))
+}
+
+/// Set the logical keyboard layout.
+///
+/// Remaps positional keycodes so Telex/VNI keys follow the letters the user
+/// actually sees (e.g. Colemak "r" is on the QWERTY "S" position).
+///
+/// # Arguments
+/// * `layout` - 0=QWERTY, 1=Dvorak, 2=Colemak, 3=AZERTY, 4=QWERTZ
+///
+/// # Returns
+/// `false` if the id is unknown (layout unchanged) or engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_layout(layout: u8) -> bool {
+    with_engine(false, |e| set_builtin_layout(e, layout))
+}
+
+/// Set a user-defined keyboard layout.
+///
+/// # Arguments
+/// * `map` - The 47 characters the layout produces on the US QWERTY keys
+///   `` `1234567890-=qwertyuiop[]\asdfghjkl;'zxcvbnm,./ ``, in that order
+///
+/// # Returns
+/// `false` if the map is invalid (layout unchanged) or engine not initialized.
+///
+/// # Safety
+/// `map` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_custom_layout(map: *const c_char) -> bool {
+    let Some(map_str) = c_str(map) else {
+        return false;
+    };
+    with_engine(false, |e| set_custom_layout(e, map_str))
 }
 
 /// Enable or disable the engine.
@@ -134,10 +782,7 @@ pub extern "C" fn ime_method(method: u8) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_enabled(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_enabled(enabled);
-    }
+    with_engine((), |e| e.set_enabled(enabled))
 }
 
 /// Set whether to skip w→ư shortcut in Telex mode.
@@ -147,10 +792,7 @@ pub extern "C" fn ime_enabled(enabled: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_skip_w_shortcut(skip: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_skip_w_shortcut(skip);
-    }
+    with_engine((), |e| e.set_skip_w_shortcut(skip))
 }
 
 /// Set whether ESC key restores raw ASCII input.
@@ -160,10 +802,7 @@ pub extern "C" fn ime_skip_w_shortcut(skip: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_esc_restore(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_esc_restore(enabled);
-    }
+    with_engine((), |e| e.set_esc_restore(enabled))
 }
 
 /// Set whether to enable free tone placement (skip validation).
@@ -174,10 +813,7 @@ pub extern "C" fn ime_esc_restore(enabled: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_free_tone(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_free_tone(enabled);
-    }
+    with_engine((), |e| e.set_free_tone(enabled))
 }
 
 /// Set whether to use modern orthography for tone placement.
@@ -187,217 +823,860 @@ pub extern "C" fn ime_free_tone(enabled: bool) {
 /// No-op if engine not initialized.
 #[no_mangle]
 pub extern "C" fn ime_modern(modern: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_modern_tone(modern);
-    }
+    with_engine((), |e| e.set_modern_tone(modern))
+}
+
+/// Enable/disable English auto-restore (experimental feature).
+///
+/// When `enabled` is true, automatically restores English words that were
+/// accidentally transformed (e.g., "tẽt" → "text", "ễpct" → "expect").
+/// When `enabled` is false (default), no auto-restore happens.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_english_auto_restore(enabled: bool) {
+    with_engine((), |e| e.set_english_auto_restore(enabled))
+}
+
+/// Enable/disable auto-capitalize after sentence-ending punctuation.
+///
+/// When `enabled` is true, automatically capitalizes the first letter
+/// after sentence-ending punctuation (. ! ? Enter).
+/// When `enabled` is false (default), no auto-capitalize happens.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_auto_capitalize(enabled: bool) {
+    with_engine((), |e| e.set_auto_capitalize(enabled))
+}
+
+/// Enable/disable Telex bracket vowels.
+///
+/// When `enabled` is true, `[` types ơ and `]` types ư (`{`/`}` for Ơ/Ư)
+/// inside a word or at its start, as in Unikey. When false (default),
+/// brackets are plain punctuation. Only Telex-style methods are affected.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_bracket_vowels(enabled: bool) {
+    with_engine((), |e| e.set_bracket_vowels(enabled))
+}
+
+/// Set the orthography profile.
+///
+/// The highlands profile adds the letters ĕ ŏ ŭ ơ̆ č ñ and the finals
+/// h l r s of Ê-đê, Jrai, Bahnar and M'nông. Breve: `v` in Telex, 8 in VNI;
+/// č/ñ: "cc"/"nn" in Telex, 9 in VNI. ơ̆ and marked ĕ ŏ ŭ are output with
+/// combining marks and count as one backspace.
+///
+/// # Arguments
+/// * `orthography` - 0=standard, 1=highlands
+///
+/// # Returns
+/// `false` if the id is unknown (profile unchanged) or engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_orthography(orthography: u8) -> bool {
+    with_engine(false, |e| e.set_orthography(orthography))
+}
+
+/// Set the output charset.
+///
+/// With a legacy charset, `ImeResult.chars` hold its bytes as U+0000–U+00FF
+/// (send them as-is to documents using TCVN3/VNI/VISCII fonts) and
+/// `backspace` counts bytes: VNI-Windows writes most letters as two
+/// ("á" = "aù"). `ime_restore_word` then takes text in that charset.
+/// With NFD, letters are base + combining diacritics and `backspace`
+/// counts code points ("ế" = "e" U+0302 U+0301 is three). VIQR writes
+/// diacritics as ASCII after the letter ("ế" = "e^'").
+///
+/// # Arguments
+/// * `charset` - 0=Unicode, 1=TCVN3 (ABC), 2=VNI-Windows, 3=VISCII, 4=NFD,
+///   5=VIQR
+///
+/// # Returns
+/// `false` if the id is unknown (charset unchanged) or engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_charset(charset: u8) -> bool {
+    with_engine(false, |e| e.set_charset(charset))
+}
+
+/// Get all engine settings as config text.
+///
+/// Format: one `key=value` per line (see `engine::config`), e.g.
+/// `method=0\nenabled=true\n...`. Identical on every platform, so the text
+/// can be persisted as-is and passed back to `ime_set_config`.
+///
+/// # Arguments
+/// * `out` - Buffer for the UTF-8 text (null-terminated if `max_len > 0`)
+/// * `max_len` - Size of `out` in bytes
+///
+/// # Returns
+/// Full length of the config text in bytes (excluding the terminator),
+/// like `snprintf`. If it is `>= max_len` the output was truncated; call
+/// again with a larger buffer. Returns 0 if engine not initialized.
+///
+/// # Safety
+/// `out` must be null or point to valid memory of at least `max_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_get_config(out: *mut c_char, max_len: i64) -> i64 {
+    with_engine(0, |e| copy_str(&e.config().to_text(), out, max_len))
+}
+
+/// Apply settings from config text (as produced by `ime_get_config`).
+///
+/// Missing keys fall back to defaults; unknown keys are ignored.
+///
+/// # Returns
+/// `false` if the text is invalid or selects method 3 with no table loaded
+/// (settings unchanged), or engine not initialized.
+///
+/// # Safety
+/// `text` must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_set_config(text: *const c_char) -> bool {
+    let Some(text_str) = c_str(text) else {
+        return false;
+    };
+    with_engine(false, |e| apply_config_text(e, text_str))
+}
+
+/// Clear the input buffer.
+///
+/// Call on word boundaries (space, punctuation).
+/// Preserves word history for backspace-after-space feature.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear() {
+    with_engine((), |e| e.clear())
+}
+
+/// Clear everything including word history.
+///
+/// Call when cursor position changes (mouse click, arrow keys). For focus
+/// changes prefer `ime_focus`, which keeps each context's state.
+/// This prevents accidental restore from stale history.
+/// No-op if engine not initialized.
+#[no_mangle]
+pub extern "C" fn ime_clear_all() {
+    with_engine((), |e| e.clear_all())
+}
+
+/// Switch the focus context.
+///
+/// Saves the typing state (buffer, raw keystrokes, word history, pending
+/// flags) of the current context and restores the state last seen for
+/// `context_id`, so backspace-after-space and ESC restore keep working when
+/// focus returns. Settings and shortcuts are shared by all contexts.
+/// Calling with the current id is a no-op, so hosts may call it before
+/// every key.
+///
+/// # Arguments
+/// * `context_id` - Host-defined id (window handle, text field id, ...);
+///   the engine starts in context 0
+#[no_mangle]
+pub extern "C" fn ime_focus(context_id: u64) {
+    with_engine((), |e| e.focus(context_id))
+}
+
+/// Set how many inactive contexts are remembered (default 16).
+///
+/// The least recently focused context is dropped first.
+#[no_mangle]
+pub extern "C" fn ime_context_capacity(capacity: u32) {
+    with_engine((), |e| e.set_context_capacity(capacity as usize))
+}
+
+/// Drop the saved state of a context (e.g. its window was closed).
+#[no_mangle]
+pub extern "C" fn ime_forget_context(context_id: u64) {
+    with_engine((), |e| e.forget_context(context_id))
+}
+
+/// Get the full composed buffer as UTF-32 codepoints.
+///
+/// Used for "Select All + Replace" injection method where the entire
+/// buffer content is needed instead of incremental backspace + chars.
+/// The text is in the output charset (see `ime_charset`), e.g. decomposed
+/// for NFD.
+///
+/// # Arguments
+/// * `out` - Pointer to output buffer for UTF-32 codepoints
+/// * `max_len` - Maximum number of codepoints to write
+///
+/// # Returns
+/// Number of codepoints written to `out`.
+///
+/// # Safety
+/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ime_get_buffer(out: *mut u32, max_len: i64) -> i64 {
+    if out.is_null() || max_len <= 0 {
+        return 0;
+    }
+    with_engine(0, |e| copy_buffer(e, out, max_len))
+}
+
+/// Free a result pointer returned by `ime_key`.
+///
+/// # Safety
+/// * `r` must be a pointer returned by `ime_key`, or null
+/// * Must be called exactly once per non-null `ime_key` return
+/// * Do not use `r` after calling this function
+#[no_mangle]
+pub unsafe extern "C" fn ime_free(r: *mut Result) {
+    if !r.is_null() {
+        drop(Box::from_raw(r));
+    }
+}
+
+// ============================================================
+// Shortcut FFI
+// ============================================================
+
+/// Add a shortcut to the engine.
+///
+/// # Arguments
+/// * `trigger` - C string for trigger (e.g., "vn")
+/// * `replacement` - C string for replacement (e.g., "Việt Nam")
+///
+/// # Safety
+/// Both pointers must be valid null-terminated UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn ime_add_shortcut(trigger: *const c_char, replacement: *const c_char) {
+    let (Some(trigger_str), Some(replacement_str)) = (c_str(trigger), c_str(replacement)) else {
+        return;
+    };
+    with_engine((), |e| add_shortcut(e, trigger_str, replacement_str))
+}
+
+/// Remove a shortcut from the engine.
+///
+/// # Arguments
+/// * `trigger` - C string for trigger to remove
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_remove_shortcut(trigger: *const c_char) {
+    let Some(trigger_str) = c_str(trigger) else {
+        return;
+    };
+    with_engine((), |e| {
+        e.shortcuts_mut().remove(trigger_str);
+    })
+}
+
+/// Clear all shortcuts from the engine.
+#[no_mangle]
+pub extern "C" fn ime_clear_shortcuts() {
+    with_engine((), |e| e.shortcuts_mut().clear())
+}
+
+// ============================================================
+// Word Restore FFI
+// ============================================================
+
+/// Restore buffer from a Vietnamese word string.
+///
+/// Used when native app detects cursor at word boundary and user
+/// wants to continue editing (e.g., backspace into previous word).
+/// Parses Vietnamese characters back to buffer components.
+///
+/// # Arguments
+/// * `word` - C string containing the Vietnamese word to restore
+///
+/// # Safety
+/// Pointer must be a valid null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn ime_restore_word(word: *const c_char) {
+    let Some(word_str) = c_str(word) else {
+        return;
+    };
+    with_engine((), |e| e.restore_word(word_str))
+}
+
+// ============================================================
+// Text Conversion FFI
+// ============================================================
+
+/// Convert text typed with the IME off, as if typed key by key.
+///
+/// Works without `ime_init`: a separate engine with default shortcuts is
+/// used, so the typing state of other engines is untouched.
+///
+/// # Arguments
+/// * `input` - UTF-8 text, e.g. "Tieengs Vieetj"
+/// * `method` - Input method the text was typed with (`IME_METHOD_*`)
+/// * `options` - Config text for other settings (see `ime_get_config`;
+///   its `method` is ignored), or null for defaults
+/// * `out` - Buffer for the UTF-8 result (null-terminated if `max_len > 0`)
+/// * `max_len` - Size of `out` in bytes
+///
+/// # Returns
+/// Full length of the result in bytes, like `snprintf` (truncated if
+/// `>= max_len`). Returns 0 if `input` or `options` is invalid.
+///
+/// # Safety
+/// * `input` must be a valid null-terminated UTF-8 string
+/// * `options` must be null or a valid null-terminated UTF-8 string
+/// * `out` must be null or point to valid memory of at least `max_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_convert(
+    input: *const c_char,
+    method: u8,
+    options: *const c_char,
+    out: *mut c_char,
+    max_len: i64,
+) -> i64 {
+    let Some(input) = c_str(input) else {
+        return 0;
+    };
+    let options = if options.is_null() {
+        Some(EngineConfig::default())
+    } else {
+        c_str(options).and_then(EngineConfig::parse)
+    };
+    let Some(options) = options else {
+        return 0;
+    };
+    copy_str(&convert::convert(input, method, &options), out, max_len)
+}
+
+/// Remove Vietnamese diacritics ("Việt Nam" → "Viet Nam").
+///
+/// # Arguments
+/// * `text` - UTF-8 text
+/// * `out` - Buffer for the UTF-8 result (null-terminated if `max_len > 0`)
+/// * `max_len` - Size of `out` in bytes
+///
+/// # Returns
+/// Full length of the result in bytes, like `snprintf`. Returns 0 if
+/// `text` is invalid.
+///
+/// # Safety
+/// * `text` must be a valid null-terminated UTF-8 string
+/// * `out` must be null or point to valid memory of at least `max_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_strip_diacritics(
+    text: *const c_char,
+    out: *mut c_char,
+    max_len: i64,
+) -> i64 {
+    match c_str(text) {
+        Some(text) => copy_str(&slug::strip_diacritics(text), out, max_len),
+        None => 0,
+    }
+}
+
+/// Change the case of a text, Vietnamese letters included ("đường" → "ĐƯỜNG").
+///
+/// # Arguments
+/// * `text` - UTF-8 text
+/// * `case_id` - 0=lower, 1=UPPER, 2=Title, 3=Sentence, 4=tOGGLE (`IME_CASE_*`)
+/// * `out` - Buffer for the UTF-8 result (null-terminated if `max_len > 0`)
+/// * `max_len` - Size of `out` in bytes
+///
+/// # Returns
+/// Full length of the result in bytes, like `snprintf`. Returns 0 if
+/// `text` is invalid or `case_id` unknown.
+///
+/// # Safety
+/// * `text` must be a valid null-terminated UTF-8 string
+/// * `out` must be null or point to valid memory of at least `max_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_change_case(
+    text: *const c_char,
+    case_id: u8,
+    out: *mut c_char,
+    max_len: i64,
+) -> i64 {
+    match c_str(text).and_then(|text| case::change_case(text, case_id)) {
+        Some(changed) => copy_str(&changed, out, max_len),
+        None => 0,
+    }
+}
+
+/// ASCII slug of a text ("Hồ Chí Minh" → "ho-chi-minh").
+///
+/// # Arguments
+/// * `text` - UTF-8 text
+/// * `separator` - ASCII char between words (e.g. `'-'`, `'_'`)
+/// * `lowercase` - Lowercase the slug
+/// * `out` - Buffer for the UTF-8 result (null-terminated if `max_len > 0`)
+/// * `max_len` - Size of `out` in bytes
+///
+/// # Returns
+/// Full length of the result in bytes, like `snprintf`. Returns 0 if
+/// `text` is invalid or `separator` isn't ASCII.
+///
+/// # Safety
+/// * `text` must be a valid null-terminated UTF-8 string
+/// * `out` must be null or point to valid memory of at least `max_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_slugify(
+    text: *const c_char,
+    separator: u8,
+    lowercase: bool,
+    out: *mut c_char,
+    max_len: i64,
+) -> i64 {
+    let Some(text) = c_str(text).filter(|_| separator.is_ascii()) else {
+        return 0;
+    };
+    let options = slug::SlugOptions {
+        separator: separator as char,
+        lowercase,
+        ..slug::SlugOptions::default()
+    };
+    copy_str(&slug::slugify(text, &options), out, max_len)
+}
+
+// ============================================================
+// Engine Handle FFI
+// ============================================================
+//
+// Independent engine instances for hosts with several typing contexts
+// (multiple text fields, a daemon serving several clients, ...).
+// Each handle owns its own buffer, raw input, word history, settings
+// and shortcuts. Handles are NOT internally synchronized: use one handle
+// per thread or guard it on the host side.
+//
+// Every `ime_engine_*` function is a no-op (or returns null/0) for a
+// null handle.
+
+/// Create a new engine instance.
+///
+/// # Returns
+/// Opaque handle; release it with `ime_engine_free`.
+#[no_mangle]
+pub extern "C" fn ime_engine_new() -> *mut Engine {
+    Box::into_raw(Box::new(Engine::new()))
+}
+
+/// Destroy an engine instance created by `ime_engine_new`.
+///
+/// # Safety
+/// * `handle` must be a pointer returned by `ime_engine_new`, or null
+/// * Must be called exactly once per handle; do not use `handle` afterwards
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_free(handle: *mut Engine) {
+    if !handle.is_null() {
+        set_handle_listener(handle, None);
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Handle variant of `ime_key`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_key(
+    handle: *mut Engine,
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+) -> *mut Result {
+    handle_key(handle, |e| e.on_key(key, caps, ctrl))
+}
+
+/// Handle variant of `ime_key_ext`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_key_ext(
+    handle: *mut Engine,
+    key: u16,
+    caps: bool,
+    ctrl: bool,
+    shift: bool,
+) -> *mut Result {
+    handle_key(handle, |e| e.on_key_ext(key, caps, ctrl, shift))
+}
+
+/// Handle variant of `ime_key_platform`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_key_platform(
+    handle: *mut Engine,
+    ns: u8,
+    code: u32,
+    mods: u8,
+) -> *mut Result {
+    handle_key(handle, |e| key_platform(e, ns, code, mods))
+}
+
+/// Handle variant of `ime_char`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_char(handle: *mut Engine, codepoint: u32) -> *mut Result {
+    handle_key(handle, |e| char_input(e, codepoint))
+}
+
+/// Handle variant of `ime_result_next_chunk`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_result_next_chunk(handle: *mut Engine) -> *mut Result {
+    with_handle(handle, std::ptr::null_mut(), |e| {
+        e.next_output_chunk().map_or(std::ptr::null_mut(), into_ffi)
+    })
+}
+
+/// Handle variant of `ime_output_encoding`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_output_encoding(handle: *mut Engine, encoding: u8) -> bool {
+    let Some(enc) = OutputEncoding::from_u8(encoding) else {
+        return false;
+    };
+    with_handle(handle, false, |e| {
+        e.set_output_encoding(enc);
+        true
+    })
+}
+
+/// Handle variant of `ime_result_text`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `result` must be null or a live pointer not yet passed to `ime_free`
+/// * `out` must be null or point to valid memory of at least `max_len` code units
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_result_text(
+    handle: *mut Engine,
+    result: *const Result,
+    out: *mut c_void,
+    max_len: i64,
+) -> i64 {
+    let enc = with_handle(handle, OutputEncoding::Utf32, |e| e.output_encoding());
+    copy_result(result, enc, out, max_len)
+}
+
+/// Handle variant of `ime_set_event_callback`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_set_event_callback(
+    handle: *mut Engine,
+    callback: Option<ImeEventCallback>,
+    user_data: *mut c_void,
+) {
+    if handle.is_null() {
+        return;
+    }
+    let listener = callback.map(|callback| EventListener {
+        callback,
+        user_data: user_data as usize,
+    });
+    set_handle_listener(handle, listener);
+    with_handle(handle, (), |e| e.set_events_enabled(listener.is_some()));
+}
+
+/// Handle variant of `ime_method`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_method(handle: *mut Engine, method: u8) {
+    with_handle(handle, (), |e| {
+        e.set_method(method);
+    })
+}
+
+/// Handle variant of `ime_method_table`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `table` must be a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_method_table(
+    handle: *mut Engine,
+    table: *const c_char,
+) -> bool {
+    let Some(text) = c_str(table) else {
+        return false;
+    };
+    with_handle(handle, false, |e| set_method_table(e, text))
+}
+
+/// Handle variant of `ime_key_bindings`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `bindings` must be a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_key_bindings(
+    handle: *mut Engine,
+    bindings: *const c_char,
+) -> bool {
+    let Some(text) = c_str(bindings) else {
+        return false;
+    };
+    with_handle(handle, false, |e| set_key_bindings(e, text))
+}
+
+/// Handle variant of `ime_layout`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_layout(handle: *mut Engine, layout: u8) -> bool {
+    with_handle(handle, false, |e| set_builtin_layout(e, layout))
+}
+
+/// Handle variant of `ime_custom_layout`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `map` must be a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_custom_layout(handle: *mut Engine, map: *const c_char) -> bool {
+    let Some(map_str) = c_str(map) else {
+        return false;
+    };
+    with_handle(handle, false, |e| set_custom_layout(e, map_str))
+}
+
+/// Handle variant of `ime_enabled`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_enabled(handle: *mut Engine, enabled: bool) {
+    with_handle(handle, (), |e| e.set_enabled(enabled))
+}
+
+/// Handle variant of `ime_skip_w_shortcut`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_skip_w_shortcut(handle: *mut Engine, skip: bool) {
+    with_handle(handle, (), |e| e.set_skip_w_shortcut(skip))
+}
+
+/// Handle variant of `ime_esc_restore`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_esc_restore(handle: *mut Engine, enabled: bool) {
+    with_handle(handle, (), |e| e.set_esc_restore(enabled))
+}
+
+/// Handle variant of `ime_free_tone`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_free_tone(handle: *mut Engine, enabled: bool) {
+    with_handle(handle, (), |e| e.set_free_tone(enabled))
+}
+
+/// Handle variant of `ime_modern`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_modern(handle: *mut Engine, modern: bool) {
+    with_handle(handle, (), |e| e.set_modern_tone(modern))
+}
+
+/// Handle variant of `ime_english_auto_restore`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_english_auto_restore(handle: *mut Engine, enabled: bool) {
+    with_handle(handle, (), |e| e.set_english_auto_restore(enabled))
+}
+
+/// Handle variant of `ime_auto_capitalize`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_auto_capitalize(handle: *mut Engine, enabled: bool) {
+    with_handle(handle, (), |e| e.set_auto_capitalize(enabled))
+}
+
+/// Handle variant of `ime_bracket_vowels`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_bracket_vowels(handle: *mut Engine, enabled: bool) {
+    with_handle(handle, (), |e| e.set_bracket_vowels(enabled))
+}
+
+/// Handle variant of `ime_orthography`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_orthography(handle: *mut Engine, orthography: u8) -> bool {
+    with_handle(handle, false, |e| e.set_orthography(orthography))
+}
+
+/// Handle variant of `ime_charset`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_charset(handle: *mut Engine, charset: u8) -> bool {
+    with_handle(handle, false, |e| e.set_charset(charset))
+}
+
+/// Handle variant of `ime_get_config`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `out` must be null or point to valid memory of at least `max_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_get_config(
+    handle: *mut Engine,
+    out: *mut c_char,
+    max_len: i64,
+) -> i64 {
+    with_handle(handle, 0, |e| copy_str(&e.config().to_text(), out, max_len))
+}
+
+/// Handle variant of `ime_set_config`.
+///
+/// # Safety
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `text` must be a valid null-terminated UTF-8 string
+#[no_mangle]
+pub unsafe extern "C" fn ime_engine_set_config(handle: *mut Engine, text: *const c_char) -> bool {
+    let Some(text_str) = c_str(text) else {
+        return false;
+    };
+    with_handle(handle, false, |e| apply_config_text(e, text_str))
 }
 
-/// Enable/disable English auto-restore (experimental feature).
+/// Handle variant of `ime_clear`.
 ///
-/// When `enabled` is true, automatically restores English words that were
-/// accidentally transformed (e.g., "tẽt" → "text", "ễpct" → "expect").
-/// When `enabled` is false (default), no auto-restore happens.
-/// No-op if engine not initialized.
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub extern "C" fn ime_english_auto_restore(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_english_auto_restore(enabled);
-    }
+pub unsafe extern "C" fn ime_engine_clear(handle: *mut Engine) {
+    with_handle(handle, (), |e| e.clear())
 }
 
-/// Enable/disable auto-capitalize after sentence-ending punctuation.
+/// Handle variant of `ime_clear_all`.
 ///
-/// When `enabled` is true, automatically capitalizes the first letter
-/// after sentence-ending punctuation (. ! ? Enter).
-/// When `enabled` is false (default), no auto-capitalize happens.
-/// No-op if engine not initialized.
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub extern "C" fn ime_auto_capitalize(enabled: bool) {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.set_auto_capitalize(enabled);
-    }
+pub unsafe extern "C" fn ime_engine_clear_all(handle: *mut Engine) {
+    with_handle(handle, (), |e| e.clear_all())
 }
 
-/// Clear the input buffer.
+/// Handle variant of `ime_focus`.
 ///
-/// Call on word boundaries (space, punctuation).
-/// Preserves word history for backspace-after-space feature.
-/// No-op if engine not initialized.
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub extern "C" fn ime_clear() {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.clear();
-    }
+pub unsafe extern "C" fn ime_engine_focus(handle: *mut Engine, context_id: u64) {
+    with_handle(handle, (), |e| e.focus(context_id))
 }
 
-/// Clear everything including word history.
+/// Handle variant of `ime_context_capacity`.
 ///
-/// Call when cursor position changes (mouse click, arrow keys, focus change).
-/// This prevents accidental restore from stale history.
-/// No-op if engine not initialized.
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub extern "C" fn ime_clear_all() {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.clear_all();
-    }
+pub unsafe extern "C" fn ime_engine_context_capacity(handle: *mut Engine, capacity: u32) {
+    with_handle(handle, (), |e| e.set_context_capacity(capacity as usize))
 }
 
-/// Get the full composed buffer as UTF-32 codepoints.
-///
-/// Used for "Select All + Replace" injection method where the entire
-/// buffer content is needed instead of incremental backspace + chars.
-///
-/// # Arguments
-/// * `out` - Pointer to output buffer for UTF-32 codepoints
-/// * `max_len` - Maximum number of codepoints to write
-///
-/// # Returns
-/// Number of codepoints written to `out`.
+/// Handle variant of `ime_forget_context`.
 ///
 /// # Safety
-/// `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes.
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub unsafe extern "C" fn ime_get_buffer(out: *mut u32, max_len: i64) -> i64 {
-    if out.is_null() || max_len <= 0 {
-        return 0;
-    }
-
-    let guard = lock_engine();
-    if let Some(ref e) = *guard {
-        let full = e.get_buffer_string();
-        let utf32: Vec<u32> = full.chars().map(|c| c as u32).collect();
-        let len = utf32.len().min(max_len as usize);
-        std::ptr::copy_nonoverlapping(utf32.as_ptr(), out, len);
-        len as i64
-    } else {
-        0
-    }
+pub unsafe extern "C" fn ime_engine_forget_context(handle: *mut Engine, context_id: u64) {
+    with_handle(handle, (), |e| e.forget_context(context_id))
 }
 
-/// Free a result pointer returned by `ime_key`.
+/// Handle variant of `ime_get_buffer`.
 ///
 /// # Safety
-/// * `r` must be a pointer returned by `ime_key`, or null
-/// * Must be called exactly once per non-null `ime_key` return
-/// * Do not use `r` after calling this function
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `out` must point to valid memory of at least `max_len * sizeof(u32)` bytes
 #[no_mangle]
-pub unsafe extern "C" fn ime_free(r: *mut Result) {
-    if !r.is_null() {
-        drop(Box::from_raw(r));
+pub unsafe extern "C" fn ime_engine_get_buffer(
+    handle: *mut Engine,
+    out: *mut u32,
+    max_len: i64,
+) -> i64 {
+    if out.is_null() || max_len <= 0 {
+        return 0;
     }
+    with_handle(handle, 0, |e| copy_buffer(e, out, max_len))
 }
 
-// ============================================================
-// Shortcut FFI
-// ============================================================
-
-/// Add a shortcut to the engine.
-///
-/// # Arguments
-/// * `trigger` - C string for trigger (e.g., "vn")
-/// * `replacement` - C string for replacement (e.g., "Việt Nam")
+/// Handle variant of `ime_add_shortcut`.
 ///
 /// # Safety
-/// Both pointers must be valid null-terminated UTF-8 strings.
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * Both strings must be valid null-terminated UTF-8 strings
 #[no_mangle]
-pub unsafe extern "C" fn ime_add_shortcut(
-    trigger: *const std::os::raw::c_char,
-    replacement: *const std::os::raw::c_char,
+pub unsafe extern "C" fn ime_engine_add_shortcut(
+    handle: *mut Engine,
+    trigger: *const c_char,
+    replacement: *const c_char,
 ) {
-    if trigger.is_null() || replacement.is_null() {
+    let (Some(trigger_str), Some(replacement_str)) = (c_str(trigger), c_str(replacement)) else {
         return;
-    }
-
-    let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
-    };
-    let replacement_str = match std::ffi::CStr::from_ptr(replacement).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
     };
-
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        // Auto-detect shortcut type:
-        // - If trigger contains only non-letter chars (like "->", "=>"), use immediate trigger
-        // - Otherwise use word boundary trigger (traditional abbreviations like "vn" → "Việt Nam")
-        let is_symbol_trigger = trigger_str.chars().all(|c| !c.is_alphabetic());
-        let shortcut = if is_symbol_trigger {
-            engine::shortcut::Shortcut::immediate(trigger_str, replacement_str)
-        } else {
-            engine::shortcut::Shortcut::new(trigger_str, replacement_str)
-        };
-        e.shortcuts_mut().add(shortcut);
-    }
+    with_handle(handle, (), |e| {
+        add_shortcut(e, trigger_str, replacement_str)
+    })
 }
 
-/// Remove a shortcut from the engine.
-///
-/// # Arguments
-/// * `trigger` - C string for trigger to remove
+/// Handle variant of `ime_remove_shortcut`.
 ///
 /// # Safety
-/// Pointer must be a valid null-terminated UTF-8 string.
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `trigger` must be a valid null-terminated UTF-8 string
 #[no_mangle]
-pub unsafe extern "C" fn ime_remove_shortcut(trigger: *const std::os::raw::c_char) {
-    if trigger.is_null() {
+pub unsafe extern "C" fn ime_engine_remove_shortcut(handle: *mut Engine, trigger: *const c_char) {
+    let Some(trigger_str) = c_str(trigger) else {
         return;
-    }
-
-    let trigger_str = match std::ffi::CStr::from_ptr(trigger).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
     };
-
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
+    with_handle(handle, (), |e| {
         e.shortcuts_mut().remove(trigger_str);
-    }
+    })
 }
 
-/// Clear all shortcuts from the engine.
+/// Handle variant of `ime_clear_shortcuts`.
+///
+/// # Safety
+/// `handle` must be null or a live handle from `ime_engine_new`.
 #[no_mangle]
-pub extern "C" fn ime_clear_shortcuts() {
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.shortcuts_mut().clear();
-    }
+pub unsafe extern "C" fn ime_engine_clear_shortcuts(handle: *mut Engine) {
+    with_handle(handle, (), |e| e.shortcuts_mut().clear())
 }
 
-// ============================================================
-// Word Restore FFI
-// ============================================================
-
-/// Restore buffer from a Vietnamese word string.
-///
-/// Used when native app detects cursor at word boundary and user
-/// wants to continue editing (e.g., backspace into previous word).
-/// Parses Vietnamese characters back to buffer components.
-///
-/// # Arguments
-/// * `word` - C string containing the Vietnamese word to restore
+/// Handle variant of `ime_restore_word`.
 ///
 /// # Safety
-/// Pointer must be a valid null-terminated UTF-8 string.
+/// * `handle` must be null or a live handle from `ime_engine_new`
+/// * `word` must be a valid null-terminated UTF-8 string
 #[no_mangle]
-pub unsafe extern "C" fn ime_restore_word(word: *const std::os::raw::c_char) {
-    if word.is_null() {
+pub unsafe extern "C" fn ime_engine_restore_word(handle: *mut Engine, word: *const c_char) {
+    let Some(word_str) = c_str(word) else {
         return;
-    }
-    let word_str = match std::ffi::CStr::from_ptr(word).to_str() {
-        Ok(s) => s,
-        Err(_) => return,
     };
-    let mut guard = lock_engine();
-    if let Some(ref mut e) = *guard {
-        e.restore_word(word_str);
-    }
+    with_handle(handle, (), |e| e.restore_word(word_str))
 }
 
 // ============================================================
@@ -408,6 +1687,7 @@ pub unsafe extern "C" fn ime_restore_word(word: *const std::os::raw::c_char) {
 mod tests {
     use super::*;
     use crate::data::keys;
+    use crate::engine::event;
     use serial_test::serial;
     use std::ffi::CString;
 
@@ -663,4 +1943,483 @@ mod tests {
 
         ime_clear();
     }
+
+    #[test]
+    fn test_engine_handles_are_independent() {
+        unsafe {
+            let h1 = ime_engine_new();
+            let h2 = ime_engine_new();
+            ime_engine_method(h2, 1); // VNI
+
+            // Telex on h1: a + s → á
+            ime_free(ime_engine_key_ext(h1, keys::A, false, false, false));
+            let r = ime_engine_key_ext(h1, keys::S, false, false, false);
+            assert_eq!((*r).chars[0], 'á' as u32);
+            ime_free(r);
+
+            // VNI on h2: a + 1 → á, 's' would be a plain letter
+            ime_free(ime_engine_key_ext(h2, keys::A, false, false, false));
+            let r = ime_engine_key_ext(h2, keys::N1, false, false, false);
+            assert_eq!((*r).chars[0], 'á' as u32);
+            ime_free(r);
+
+            // Each handle keeps its own buffer
+            let mut out = [0u32; 8];
+            assert_eq!(ime_engine_get_buffer(h1, out.as_mut_ptr(), 8), 1);
+            ime_engine_clear(h2);
+            assert_eq!(ime_engine_get_buffer(h2, out.as_mut_ptr(), 8), 0);
+            assert_eq!(ime_engine_get_buffer(h1, out.as_mut_ptr(), 8), 1);
+
+            ime_engine_free(h1);
+            ime_engine_free(h2);
+        }
+    }
+
+    #[test]
+    fn test_engine_handle_shortcuts_and_restore() {
+        unsafe {
+            let h1 = ime_engine_new();
+            let h2 = ime_engine_new();
+
+            let trigger = CString::new("vn").unwrap();
+            let replacement = CString::new("Việt Nam").unwrap();
+            ime_engine_add_shortcut(h1, trigger.as_ptr(), replacement.as_ptr());
+            assert_eq!((*h1).shortcuts().len(), 1);
+            assert_eq!((*h2).shortcuts().len(), 0);
+
+            ime_engine_remove_shortcut(h1, trigger.as_ptr());
+            assert_eq!((*h1).shortcuts().len(), 0);
+
+            let word = CString::new("việt").unwrap();
+            ime_engine_restore_word(h2, word.as_ptr());
+            assert_eq!((*h2).get_buffer_string(), "việt");
+            assert_eq!((*h1).get_buffer_string(), "");
+
+            ime_engine_free(h1);
+            ime_engine_free(h2);
+        }
+    }
+
+    #[test]
+    fn test_engine_handle_null_safety() {
+        unsafe {
+            let h = std::ptr::null_mut();
+            assert!(ime_engine_key_ext(h, keys::A, false, false, false).is_null());
+            ime_engine_method(h, 1);
+            ime_engine_clear_all(h);
+            ime_engine_restore_word(h, std::ptr::null());
+            let mut out = [0u32; 4];
+            assert_eq!(ime_engine_get_buffer(h, out.as_mut_ptr(), 4), 0);
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_key_platform() {
+        unsafe {
+            let h = ime_engine_new();
+            // Windows VK: 'A' (0x41) + 'S' (0x53) → á
+            ime_free(ime_engine_key_platform(h, 1, 0x41, 0));
+            let r = ime_engine_key_platform(h, 1, 0x53, 0);
+            assert_eq!((*r).chars[0], 'á' as u32);
+            ime_free(r);
+            ime_engine_clear(h);
+
+            // evdev with Shift: KEY_D (32) twice → Đ
+            ime_free(ime_engine_key_platform(h, 3, 32, 0x04));
+            let r = ime_engine_key_platform(h, 3, 32, 0x04);
+            assert_eq!((*r).chars[0], 'Đ' as u32);
+            ime_free(r);
+
+            // Unknown namespace passes through
+            let r = ime_engine_key_platform(h, 99, 0x41, 0);
+            assert_eq!((*r).action, 0);
+            ime_free(r);
+
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_char_ffi() {
+        ime_init();
+        ime_method(0); // Telex
+
+        unsafe { ime_free(ime_char('V' as u32)) };
+        unsafe { ime_free(ime_char('i' as u32)) };
+        unsafe { ime_free(ime_char('e' as u32)) };
+        let r = ime_char('e' as u32);
+        unsafe {
+            assert_eq!((*r).action, 1);
+            assert_eq!((*r).chars[0], 'ê' as u32);
+            ime_free(r);
+        }
+
+        // Invalid codepoint passes through
+        let r = ime_char(0xD800);
+        unsafe {
+            assert_eq!((*r).action, 0);
+            ime_free(r);
+        }
+
+        ime_clear();
+    }
+
+    #[test]
+    fn test_engine_layout_ffi() {
+        unsafe {
+            let h = ime_engine_new();
+            assert!(ime_engine_layout(h, 2)); // Colemak
+            assert!(!ime_engine_layout(h, 99));
+
+            // Colemak: QWERTY 'A' position = a, QWERTY 'D' position = s (sắc)
+            ime_free(ime_engine_key_ext(h, keys::A, false, false, false));
+            let r = ime_engine_key_ext(h, keys::D, false, false, false);
+            assert_eq!((*r).chars[0], 'á' as u32);
+            ime_free(r);
+
+            let bad = CString::new("qwerty").unwrap();
+            assert!(!ime_engine_custom_layout(h, bad.as_ptr()));
+            let qwerty = CString::new(data::layout::QWERTY_ORDER).unwrap();
+            assert!(ime_engine_custom_layout(h, qwerty.as_ptr()));
+
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_method_table_ffi() {
+        unsafe {
+            let h = ime_engine_new();
+            let table = CString::new("sac = q\nhorn = w").unwrap();
+            assert!(ime_engine_method_table(h, table.as_ptr()));
+            assert_eq!((*h).config().method, input::id::CUSTOM);
+            assert_eq!(utils::type_word(&mut *h, "aq"), "á");
+
+            // Invalid table keeps the current method
+            let bad = CString::new("sac = q\nhoi = q").unwrap();
+            assert!(!ime_engine_method_table(h, bad.as_ptr()));
+            assert_eq!(utils::type_word(&mut *h, " uw"), " ư");
+
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_key_bindings_ffi() {
+        unsafe {
+            let h = ime_engine_new();
+            let bindings = CString::new("huyen:q nang:").unwrap();
+            assert!(ime_engine_key_bindings(h, bindings.as_ptr()));
+            assert_eq!(utils::type_word(&mut *h, "aq af aj"), "à af aj");
+
+            // `s` still types sắc, so it can't take huyền
+            let clash = CString::new("huyen:s").unwrap();
+            assert!(!ime_engine_key_bindings(h, clash.as_ptr()));
+            assert_eq!((*h).key_bindings().to_text(), "huyen:q nang:");
+
+            let reset = CString::new("").unwrap();
+            assert!(ime_engine_key_bindings(h, reset.as_ptr()));
+            assert_eq!(utils::type_word(&mut *h, " af"), " à");
+
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_orthography_ffi() {
+        unsafe {
+            let h = ime_engine_new();
+            assert_eq!(utils::type_word(&mut *h, "ev cca"), "ev cca");
+            assert!(ime_engine_orthography(h, 1));
+            assert_eq!(utils::type_word(&mut *h, " ev cca"), " ĕ ča");
+            assert!(!ime_engine_orthography(h, 9));
+            assert_eq!((*h).orthography(), 1);
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_charset_ffi() {
+        unsafe {
+            let h = ime_engine_new();
+            assert!(ime_engine_charset(h, 2));
+            let r = &*ime_engine_key(h, keys::A, false, false);
+            assert_eq!(r.action, 0);
+            let r = &*ime_engine_key(h, keys::S, false, false);
+            assert_eq!((r.backspace, r.count), (1, 2));
+            assert_eq!(r.to_utf8(), "aù");
+            // Backspace deletes both bytes of "aù"
+            let r = &*ime_engine_key(h, keys::DELETE, false, false);
+            assert_eq!((r.action, r.backspace), (1, 2));
+            assert!(!ime_engine_charset(h, 9));
+            assert_eq!((*h).charset(), 2);
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_convert_ffi() {
+        unsafe {
+            let input = CString::new("hoaf Vieetj").unwrap();
+            let options = CString::new("modern_tone=false\n").unwrap();
+            let len = ime_convert(input.as_ptr(), 0, options.as_ptr(), std::ptr::null_mut(), 0);
+            let mut out = vec![0 as c_char; len as usize + 1];
+            ime_convert(
+                input.as_ptr(),
+                0,
+                options.as_ptr(),
+                out.as_mut_ptr(),
+                len + 1,
+            );
+            let read = std::ffi::CStr::from_ptr(out.as_ptr()).to_str().unwrap();
+            assert_eq!(read, "hòa Việt");
+            // Null options: defaults
+            let n = ime_convert(
+                input.as_ptr(),
+                0,
+                std::ptr::null(),
+                out.as_mut_ptr(),
+                len + 1,
+            );
+            assert_eq!(n, len);
+            let bad = CString::new("modern_tone=maybe").unwrap();
+            assert_eq!(
+                ime_convert(input.as_ptr(), 0, bad.as_ptr(), out.as_mut_ptr(), 8),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn test_strip_diacritics_ffi() {
+        unsafe {
+            let text = CString::new("Hồ Chí Minh").unwrap();
+            let mut out = vec![0 as c_char; 32];
+            assert_eq!(
+                ime_strip_diacritics(text.as_ptr(), out.as_mut_ptr(), 32),
+                11
+            );
+            let read = std::ffi::CStr::from_ptr(out.as_ptr()).to_str().unwrap();
+            assert_eq!(read, "Ho Chi Minh");
+            assert_eq!(
+                ime_slugify(text.as_ptr(), b'_', true, out.as_mut_ptr(), 32),
+                11
+            );
+            let read = std::ffi::CStr::from_ptr(out.as_ptr()).to_str().unwrap();
+            assert_eq!(read, "ho_chi_minh");
+            assert_eq!(
+                ime_slugify(text.as_ptr(), 0xC3, true, out.as_mut_ptr(), 32),
+                0
+            );
+        }
+    }
+
+    #[test]
+    fn test_change_case_ffi() {
+        unsafe {
+            let text = CString::new("đường đến trường").unwrap();
+            let mut out = vec![0 as c_char; 32];
+            let len = ime_change_case(text.as_ptr(), case::id::TITLE, out.as_mut_ptr(), 32);
+            let read = std::ffi::CStr::from_ptr(out.as_ptr()).to_str().unwrap();
+            assert_eq!(read, "Đường Đến Trường");
+            assert_eq!(len, read.len() as i64);
+            assert_eq!(ime_change_case(text.as_ptr(), 9, out.as_mut_ptr(), 32), 0);
+        }
+    }
+
+    #[test]
+    fn test_engine_config_ffi() {
+        unsafe {
+            let h = ime_engine_new();
+            let text = CString::new("method=1\nmodern_tone=false\nlayout=colemak\n").unwrap();
+            assert!(ime_engine_set_config(h, text.as_ptr()));
+            assert_eq!((*h).config().method, 1);
+
+            // Query length first, then read
+            let len = ime_engine_get_config(h, std::ptr::null_mut(), 0);
+            let mut out = vec![0 as c_char; len as usize + 1];
+            assert_eq!(ime_engine_get_config(h, out.as_mut_ptr(), len + 1), len);
+            let read = std::ffi::CStr::from_ptr(out.as_ptr()).to_str().unwrap();
+            assert!(read.contains("method=1\n"));
+            assert!(read.contains("modern_tone=false\n"));
+            assert!(read.contains("layout=colemak\n"));
+
+            // Truncated output stays null-terminated
+            let mut small = [1 as c_char; 4];
+            assert_eq!(ime_engine_get_config(h, small.as_mut_ptr(), 4), len);
+            assert_eq!(small[3], 0);
+
+            // Invalid text leaves settings unchanged
+            let bad = CString::new("method=x").unwrap();
+            assert!(!ime_engine_set_config(h, bad.as_ptr()));
+            assert_eq!((*h).config().method, 1);
+
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_result_chunks() {
+        unsafe {
+            let h = ime_engine_new();
+            let long = "Trân trọng cảm ơn quý khách đã sử dụng dịch vụ của chúng tôi. Chúc quý khách một ngày tốt lành!";
+            let trigger = CString::new("tt").unwrap();
+            let replacement = CString::new(long).unwrap();
+            ime_engine_add_shortcut(h, trigger.as_ptr(), replacement.as_ptr());
+
+            ime_free(ime_engine_key(h, keys::T, false, false));
+            ime_free(ime_engine_key(h, keys::T, false, false));
+            let r = ime_engine_key(h, keys::SPACE, false, false);
+            assert!(!r.is_null());
+            assert_eq!((*r).backspace, 2);
+            assert!((*r).has_more_output());
+            let mut text: String = (*r).output().collect();
+            ime_free(r);
+
+            loop {
+                let chunk = ime_engine_result_next_chunk(h);
+                assert!(!chunk.is_null());
+                assert_eq!((*chunk).backspace, 0);
+                text.extend((*chunk).output());
+                let more = (*chunk).has_more_output();
+                ime_free(chunk);
+                if !more {
+                    break;
+                }
+            }
+            assert_eq!(text, format!("{} ", long));
+            assert!(ime_engine_result_next_chunk(h).is_null());
+
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_result_encodings() {
+        unsafe {
+            let h = ime_engine_new();
+            let trigger = CString::new("cuoi").unwrap();
+            let replacement = CString::new("Vệ 😀").unwrap();
+            ime_engine_add_shortcut(h, trigger.as_ptr(), replacement.as_ptr());
+            for key in [keys::C, keys::U, keys::O, keys::I] {
+                ime_free(ime_engine_key(h, key, false, false));
+            }
+            let r = ime_engine_key(h, keys::SPACE, false, false);
+
+            // UTF-8: "Vệ 😀 " = 1 + 3 + 1 + 4 + 1 bytes
+            let mut out8 = [0 as c_char; 16];
+            assert_eq!(ime_result_utf8(r, out8.as_mut_ptr(), 16), 10);
+            let s = std::ffi::CStr::from_ptr(out8.as_ptr()).to_str().unwrap();
+            assert_eq!(s, "Vệ 😀 ");
+
+            // UTF-16: emoji is a surrogate pair
+            let mut out16 = [0u16; 16];
+            assert_eq!(ime_result_utf16(r, out16.as_mut_ptr(), 16), 6);
+            assert_eq!(&out16[..7], &[0x56, 0x1EC7, 0x20, 0xD83D, 0xDE00, 0x20, 0]);
+
+            // Truncation never splits the pair (room for 4 units + terminator)
+            let mut small = [0xFFFFu16; 5];
+            assert_eq!(ime_result_utf16(r, small.as_mut_ptr(), 5), 6);
+            assert_eq!(&small, &[0x56, 0x1EC7, 0x20, 0, 0xFFFF]);
+
+            // Engine option selects the unit size of ime_engine_result_text
+            assert!(!ime_engine_output_encoding(h, 9));
+            assert!(ime_engine_output_encoding(h, 2));
+            let mut text = [0u16; 16];
+            assert_eq!(
+                ime_engine_result_text(h, r, text.as_mut_ptr().cast(), 16),
+                6
+            );
+            assert_eq!(text, out16);
+
+            assert_eq!(ime_result_utf8(std::ptr::null(), out8.as_mut_ptr(), 16), 0);
+            ime_free(r);
+            ime_engine_free(h);
+        }
+    }
+
+    extern "C" fn collect_event(event: *const ImeEvent, user_data: *mut c_void) {
+        unsafe {
+            let events = &mut *(user_data as *mut Vec<(u8, String, String)>);
+            let ev = &*event;
+            let text = std::ffi::CStr::from_ptr(ev.text)
+                .to_string_lossy()
+                .into_owned();
+            let detail = std::ffi::CStr::from_ptr(ev.detail)
+                .to_string_lossy()
+                .into_owned();
+            events.push((ev.kind, text, detail));
+        }
+    }
+
+    #[test]
+    fn test_engine_event_callback() {
+        unsafe {
+            let h = ime_engine_new();
+            let mut events: Vec<(u8, String, String)> = Vec::new();
+            let user_data = &mut events as *mut _ as *mut c_void;
+            ime_engine_set_event_callback(h, Some(collect_event), user_data);
+
+            let trigger = CString::new("vn").unwrap();
+            let replacement = CString::new("Việt Nam").unwrap();
+            ime_engine_add_shortcut(h, trigger.as_ptr(), replacement.as_ptr());
+
+            for key in [
+                keys::V,
+                keys::I,
+                keys::E,
+                keys::E,
+                keys::T,
+                keys::J,
+                keys::SPACE,
+            ] {
+                ime_free(ime_engine_key(h, key, false, false));
+            }
+            for key in [keys::V, keys::N, keys::SPACE] {
+                ime_free(ime_engine_key(h, key, false, false));
+            }
+            assert_eq!(
+                events,
+                vec![
+                    (event::kind::COMMIT, "việt".into(), "vieetj".into()),
+                    (event::kind::SHORTCUT, "Việt Nam".into(), "vn".into()),
+                ]
+            );
+
+            // Unregistered: no more events
+            ime_engine_set_event_callback(h, None, std::ptr::null_mut());
+            for key in [keys::A, keys::SPACE] {
+                ime_free(ime_engine_key(h, key, false, false));
+            }
+            assert_eq!(events.len(), 2);
+
+            ime_engine_free(h);
+        }
+    }
+
+    #[test]
+    fn test_engine_focus_ffi() {
+        unsafe {
+            let h = ime_engine_new();
+            ime_engine_focus(h, 0x1001);
+            for key in [keys::A, keys::A] {
+                ime_free(ime_engine_key(h, key, false, false));
+            }
+            let mut buf = [0u32; 8];
+            ime_engine_focus(h, 0x2002);
+            assert_eq!(ime_engine_get_buffer(h, buf.as_mut_ptr(), 8), 0);
+
+            ime_engine_focus(h, 0x1001);
+            assert_eq!(ime_engine_get_buffer(h, buf.as_mut_ptr(), 8), 1);
+            assert_eq!(buf[0], 'â' as u32);
+
+            ime_engine_forget_context(h, 0x2002);
+            ime_engine_context_capacity(h, 0);
+            assert_eq!((*h).saved_contexts(), 0);
+
+            ime_engine_free(h);
+        }
+    }
 }
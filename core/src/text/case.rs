@@ -0,0 +1,134 @@
+//! Case Transformation
+//!
+//! lower, UPPER, Title, Sentence and tOGGLE case for selected text.
+//! Vietnamese letters change case with their diacritics (đ ↔ Đ, ự ↔ Ự);
+//! decomposed (NFD) marks follow their base letter.
+//!
+//! Sentence boundaries are shared with the engine's auto-capitalize:
+//! `. ! ? …` and line breaks end a sentence, spaces, quotes and brackets
+//! after them are neutral, anything else starts the next word normally.
+
+use crate::data::chars;
+
+/// Case ids (`change_case`, FFI `ime_change_case`)
+pub mod id {
+    pub const LOWER: u8 = 0;
+    pub const UPPER: u8 = 1;
+    /// First letter of each word uppercase, the rest lowercase
+    pub const TITLE: u8 = 2;
+    /// First letter of each sentence uppercase, the rest lowercase
+    pub const SENTENCE: u8 = 3;
+    /// Swap the case of every letter
+    pub const TOGGLE: u8 = 4;
+}
+
+/// Case names (index = id)
+pub const NAMES: [&str; 5] = ["lower", "upper", "title", "sentence", "toggle"];
+
+/// Whether `c` ends a sentence (the next letter is capitalized)
+pub fn is_sentence_ending(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '…' | '\n' | '\r')
+}
+
+/// Whether `c` may stand between a sentence end and the next sentence
+/// without cancelling its capital (". \"Tôi", "! (Và")
+pub fn is_sentence_neutral(c: char) -> bool {
+    " \t'\"()[]{}“”‘’«»".contains(c)
+}
+
+/// Text in the case `case_id` (None for unknown ids)
+pub fn change_case(text: &str, case_id: u8) -> Option<String> {
+    match case_id {
+        id::LOWER => Some(text.to_lowercase()),
+        id::UPPER => Some(text.to_uppercase()),
+        id::TITLE => Some(to_title(text)),
+        id::SENTENCE => Some(to_sentence(text)),
+        id::TOGGLE => Some(toggle(text)),
+        _ => None,
+    }
+}
+
+/// Title Case: each word starts with a capital ("hồ chí minh" → "Hồ Chí Minh")
+///
+/// Apostrophes stay inside words ("m'nông" → "M'nông").
+pub fn to_title(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_word = false;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            push_case(&mut out, c, !in_word);
+            in_word = true;
+        } else {
+            out.push(c);
+            in_word &= chars::is_combining(c) || matches!(c, '\'' | '’');
+        }
+    }
+    out
+}
+
+/// Sentence case: each sentence starts with a capital
+pub fn to_sentence(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut capitalize = true;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            push_case(&mut out, c, capitalize);
+            capitalize = false;
+        } else {
+            out.push(c);
+            if is_sentence_ending(c) {
+                capitalize = true;
+            } else if !is_sentence_neutral(c) && !chars::is_combining(c) {
+                capitalize = false;
+            }
+        }
+    }
+    out
+}
+
+/// tOGGLE cASE: every letter swaps case
+pub fn toggle(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        push_case(&mut out, c, c.is_lowercase());
+    }
+    out
+}
+
+fn push_case(out: &mut String, c: char, upper: bool) {
+    if upper {
+        out.extend(c.to_uppercase());
+    } else {
+        out.extend(c.to_lowercase());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_case() {
+        let text = "đường Đến ƯỚC mơ";
+        assert_eq!(change_case(text, id::LOWER).unwrap(), "đường đến ước mơ");
+        assert_eq!(change_case(text, id::UPPER).unwrap(), "ĐƯỜNG ĐẾN ƯỚC MƠ");
+        assert_eq!(change_case(text, id::TITLE).unwrap(), "Đường Đến Ước Mơ");
+        assert_eq!(change_case(text, id::TOGGLE).unwrap(), "ĐƯỜNG đẾN ước MƠ");
+        assert_eq!(change_case(text, 5), None);
+    }
+
+    #[test]
+    fn test_sentence() {
+        assert_eq!(
+            to_sentence("xin chào. \"tôi là AI?\" (không!) vâng, ĐÚNG"),
+            "Xin chào. \"Tôi là ai?\" (Không!) Vâng, đúng"
+        );
+        assert_eq!(to_sentence("một\nhai… ba"), "Một\nHai… Ba");
+    }
+
+    #[test]
+    fn test_title_apostrophe_and_nfd() {
+        assert_eq!(to_title("m'nông ê-đê"), "M'nông Ê-Đê");
+        assert_eq!(to_title("vie\u{323}\u{302}t nam"), "Vie\u{323}\u{302}t Nam");
+    }
+}
@@ -0,0 +1,11 @@
+//! Text Utilities
+//!
+//! Operations on existing Vietnamese text (a selection, a filename, a
+//! document) rather than on keystrokes:
+//! - `case`: lower/UPPER/Title/Sentence/toggle case
+//! - `slug`: Diacritic stripping and URL/filename slugs
+//! - `tone_style`: Modern/traditional tone mark placement
+
+pub mod case;
+pub mod slug;
+pub mod tone_style;
@@ -0,0 +1,106 @@
+//! Diacritic Stripping and Slugs
+//!
+//! "Việt Nam" → "Viet Nam", "Hồ Chí Minh" → "ho-chi-minh". Vietnamese
+//! letters lose their tone and mark (đ → d, highlands ĕ č → e c),
+//! composed or decomposed (NFD); other chars are kept.
+
+use crate::data::chars::{self, mark, tone};
+use crate::utils;
+
+/// Slug settings (`slugify`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlugOptions {
+    /// Replaces every run of non-alphanumeric chars
+    pub separator: char,
+    pub lowercase: bool,
+    /// Maximum length in chars, cut at a word boundary (0 = no limit)
+    pub max_len: usize,
+}
+
+impl Default for SlugOptions {
+    fn default() -> Self {
+        Self {
+            separator: '-',
+            lowercase: true,
+            max_len: 0,
+        }
+    }
+}
+
+/// Text with Vietnamese diacritics removed
+pub fn strip_diacritics(text: &str) -> String {
+    text.chars()
+        .filter(|&c| !chars::is_combining(c))
+        .map(|c| match chars::parse_char(c) {
+            Some(p) if !c.is_ascii() => chars::to_char(p.key, p.caps, tone::NONE, mark::NONE)
+                .or_else(|| utils::key_to_char(p.key, p.caps))
+                .unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// ASCII slug of `text` for URLs, filenames and search keys
+///
+/// Diacritics are stripped, runs of anything but ASCII letters and digits
+/// become one separator, and separators at either end are dropped.
+pub fn slugify(text: &str, options: &SlugOptions) -> String {
+    let stripped = strip_diacritics(text);
+    let words = stripped
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty());
+    let mut slug = String::with_capacity(stripped.len());
+    let mut len = 0;
+    for word in words {
+        let sep = (len > 0) as usize;
+        if options.max_len > 0 && len + sep + word.len() > options.max_len {
+            // A first word longer than the limit is cut rather than dropped
+            if len == 0 {
+                slug.push_str(&word[..options.max_len]);
+            }
+            break;
+        }
+        if sep > 0 {
+            slug.push(options.separator);
+        }
+        slug.push_str(word);
+        len += sep + word.len();
+    }
+    if options.lowercase {
+        slug.make_ascii_lowercase();
+    }
+    slug
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_diacritics() {
+        assert_eq!(strip_diacritics("Việt Nam"), "Viet Nam");
+        assert_eq!(strip_diacritics("ĐƯỜNG đi"), "DUONG di");
+        assert_eq!(strip_diacritics("Vie\u{323}\u{302}t"), "Viet");
+        assert_eq!(strip_diacritics("Ơ\u{306}ñĕ\u{301} čă"), "One ca");
+        assert_eq!(strip_diacritics("café ✓ 100%"), "cafe ✓ 100%");
+    }
+
+    #[test]
+    fn test_slugify() {
+        let options = SlugOptions::default();
+        assert_eq!(slugify("Hồ Chí Minh", &options), "ho-chi-minh");
+        assert_eq!(slugify("  Đà Nẵng -- 2024! ", &options), "da-nang-2024");
+        assert_eq!(slugify("✓✓", &options), "");
+        let custom = SlugOptions {
+            separator: '_',
+            lowercase: false,
+            max_len: 12,
+        };
+        assert_eq!(slugify("Thành phố Hồ Chí Minh", &custom), "Thanh_pho_Ho");
+        let short = SlugOptions {
+            max_len: 4,
+            ..SlugOptions::default()
+        };
+        assert_eq!(slugify("Nghiêng ngả", &short), "nghi");
+    }
+}
@@ -0,0 +1,134 @@
+//! Tone Style Normalization
+//!
+//! Moves tone marks of existing text to one placement style, so a document
+//! typed over several sessions doesn't mix "hòa" and "hoà":
+//! - modern: hoà, thuỷ, khoẻ (mark on the second vowel of oa, oe, uy)
+//! - traditional: hòa, thủy, khỏe
+//!
+//! Each word is parsed as a syllable and its mark position recomputed with
+//! `Phonology::find_tone_position`. Only syllables marked in the other
+//! style's position change; words that aren't valid Vietnamese (English,
+//! numbers, typos) are copied unchanged.
+
+use crate::data::chars;
+use crate::data::charset;
+use crate::data::vowel::Phonology;
+use crate::engine::buffer::{self, Buffer, Char};
+use crate::engine::validation;
+use crate::utils;
+
+/// Text with every syllable's tone mark in the `modern` (or traditional) position
+pub fn normalize_tone_style(text: &str, modern: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() || chars::is_combining(c) {
+            word.push(c);
+            continue;
+        }
+        push_word(&mut out, &word, modern);
+        word.clear();
+        out.push(c);
+    }
+    push_word(&mut out, &word, modern);
+    out
+}
+
+fn push_word(out: &mut String, word: &str, modern: bool) {
+    match restyle(word, modern) {
+        // Decomposed input stays decomposed
+        Some(s) if word.chars().any(chars::is_combining) => {
+            out.push_str(&charset::encode(&s, charset::id::NFD))
+        }
+        Some(s) => out.push_str(&s),
+        None => out.push_str(word),
+    }
+}
+
+/// `word` with its mark moved to the `modern` style position, or None if
+/// it already is there (or isn't a marked Vietnamese syllable)
+fn restyle(word: &str, modern: bool) -> Option<String> {
+    let mut buf = Buffer::new();
+    let mut marked = None;
+    for (i, c) in chars::compose(word).chars().enumerate() {
+        let p = chars::parse_char(c)?;
+        if i >= buffer::MAX || (p.mark != 0 && marked.is_some()) {
+            return None;
+        }
+        if p.mark != 0 {
+            marked = Some(i);
+        }
+        buf.push(Char {
+            key: p.key,
+            caps: p.caps,
+            tone: p.tone,
+            mark: p.mark,
+            stroke: p.stroke,
+        });
+    }
+    let marked = marked?;
+
+    let keys: Vec<u16> = buf.iter().map(|c| c.key).collect();
+    let tones: Vec<u8> = buf.iter().map(|c| c.tone).collect();
+    if !validation::is_valid_with_tones(&keys, &tones) {
+        return None;
+    }
+    let vowels = utils::collect_vowels(&buf);
+    let last_vowel_pos = vowels.last()?.pos;
+    let has_final = utils::has_final_consonant(&buf, last_vowel_pos);
+    let has_qu = utils::has_qu_initial(&buf);
+    let has_gi = utils::has_gi_initial(&buf);
+    let position =
+        |modern| Phonology::find_tone_position(&vowels, has_final, modern, has_qu, has_gi);
+    let pos = position(modern);
+    // Marks neither style uses ("hóan") are typos, left alone
+    if pos == marked || position(!modern) != marked {
+        return None;
+    }
+
+    let mark = buf.get(marked)?.mark;
+    buf.get_mut(marked)?.mark = 0;
+    buf.get_mut(pos)?.mark = mark;
+    Some(buf.to_full_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize_tone_style("hòa thủy khỏe", true), "hoà thuỷ khoẻ");
+        assert_eq!(
+            normalize_tone_style("hoà thuỷ khoẻ", false),
+            "hòa thủy khỏe"
+        );
+        // Same in both styles
+        assert_eq!(
+            normalize_tone_style("hoàn quý người", true),
+            "hoàn quý người"
+        );
+        assert_eq!(
+            normalize_tone_style("hoàn quý người", false),
+            "hoàn quý người"
+        );
+    }
+
+    #[test]
+    fn test_case_and_foreign_words() {
+        assert_eq!(
+            normalize_tone_style("HÒA Bình, Thủy-lợi 2024!", true),
+            "HOÀ Bình, Thuỷ-lợi 2024!"
+        );
+        assert_eq!(normalize_tone_style("café hòa2 óa", true), "café hòa2 oá");
+    }
+
+    #[test]
+    fn test_decomposed() {
+        let nfd = charset::encode("hòa", charset::id::NFD);
+        assert_eq!(
+            normalize_tone_style(&nfd, true),
+            charset::encode("hoà", charset::id::NFD)
+        );
+    }
+}
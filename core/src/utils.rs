@@ -55,7 +55,7 @@ pub fn key_to_char(key: u16, caps: bool) -> Option<char> {
 }
 
 /// Convert key code to character with shift state support
-/// Handles shifted symbols like @ (Shift+2), # (Shift+3), etc.
+/// Handles shifted symbols like @ (Shift+2), # (Shift+3), etc. and punctuation
 pub fn key_to_char_ext(key: u16, caps: bool, shift: bool) -> Option<char> {
     // If shift is pressed, check for shifted symbols first
     if shift {
@@ -84,7 +84,20 @@ pub fn key_to_char_ext(key: u16, caps: bool, shift: bool) -> Option<char> {
             _ => key_to_char(key, caps),
         };
     }
-    key_to_char(key, caps)
+    match key {
+        keys::MINUS => Some('-'),
+        keys::EQUAL => Some('='),
+        keys::SEMICOLON => Some(';'),
+        keys::QUOTE => Some('\''),
+        keys::COMMA => Some(','),
+        keys::DOT => Some('.'),
+        keys::SLASH => Some('/'),
+        keys::BACKSLASH => Some('\\'),
+        keys::LBRACKET => Some('['),
+        keys::RBRACKET => Some(']'),
+        keys::BACKQUOTE => Some('`'),
+        _ => key_to_char(key, caps),
+    }
 }
 
 /// Collect vowels from buffer with phonological info
@@ -395,6 +408,26 @@ mod test_utils {
         }
     }
 
+    /// Run VIQR test cases
+    pub fn viqr(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            let mut e = Engine::new();
+            e.set_method(2);
+            let result = type_word(&mut e, input);
+            assert_eq!(result, *expected, "[VIQR] '{}' → '{}'", input, result);
+        }
+    }
+
+    /// Run direct layout test cases (number row types ă â ê ô đ and marks)
+    pub fn direct(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            let mut e = Engine::new();
+            e.set_method(5);
+            let result = type_word(&mut e, input);
+            assert_eq!(result, *expected, "[Direct] '{}' → '{}'", input, result);
+        }
+    }
+
     /// Run Telex test cases with traditional tone placement (hòa, thúy style)
     pub fn telex_traditional(cases: &[(&str, &str)]) {
         for (input, expected) in cases {
@@ -424,6 +457,49 @@ mod test_utils {
         }
     }
 
+    /// Run Telex test cases with bracket vowels ([ → ơ, ] → ư)
+    pub fn telex_brackets(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            let mut e = Engine::new();
+            e.set_bracket_vowels(true);
+            let result = type_word(&mut e, input);
+            assert_eq!(
+                result, *expected,
+                "[Telex Brackets] '{}' → '{}'",
+                input, result
+            );
+        }
+    }
+
+    /// Run Telex + VNI test cases (keys of either scheme)
+    pub fn telex_vni(cases: &[(&str, &str)]) {
+        for (input, expected) in cases {
+            let mut e = Engine::new();
+            e.set_method(4);
+            let result = type_word(&mut e, input);
+            assert_eq!(result, *expected, "[Telex+VNI] '{}' → '{}'", input, result);
+        }
+    }
+
+    /// Run Telex test cases with key bindings (`KeyBindings::parse` text)
+    pub fn telex_bindings(bindings: &str, cases: &[(&str, &str)]) {
+        let parsed = crate::input::KeyBindings::parse(bindings).expect("bindings");
+        for (input, expected) in cases {
+            let mut e = Engine::new();
+            assert!(
+                e.set_key_bindings(parsed.clone()),
+                "[{}] rejected",
+                bindings
+            );
+            let result = type_word(&mut e, input);
+            assert_eq!(
+                result, *expected,
+                "[Telex {}] '{}' → '{}'",
+                bindings, input, result
+            );
+        }
+    }
+
     /// Simulate typing with extended parameters (supports raw mode prefix)
     /// Input format: use special prefixes to trigger shift+key:
     /// - "@" triggers Shift+2
@@ -0,0 +1,297 @@
+//! ABI Tests - `include/gonhanh.h` must match the exported functions
+//!
+//! Parses every `#[no_mangle] extern "C"` function in the FFI sources,
+//! renders the C prototype it implies, and compares with the header.
+//! When this fails, copy the "expected" prototypes into the header.
+
+use gonhanh_core::data::charset;
+use gonhanh_core::data::keymap::{modifier, KeyNamespace};
+use gonhanh_core::data::layout;
+use gonhanh_core::data::orthography;
+use gonhanh_core::engine::buffer::MAX;
+use gonhanh_core::engine::encoding::OutputEncoding;
+use gonhanh_core::engine::event;
+use gonhanh_core::engine::{Action, Result, FLAG_KEY_CONSUMED, FLAG_MORE_OUTPUT};
+use gonhanh_core::input;
+use gonhanh_core::text::case;
+use gonhanh_core::{capability, ABI_VERSION};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Files containing `#[no_mangle]` exports
+const FFI_SOURCES: [&str; 2] = ["src/lib.rs", "src/updater/mod.rs"];
+
+const HEADER: &str = "include/gonhanh.h";
+
+fn read(path: &str) -> String {
+    let full = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path);
+    std::fs::read_to_string(&full).unwrap_or_else(|e| panic!("{}: {}", full, e))
+}
+
+/// Rust FFI type → C type (with trailing space or `*` so the name can follow)
+fn c_type(rust: &str) -> &'static str {
+    match rust {
+        "" | "()" => "void ",
+        "bool" => "bool ",
+        "u8" => "uint8_t ",
+        "u16" => "uint16_t ",
+        "u32" => "uint32_t ",
+        "u64" => "uint64_t ",
+        "i32" => "int32_t ",
+        "i64" => "int64_t ",
+        "*const c_char" | "*const i8" => "const char *",
+        "*mut c_char" => "char *",
+        "*mut u16" => "uint16_t *",
+        "*mut u32" => "uint32_t *",
+        "*mut c_void" => "void *",
+        "*mut Result" => "ImeResult *",
+        "*const Result" => "const ImeResult *",
+        "*mut Engine" => "ImeEngine *",
+        "Option<ImeEventCallback>" => "ImeEventCallback ",
+        other => panic!("no C mapping for FFI type `{}`", other),
+    }
+}
+
+fn normalize(proto: &str) -> String {
+    proto
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace(" ,", ",")
+}
+
+/// C prototypes of all `#[no_mangle]` functions, keyed by name
+fn rust_exports() -> BTreeMap<String, String> {
+    let mut exports = BTreeMap::new();
+    for path in FFI_SOURCES {
+        let src = read(path);
+        for (i, _) in src.match_indices("#[no_mangle]") {
+            let rest = &src[i..];
+            let rest = &rest[rest.find("fn ").unwrap()..];
+            let fn_start = 3;
+            let open = rest.find('(').unwrap();
+            let close = rest.find(')').unwrap();
+            let body = rest.find('{').unwrap();
+            let name = rest[fn_start..open].trim();
+            let ret = rest[close + 1..body].trim().trim_start_matches("->").trim();
+
+            let params: Vec<String> = rest[open + 1..close]
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(|p| {
+                    let (pname, ty) = p.split_once(':').unwrap();
+                    format!("{}{}", c_type(ty.trim()), pname.trim())
+                })
+                .collect();
+            let params = if params.is_empty() {
+                "void".to_string()
+            } else {
+                params.join(", ")
+            };
+            let proto = format!("{}{}({})", c_type(ret), name, params);
+            exports.insert(name.to_string(), normalize(&proto));
+        }
+    }
+    exports
+}
+
+/// Header with comments and preprocessor lines removed
+fn header_code() -> String {
+    let mut src = read(HEADER);
+    while let Some(start) = src.find("/*") {
+        let end = src[start..].find("*/").unwrap() + start + 2;
+        src.replace_range(start..end, " ");
+    }
+    src.lines()
+        .map(|l| l.split("//").next().unwrap())
+        .filter(|l| !l.trim_start().starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Function prototypes declared in the header, keyed by name
+fn header_prototypes() -> BTreeMap<String, String> {
+    header_code()
+        .split(';')
+        .map(normalize)
+        .filter(|s| s.contains('(') && !s.starts_with("typedef"))
+        .map(|proto| {
+            // Strip `extern "C" {` wrapper left over from C++ guards
+            let proto = proto.rsplit('{').next().unwrap().trim().to_string();
+            let name = proto[..proto.find('(').unwrap()]
+                .rsplit([' ', '*'])
+                .next()
+                .unwrap()
+                .to_string();
+            (name, proto)
+        })
+        .collect()
+}
+
+/// `#define NAME <number>` values from the header
+fn header_defines() -> BTreeMap<String, u64> {
+    read(HEADER)
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("#define "))
+        .filter_map(|l| {
+            let mut parts = l.split_whitespace();
+            let name = parts.next()?;
+            let value = parts.next()?.trim_end_matches(['u', 'U']);
+            let value = match value.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+                None => value.parse().ok()?,
+            };
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+#[test]
+fn header_declares_every_export() {
+    let exports = rust_exports();
+    let header = header_prototypes();
+
+    let mut errors = Vec::new();
+    for (name, expected) in &exports {
+        match header.get(name) {
+            None => errors.push(format!("missing in header: {};", expected)),
+            Some(actual) if actual != expected => errors.push(format!(
+                "signature mismatch:\n  header:   {};\n  expected: {};",
+                actual, expected
+            )),
+            _ => {}
+        }
+    }
+    let exported: BTreeSet<_> = exports.keys().collect();
+    for name in header.keys().filter(|n| !exported.contains(n)) {
+        errors.push(format!("declared in header but not exported: {}", name));
+    }
+    assert!(
+        errors.is_empty(),
+        "{} out of sync:\n{}",
+        HEADER,
+        errors.join("\n")
+    );
+}
+
+#[test]
+fn header_constants_match() {
+    let defines = header_defines();
+    let expect = |name: &str, value: u64| {
+        assert_eq!(defines.get(name), Some(&value), "{} in {}", name, HEADER);
+    };
+
+    expect("GONHANH_ABI_VERSION", ABI_VERSION as u64);
+    expect("IME_RESULT_MAX_CHARS", MAX as u64);
+    expect("IME_FLAG_KEY_CONSUMED", FLAG_KEY_CONSUMED as u64);
+    expect("IME_FLAG_MORE_OUTPUT", FLAG_MORE_OUTPUT as u64);
+
+    expect("IME_ACTION_NONE", Action::None as u64);
+    expect("IME_ACTION_SEND", Action::Send as u64);
+    expect("IME_ACTION_RESTORE", Action::Restore as u64);
+
+    expect("IME_NS_MAC_VK", KeyNamespace::MacVk as u64);
+    expect("IME_NS_WIN_VK", KeyNamespace::WinVk as u64);
+    expect("IME_NS_X11_KEYSYM", KeyNamespace::X11Keysym as u64);
+    expect("IME_NS_EVDEV", KeyNamespace::Evdev as u64);
+    expect("IME_MOD_CAPS", modifier::CAPS as u64);
+    expect("IME_MOD_CTRL", modifier::CTRL as u64);
+    expect("IME_MOD_SHIFT", modifier::SHIFT as u64);
+
+    expect("IME_METHOD_TELEX", input::id::TELEX as u64);
+    expect("IME_METHOD_VNI", input::id::VNI as u64);
+    expect("IME_METHOD_VIQR", input::id::VIQR as u64);
+    expect("IME_METHOD_CUSTOM", input::id::CUSTOM as u64);
+    expect("IME_METHOD_TELEX_VNI", input::id::TELEX_VNI as u64);
+    expect("IME_METHOD_DIRECT", input::id::DIRECT as u64);
+
+    expect("IME_ORTHOGRAPHY_STANDARD", orthography::id::STANDARD as u64);
+    expect(
+        "IME_ORTHOGRAPHY_HIGHLANDS",
+        orthography::id::HIGHLANDS as u64,
+    );
+
+    expect("IME_CHARSET_UNICODE", charset::id::UNICODE as u64);
+    expect("IME_CHARSET_TCVN3", charset::id::TCVN3 as u64);
+    expect("IME_CHARSET_VNI_WIN", charset::id::VNI_WIN as u64);
+    expect("IME_CHARSET_VISCII", charset::id::VISCII as u64);
+    expect("IME_CHARSET_NFD", charset::id::NFD as u64);
+    expect("IME_CHARSET_VIQR", charset::id::VIQR as u64);
+
+    expect("IME_CASE_LOWER", case::id::LOWER as u64);
+    expect("IME_CASE_UPPER", case::id::UPPER as u64);
+    expect("IME_CASE_TITLE", case::id::TITLE as u64);
+    expect("IME_CASE_SENTENCE", case::id::SENTENCE as u64);
+    expect("IME_CASE_TOGGLE", case::id::TOGGLE as u64);
+
+    expect("IME_LAYOUT_QWERTY", layout::id::QWERTY as u64);
+    expect("IME_LAYOUT_DVORAK", layout::id::DVORAK as u64);
+    expect("IME_LAYOUT_COLEMAK", layout::id::COLEMAK as u64);
+    expect("IME_LAYOUT_AZERTY", layout::id::AZERTY as u64);
+    expect("IME_LAYOUT_QWERTZ", layout::id::QWERTZ as u64);
+
+    expect("IME_ENCODING_UTF32", OutputEncoding::Utf32 as u64);
+    expect("IME_ENCODING_UTF8", OutputEncoding::Utf8 as u64);
+    expect("IME_ENCODING_UTF16", OutputEncoding::Utf16 as u64);
+
+    expect("IME_EVENT_COMMIT", event::kind::COMMIT as u64);
+    expect("IME_EVENT_AUTO_RESTORE", event::kind::AUTO_RESTORE as u64);
+    expect("IME_EVENT_ESC_RESTORE", event::kind::ESC_RESTORE as u64);
+    expect("IME_EVENT_SHORTCUT", event::kind::SHORTCUT as u64);
+    expect(
+        "IME_EVENT_AUTO_CAPITALIZE",
+        event::kind::AUTO_CAPITALIZE as u64,
+    );
+
+    let caps = [
+        ("IME_CAP_SHORTCUTS", capability::SHORTCUTS),
+        ("IME_CAP_AUTO_RESTORE", capability::AUTO_RESTORE),
+        ("IME_CAP_ESC_RESTORE", capability::ESC_RESTORE),
+        ("IME_CAP_AUTO_CAPITALIZE", capability::AUTO_CAPITALIZE),
+        ("IME_CAP_PREEDIT", capability::PREEDIT),
+        ("IME_CAP_RESTORE_WORD", capability::RESTORE_WORD),
+        ("IME_CAP_ENGINE_HANDLES", capability::ENGINE_HANDLES),
+        ("IME_CAP_PLATFORM_KEYS", capability::PLATFORM_KEYS),
+        ("IME_CAP_CHAR_INPUT", capability::CHAR_INPUT),
+        ("IME_CAP_LAYOUTS", capability::LAYOUTS),
+        ("IME_CAP_CONFIG", capability::CONFIG),
+        ("IME_CAP_OUTPUT_CHUNKS", capability::OUTPUT_CHUNKS),
+        ("IME_CAP_OUTPUT_ENCODING", capability::OUTPUT_ENCODING),
+        ("IME_CAP_EVENTS", capability::EVENTS),
+        ("IME_CAP_FOCUS_CONTEXTS", capability::FOCUS_CONTEXTS),
+        ("IME_CAP_METHOD_TABLES", capability::METHOD_TABLES),
+        ("IME_CAP_BRACKET_VOWELS", capability::BRACKET_VOWELS),
+        ("IME_CAP_KEY_BINDINGS", capability::KEY_BINDINGS),
+        ("IME_CAP_ORTHOGRAPHY", capability::ORTHOGRAPHY),
+        ("IME_CAP_CHARSETS", capability::CHARSETS),
+        ("IME_CAP_NFD", capability::NFD),
+        ("IME_CAP_CONVERT", capability::CONVERT),
+        ("IME_CAP_VIQR_CHARSET", capability::VIQR_CHARSET),
+        ("IME_CAP_STRIP_DIACRITICS", capability::STRIP_DIACRITICS),
+        ("IME_CAP_CHANGE_CASE", capability::CHANGE_CASE),
+    ];
+    let mut all = 0;
+    for (name, flag) in caps {
+        expect(name, flag as u64);
+        all |= flag;
+    }
+    assert_eq!(all, capability::ALL, "capability list incomplete");
+}
+
+#[test]
+fn result_layout_matches_header() {
+    // uint32_t chars[MAX] + 4 x uint8_t
+    assert_eq!(std::mem::size_of::<Result>(), MAX * 4 + 4);
+    assert_eq!(std::mem::align_of::<Result>(), 4);
+    // uint8_t kind + 2 pointers
+    let ptr = std::mem::size_of::<usize>();
+    assert_eq!(std::mem::size_of::<gonhanh_core::ImeEvent>(), 3 * ptr);
+}
+
+#[test]
+fn abi_version_exported() {
+    assert_eq!(gonhanh_core::ime_abi_version(), ABI_VERSION);
+    assert_eq!(gonhanh_core::ime_capabilities(), capability::ALL);
+}
@@ -0,0 +1,269 @@
+//! Charset Tests - legacy 8-bit output (TCVN3, VNI-Windows, VISCII), NFD,
+//! VIQR and conversion of existing text
+//!
+//! The screen here holds code units: one backspace deletes one char, as in
+//! a document using a legacy font, or one code point of NFD text.
+
+use gonhanh_core::data::charset::{self, id};
+use gonhanh_core::data::keys;
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::input;
+use gonhanh_core::utils::char_to_key;
+
+/// Type `input` ('<' = Backspace) and return the screen in code units
+fn type_units(e: &mut Engine, input: &str) -> String {
+    let mut screen: Vec<char> = Vec::new();
+    for c in input.chars() {
+        let key = char_to_key(c);
+        let r = e.on_key(key, c.is_uppercase(), false);
+        if r.action == Action::Send as u8 {
+            screen.truncate(screen.len().saturating_sub(r.backspace as usize));
+            screen.extend(r.output());
+            let typed_after = key != keys::SPACE && keys::is_break(key) && !r.key_consumed();
+            if typed_after {
+                screen.push(c);
+            }
+        } else if key == keys::DELETE {
+            screen.pop();
+        } else {
+            screen.push(c);
+        }
+    }
+    screen.into_iter().collect()
+}
+
+fn run(method: u8, charset_id: u8, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(method);
+        e.set_charset(charset_id);
+        let screen = type_units(&mut e, input);
+        assert_eq!(
+            screen,
+            *expected,
+            "[{}] '{}' → {:?}",
+            charset::NAMES[charset_id as usize],
+            input,
+            screen
+        );
+    }
+}
+
+// ============================================================
+// OUTPUT
+// ============================================================
+
+const VNI_WIN: &[(&str, &str)] = &[
+    ("vieetj nam", "vieät nam"),
+    ("dduwowcj", "ñöôïc"),
+    ("ddaf nawngx", "ñaø naüng"),
+    ("chir thij", "chæ thò"),
+    ("Vieetj", "Vieät"),
+    // Mark moved to another letter: both bytes of the old one are replaced
+    ("hoaf", "hoaø"),
+    ("tieengs vieetj", "tieáng vieät"),
+];
+
+const TCVN3: &[(&str, &str)] = &[
+    ("vieetj", "vi\u{D6}t"),
+    ("dduwowcj", "\u{AE}\u{AD}\u{EE}c"),
+    ("Ddaf", "\u{A7}\u{B5}"),
+];
+
+const VISCII: &[(&str, &str)] = &[
+    ("vieetj", "vi\u{AE}t"),
+    ("Vieetj", "Vi\u{AE}t"),
+    ("VIEETJ", "VI\u{8E}T"),
+];
+
+/// Backspace removes whole letters, so editing keeps working
+const VNI_WIN_BACKSPACE: &[(&str, &str)] = &[
+    ("as<", ""),
+    ("bas<e", "be"),
+    ("vieetj<<", "vi"),
+    ("vieetj<<eef", "vieà"),
+    // Backspace-after-space restores the word, then edits it
+    ("vieet <s", "vieát"),
+];
+
+#[test]
+fn vni_win_output() {
+    run(input::id::TELEX, id::VNI_WIN, VNI_WIN);
+    run(
+        input::id::VNI,
+        id::VNI_WIN,
+        &[("vie65t", "vieät"), ("d9a2", "ñaø")],
+    );
+}
+
+#[test]
+fn tcvn3_output() {
+    run(input::id::TELEX, id::TCVN3, TCVN3);
+}
+
+#[test]
+fn viscii_output() {
+    run(input::id::TELEX, id::VISCII, VISCII);
+}
+
+#[test]
+fn vni_win_backspace() {
+    run(input::id::TELEX, id::VNI_WIN, VNI_WIN_BACKSPACE);
+}
+
+/// Letters decomposed: base + combining diacritics in canonical order
+const NFD: &[(&str, &str)] = &[
+    ("as", "a\u{301}"),
+    ("vieetj", "vie\u{323}\u{302}t"),
+    ("dduwowcj", "đu\u{31B}o\u{31B}\u{323}c"),
+    ("Tieengs", "Tie\u{302}\u{301}ng"),
+    // Backspace removes the base and all its marks
+    ("vieetj<<", "vi"),
+    ("hoaf<", "ho"),
+    ("vieet <s", "vie\u{302}\u{301}t"),
+];
+
+#[test]
+fn nfd_output() {
+    run(input::id::TELEX, id::NFD, NFD);
+    run(input::id::VNI, id::NFD, &[("a61", "a\u{302}\u{301}")]);
+}
+
+#[test]
+fn nfd_backspace_counts() {
+    let mut e = Engine::new();
+    e.set_charset(id::NFD);
+    e.on_key(keys::E, false, false);
+    let r = e.on_key(keys::E, false, false);
+    assert_eq!((r.backspace, r.to_utf8()), (1, "e\u{302}".to_string()));
+    // ê is two code points on screen
+    let r = e.on_key(keys::S, false, false);
+    assert_eq!(
+        (r.backspace, r.to_utf8()),
+        (2, "e\u{302}\u{301}".to_string())
+    );
+    let r = e.on_key(keys::DELETE, false, false);
+    assert_eq!((r.action, r.backspace), (Action::Send as u8, 3));
+}
+
+#[test]
+fn viqr_output() {
+    run(
+        input::id::TELEX,
+        id::VIQR,
+        &[
+            ("vieetj", "vie^.t"),
+            ("dduwowcj", "ddu+o+.c"),
+            ("vieetj<<", "vi"),
+            ("vieet <s", "vie^'t"),
+        ],
+    );
+}
+
+#[test]
+fn unicode_unchanged() {
+    run(
+        input::id::TELEX,
+        id::UNICODE,
+        &[("vieetj", "việt"), ("as<", "")],
+    );
+}
+
+// ============================================================
+// INPUT
+// ============================================================
+
+#[test]
+fn restore_word_legacy() {
+    let mut e = Engine::new();
+    e.set_charset(id::VNI_WIN);
+    e.restore_word("Vieät");
+    assert_eq!(e.get_buffer_string(), "Việt");
+    // The restored word is on screen: editing it replaces both bytes
+    let r = e.on_key(keys::F, false, false);
+    assert_eq!(r.backspace, 3);
+    assert_eq!(r.to_utf8(), "eàt");
+
+    e.set_charset(id::TCVN3);
+    e.restore_word("\u{AE}\u{AD}\u{EE}c");
+    assert_eq!(e.get_buffer_string(), "được");
+
+    e.set_charset(id::VISCII);
+    e.restore_word("Vi\u{AE}t");
+    assert_eq!(e.get_buffer_string(), "Việt");
+}
+
+#[test]
+fn restore_word_nfd() {
+    // Decomposed text is read in any charset
+    let mut e = Engine::new();
+    e.restore_word("Vie\u{323}\u{302}t");
+    assert_eq!(e.get_buffer_string(), "Việt");
+    let r = e.on_key(keys::F, false, false);
+    assert_eq!(r.backspace, 2);
+    assert_eq!(r.to_utf8(), "ềt");
+
+    e.set_charset(id::NFD);
+    e.restore_word("đu\u{31B}o\u{31B}\u{323}c");
+    assert_eq!(e.get_buffer_string(), "được");
+    assert_eq!(e.get_buffer_output(), "đu\u{31B}o\u{31B}\u{323}c");
+    // Restored word is on screen decomposed
+    let r = e.on_key(keys::S, false, false);
+    assert_eq!(r.backspace, 4);
+    assert_eq!(r.to_utf8(), "o\u{31B}\u{301}c");
+}
+
+#[test]
+fn parse_char_legacy() {
+    let p = charset::parse_char('\u{D6}', id::TCVN3).unwrap();
+    assert_eq!(p.key, keys::E);
+    assert_eq!(charset::parse_char('ö', id::VNI_WIN).unwrap().key, keys::U);
+    assert_eq!(
+        charset::parse_char('\u{AE}', id::VISCII).unwrap().key,
+        keys::E
+    );
+}
+
+#[test]
+fn restore_word_viqr() {
+    let mut e = Engine::new();
+    e.set_charset(id::VIQR);
+    e.restore_word("Vie^.t");
+    assert_eq!(e.get_buffer_string(), "Việt");
+    let r = e.on_key(keys::F, false, false);
+    assert_eq!((r.backspace, r.to_utf8()), (4, "e^`t".to_string()));
+}
+
+// ============================================================
+// CONVERSION OF EXISTING TEXT
+// ============================================================
+
+#[test]
+fn convert_documents() {
+    let text = "Cộng hoà xã hội chủ nghĩa Việt Nam";
+    let cases = [
+        (
+            id::TCVN3,
+            "C\u{E9}ng ho\u{B5} x\u{B7} h\u{E9}i ch\u{F1} ngh\u{DC}a Vi\u{D6}t Nam",
+        ),
+        (id::VNI_WIN, "Coäng hoaø xaõ hoäi chuû nghóa Vieät Nam"),
+        (id::VIQR, "Co^.ng hoa` xa~ ho^.i chu? nghi~a Vie^.t Nam"),
+    ];
+    for (charset_id, encoded) in cases {
+        let to = charset::convert_charset(text, id::UNICODE, charset_id).unwrap();
+        assert_eq!(to.text, encoded);
+        let back = charset::convert_charset(encoded, charset_id, id::UNICODE).unwrap();
+        assert_eq!(back.text, text);
+    }
+    // Between two legacy charsets
+    let r = charset::convert_charset("Vieät", id::VNI_WIN, id::TCVN3).unwrap();
+    assert_eq!(r.text, "Vi\u{D6}t");
+}
+
+#[test]
+fn convert_reports_unconvertible() {
+    let r = charset::convert_charset("Đăk Lăk ĕ ñ ✓", id::UNICODE, id::VNI_WIN).unwrap();
+    assert_eq!(r.text, "Ñaêk Laêk ĕ ñ ✓");
+    assert_eq!(r.unconvertible, ['ĕ', 'ñ', '✓']);
+    assert!(charset::convert_charset("a", 7, id::UNICODE).is_none());
+}
@@ -0,0 +1,194 @@
+//! Convert Tests - offline conversion of whole texts and keystrokes of words
+
+use gonhanh_core::data::charset;
+use gonhanh_core::engine::config::EngineConfig;
+use gonhanh_core::engine::convert::{convert, convert_with, to_keystrokes};
+use gonhanh_core::engine::shortcut::Shortcut;
+use gonhanh_core::engine::Engine;
+use gonhanh_core::input::id;
+
+fn defaults() -> EngineConfig {
+    EngineConfig::default()
+}
+
+#[test]
+fn paragraph_telex() {
+    let options = EngineConfig {
+        english_auto_restore: true,
+        ..defaults()
+    };
+    let input = "Tooi ddax thuwr raats nhieeuf booj gox. Gox \"aaa\" thanhf \"aâ\", deadline gaaps thif burnout laf cais chawcs! Inbox nhatkha1407@gmail.com nha.";
+    let expected = "Tôi đã thử rất nhiều bộ gõ. Gõ \"aa\" thành \"aâ\", deadline gấp thì burnout là cái chắc! Inbox nhatkha1407@gmail.com nha.";
+    assert_eq!(convert(input, id::TELEX, &options), expected);
+}
+
+#[test]
+fn paragraph_vni() {
+    let input =
+        "To6i d9a4 thu73 ra61t nhie62u bo65 go4 tie61ng Vie65t, kho6ng stress ve62 typo nu7a4.";
+    let expected = "Tôi đã thử rất nhiều bộ gõ tiếng Việt, không stress về typo nữa.";
+    assert_eq!(convert(input, id::VNI, &defaults()), expected);
+}
+
+#[test]
+fn lines_and_tabs() {
+    assert_eq!(
+        convert("xin chaof\n\tVieetj Nam\r\n", id::TELEX, &defaults()),
+        "xin chào\n\tViệt Nam\r\n"
+    );
+}
+
+#[test]
+fn unicode_text_kept() {
+    // Already-Vietnamese text and emoji are copied unchanged
+    assert_eq!(
+        convert("Việt Nam 🇻🇳 vaf ddepj", id::TELEX, &defaults()),
+        "Việt Nam 🇻🇳 và đẹp"
+    );
+}
+
+#[test]
+fn last_word_auto_restore() {
+    let options = EngineConfig {
+        english_auto_restore: true,
+        ..defaults()
+    };
+    assert_eq!(convert("expect", id::TELEX, &options), "expect");
+    assert_eq!(convert("hoaf", id::TELEX, &options), "hoà");
+}
+
+#[test]
+fn options_applied() {
+    let traditional = EngineConfig {
+        modern_tone: false,
+        ..defaults()
+    };
+    assert_eq!(convert("hoaf", id::TELEX, &traditional), "hòa");
+    // Method argument wins over options
+    let vni = EngineConfig {
+        method: id::VNI,
+        ..defaults()
+    };
+    assert_eq!(convert("as", id::TELEX, &vni), "á");
+    let nfd = EngineConfig {
+        charset: charset::id::NFD,
+        ..defaults()
+    };
+    assert_eq!(convert("Vieetj", id::TELEX, &nfd), "Vie\u{323}\u{302}t");
+}
+
+#[test]
+fn shortcuts() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("vn", "Việt Nam"));
+    // Longer than one Result: the rest comes in output chunks
+    let long = "Cộng hoà xã hội chủ nghĩa Việt Nam - Độc lập, Tự do, Hạnh phúc";
+    e.shortcuts_mut().add(Shortcut::new("qh", long));
+    assert_eq!(
+        convert_with(&mut e, "tooi yeeu vn qh"),
+        format!("tôi yêu Việt Nam {}", long)
+    );
+    // End of input finishes the last word; punctuation doesn't trigger
+    assert_eq!(convert_with(&mut e, "ddi vn"), "đi Việt Nam");
+    assert_eq!(convert_with(&mut e, "vn."), "vn.");
+}
+
+// ============================================================
+// KEYSTROKES (reverse conversion)
+// ============================================================
+
+/// Words whose tone placement is the same in both styles
+const WORDS: &[&str] = &[
+    "người",
+    "rượu",
+    "hươu",
+    "thuở",
+    "huơ",
+    "giường",
+    "giữ",
+    "gì",
+    "quá",
+    "quốc",
+    "khuyến",
+    "khuỷu",
+    "tuần",
+    "Việt",
+    "ĐƯỜNG",
+    "Đắk",
+    "boong",
+    "xoong",
+    "ăn",
+    "ư",
+    "đ",
+    "oải",
+    "nghiêng",
+    "chuyển",
+    "uyên",
+    "quyết",
+    "Nguyễn",
+];
+
+#[test]
+fn keystrokes_canonical() {
+    let cases = [
+        ("người", "nguwowif", "ngu7o7i2"),
+        ("Việt", "Vieetj", "Vie6t5"),
+        ("đường", "dduwowngf", "d9u7o7ng2"),
+        ("ăn", "awn", "a8n"),
+        ("boong", "booong", "boong"),
+        ("ĐẮK", "DDAWKS", "D9A8K1"),
+    ];
+    for (word, telex, vni) in cases {
+        assert_eq!(to_keystrokes(word, id::TELEX, true).as_deref(), Some(telex));
+        assert_eq!(to_keystrokes(word, id::VNI, true).as_deref(), Some(vni));
+    }
+}
+
+#[test]
+fn keystrokes_round_trip() {
+    for method in [id::TELEX, id::VNI, id::TELEX_VNI] {
+        for modern in [true, false] {
+            let options = EngineConfig {
+                modern_tone: modern,
+                ..defaults()
+            };
+            for word in WORDS {
+                let keys = to_keystrokes(word, method, modern)
+                    .unwrap_or_else(|| panic!("[{}] no keys for '{}'", method, word));
+                assert_eq!(convert(&keys, method, &options), *word, "keys '{}'", keys);
+            }
+        }
+    }
+}
+
+#[test]
+fn keystrokes_tone_style() {
+    // Modern and traditional place the mark differently
+    assert_eq!(
+        to_keystrokes("hoà", id::TELEX, true).as_deref(),
+        Some("hoaf")
+    );
+    assert_eq!(to_keystrokes("hoà", id::TELEX, false), None);
+    assert_eq!(
+        to_keystrokes("hòa", id::TELEX, false).as_deref(),
+        Some("hoaf")
+    );
+    assert_eq!(
+        to_keystrokes("thủy", id::VNI, false).as_deref(),
+        Some("thuy3")
+    );
+    assert_eq!(to_keystrokes("thủy", id::VNI, true), None);
+}
+
+#[test]
+fn keystrokes_unsupported() {
+    // Not a word, not Vietnamese, highlands letters, other methods
+    assert_eq!(to_keystrokes("a b", id::TELEX, true), None);
+    assert_eq!(to_keystrokes("ĕ", id::TELEX, true), None);
+    assert_eq!(to_keystrokes("Việt", id::VIQR, true), None);
+    // Decomposed input is accepted
+    assert_eq!(
+        to_keystrokes("Vie\u{323}\u{302}t", id::TELEX, true).as_deref(),
+        Some("Vieetj")
+    );
+}
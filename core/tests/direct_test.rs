@@ -0,0 +1,101 @@
+//! Direct Layout Tests - Number row types letters and marks
+
+mod common;
+use common::{direct, type_word};
+use gonhanh_core::engine::Engine;
+use gonhanh_core::input::id;
+
+// ============================================================
+// LETTERS & MARKS
+// ============================================================
+
+const DIRECT_LETTERS: &[(&str, &str)] = &[
+    ("1", "ă"),
+    ("2", "â"),
+    ("3", "ê"),
+    ("4", "ô"),
+    ("0", "đ"),
+    ("[", "ư"),
+    ("]", "ơ"),
+    // Shift gives capitals
+    ("!", "Ă"),
+    ("{", "Ư"),
+];
+
+const DIRECT_MARKS: &[(&str, &str)] = &[
+    ("a8", "á"),
+    ("a5", "à"),
+    ("a6", "ả"),
+    ("a7", "ã"),
+    ("a9", "ạ"),
+    ("28", "ấ"),
+    ("]7", "ỡ"),
+];
+
+// ============================================================
+// WORDS
+// ============================================================
+
+const DIRECT_WORDS: &[(&str, &str)] = &[
+    ("vi3t9", "việt"),
+    ("t4i8", "tối"),
+    ("0i", "đi"),
+    ("0[]ng5", "đường"),
+    ("ng[]i5", "người"),
+    ("gi[a7", "giữa"),
+    ("kh4ng", "không"),
+    ("chu4ng", "chuông"),
+    ("qu3", "quê"),
+    ("thu]6", "thuở"),
+    ("x1n", "xăn"),
+    ("Vi3t9 Nam", "Việt Nam"),
+];
+
+// Mark placed before the last vowel moves like in Telex
+const DIRECT_REPOSITION: &[(&str, &str)] = &[("h]8i", "hới"), ("hoa5", "hoà")];
+
+// ============================================================
+// KEYS THAT DON'T FIT
+// ============================================================
+
+// No letter fits: the key types its own character
+const DIRECT_LITERAL: &[(&str, &str)] = &[
+    ("ba1", "ba1"),
+    ("12", "ă2"),
+    ("00", "đ0"),
+    ("ku3", "ku3"),
+    ("2024 ", "â024 "),
+];
+
+#[test]
+fn direct_letters() {
+    direct(DIRECT_LETTERS);
+}
+
+#[test]
+fn direct_marks() {
+    direct(DIRECT_MARKS);
+}
+
+#[test]
+fn direct_words() {
+    direct(DIRECT_WORDS);
+}
+
+#[test]
+fn direct_reposition() {
+    direct(DIRECT_REPOSITION);
+}
+
+#[test]
+fn direct_literal() {
+    direct(DIRECT_LITERAL);
+}
+
+#[test]
+fn direct_esc_restore() {
+    let mut e = Engine::new();
+    e.set_method(id::DIRECT);
+    e.set_esc_restore(true);
+    assert_eq!(type_word(&mut e, "vi3t9\x1b"), "vi3t9");
+}
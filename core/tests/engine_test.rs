@@ -1,8 +1,16 @@
 //! Engine Tests - Syllable parsing, validation, and transformation
 
 mod common;
-use common::{telex, vni};
-use gonhanh_core::engine::Engine;
+use common::{telex, telex_bindings, telex_brackets, telex_vni, vni};
+use gonhanh_core::data::keymap::KeyNamespace;
+use gonhanh_core::data::keys;
+use gonhanh_core::data::layout::{self, KeyboardLayout};
+use gonhanh_core::engine::config::EngineConfig;
+use gonhanh_core::engine::event::Event;
+use gonhanh_core::engine::shortcut::{InputMethod, Shortcut};
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::input::{id, KeyBindings, TableMethod};
+use gonhanh_core::utils::type_word;
 
 // ============================================================
 // SYLLABLE PARSING TESTS
@@ -691,3 +699,636 @@ fn delayed_circumflex_no_false_positives() {
         result2
     );
 }
+
+// ============================================================
+// CHARACTER INPUT (on_char)
+// ============================================================
+
+/// Feed characters through `on_char`, returns screen output
+fn type_chars(e: &mut Engine, input: &str) -> String {
+    let mut screen = String::new();
+    for c in input.chars() {
+        let r = e.on_char(c);
+        if r.action == Action::Send as u8 {
+            for _ in 0..r.backspace {
+                screen.pop();
+            }
+            screen.extend((0..r.count as usize).filter_map(|i| char::from_u32(r.chars[i])));
+            if c.is_ascii_punctuation() && !r.key_consumed() {
+                screen.push(c);
+            }
+        } else if c == '\x08' {
+            screen.pop();
+        } else {
+            screen.push(c);
+        }
+    }
+    screen
+}
+
+#[test]
+fn on_char_matches_key_input() {
+    let cases = [
+        ("Vieejt Nam", "Việt Nam"),
+        ("dduowcj", "được"),
+        ("tooi\x08\x08i", "ti"),
+        ("hoaf?", "hoà?"),
+        ("Khoong!", "Không!"),
+    ];
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        assert_eq!(type_chars(&mut e, input), expected, "on_char '{}'", input);
+    }
+}
+
+#[test]
+fn on_char_vni_shifted_symbols() {
+    // Shift+2 resolves to '@' and must not apply huyền
+    let mut e = Engine::new();
+    e.set_method(1);
+    assert_eq!(type_chars(&mut e, "a@"), "a@");
+    let mut e = Engine::new();
+    e.set_method(1);
+    assert_eq!(type_chars(&mut e, "a2"), "à");
+}
+
+#[test]
+fn on_char_foreign_character_ends_word() {
+    let mut e = Engine::new();
+    assert_eq!(type_chars(&mut e, "aéa"), "aéa");
+    assert_eq!(e.get_buffer_string(), "a");
+}
+
+// ============================================================
+// KEYBOARD LAYOUT REMAPPING
+// ============================================================
+
+/// Type physical QWERTY positions with a logical layout active, returns composed buffer
+/// (screen output from `type_word` would echo the physical chars on pass-through)
+fn type_with_layout(layout_id: u8, physical: &str) -> String {
+    let mut e = Engine::new();
+    e.set_layout(KeyboardLayout::builtin(layout_id).unwrap());
+    type_word(&mut e, physical);
+    e.get_buffer_string()
+}
+
+#[test]
+fn layout_colemak_telex() {
+    // Colemak "vieejt" is typed on QWERTY positions "vlkkyf"
+    assert_eq!(type_with_layout(layout::id::COLEMAK, "vlkkyf"), "việt");
+    // Colemak "as" is typed on QWERTY "ad"
+    assert_eq!(type_with_layout(layout::id::COLEMAK, "ad"), "á");
+}
+
+#[test]
+fn layout_azerty_telex() {
+    // AZERTY "aa" → QWERTY "qq", "mooi" → ";ooi"
+    assert_eq!(type_with_layout(layout::id::AZERTY, "qq"), "â");
+    assert_eq!(type_with_layout(layout::id::AZERTY, ";ooi"), "môi");
+}
+
+#[test]
+fn layout_dvorak_telex() {
+    // Dvorak "dd" → QWERTY "hh", Dvorak "o" → QWERTY "s", Dvorak "w" → QWERTY ","
+    assert_eq!(type_with_layout(layout::id::DVORAK, "hhs,"), "đơ");
+}
+
+#[test]
+fn layout_skips_windows_letter_vk() {
+    // Windows letter VK codes already follow the active layout
+    for layout_id in [layout::id::AZERTY, layout::id::DVORAK] {
+        let mut e = Engine::new();
+        e.set_layout(KeyboardLayout::builtin(layout_id).unwrap());
+        for vk in "DDAAS".bytes() {
+            e.on_key_platform(KeyNamespace::WinVk, vk as u32, 0);
+        }
+        assert_eq!(e.get_buffer_string(), "đấ");
+    }
+}
+
+#[test]
+fn layout_does_not_affect_on_char() {
+    let mut e = Engine::new();
+    e.set_layout(KeyboardLayout::builtin(layout::id::COLEMAK).unwrap());
+    e.on_char('a');
+    let r = e.on_char('s');
+    assert_eq!(r.chars[0], 'á' as u32);
+}
+
+// ============================================================
+// CONFIG
+// ============================================================
+
+#[test]
+fn config_defaults_match_new_engine() {
+    assert_eq!(Engine::new().config(), EngineConfig::default());
+}
+
+#[test]
+fn apply_config_roundtrip() {
+    let config = EngineConfig::parse("method=1\nfree_tone=true\nlayout=dvorak\n").unwrap();
+    let mut e = Engine::new();
+    e.apply_config(&config);
+    assert_eq!(e.config(), config);
+    assert_eq!(EngineConfig::parse(&e.config().to_text()), Some(config));
+}
+
+// ============================================================
+// EVENTS
+// ============================================================
+
+fn events_for(e: &mut Engine, input: &str) -> Vec<Event> {
+    e.set_events_enabled(true);
+    type_word(e, input);
+    e.take_events()
+}
+
+#[test]
+fn event_commit_on_space_and_break() {
+    let mut e = Engine::new();
+    let events = events_for(&mut e, "vieetj nam,");
+    assert_eq!(
+        events,
+        vec![
+            Event::Commit {
+                text: "việt".into(),
+                raw: "vieetj".into()
+            },
+            Event::Commit {
+                text: "nam".into(),
+                raw: "nam".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn event_auto_restore() {
+    let mut e = Engine::new();
+    e.set_english_auto_restore(true);
+    let events = events_for(&mut e, "text ");
+    assert_eq!(
+        events,
+        vec![
+            Event::AutoRestore {
+                from: "tẽt".into(),
+                to: "text".into()
+            },
+            Event::Commit {
+                text: "text".into(),
+                raw: "text".into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn event_esc_restore() {
+    let mut e = Engine::new();
+    e.set_esc_restore(true);
+    let events = events_for(&mut e, "vieetj\x1b");
+    assert_eq!(
+        events,
+        vec![Event::EscRestore {
+            from: "việt".into(),
+            to: "vieetj".into()
+        }]
+    );
+}
+
+#[test]
+fn shortcut_follows_method_keys() {
+    let expand = |method: u8, table: Option<&str>| {
+        let mut e = Engine::new();
+        if let Some(t) = table {
+            e.set_method_table(TableMethod::parse(t).unwrap());
+        }
+        e.set_method(method);
+        e.shortcuts_mut()
+            .add(Shortcut::new("vn", "Việt Nam").for_method(InputMethod::Telex));
+        e.shortcuts_mut()
+            .add(Shortcut::new("hn", "Hà Nội").for_method(InputMethod::Vni));
+        type_word(&mut e, "vn hn ")
+    };
+    assert_eq!(expand(id::TELEX, None), "Việt Nam hn ");
+    assert_eq!(expand(id::VNI, None), "vn Hà Nội ");
+    assert_eq!(expand(id::TELEX_VNI, None), "Việt Nam Hà Nội ");
+    assert_eq!(expand(id::VIQR, None), "vn hn ");
+    // A letter-keyed table takes Telex shortcuts
+    assert_eq!(expand(id::CUSTOM, Some("sac = j")), "Việt Nam hn ");
+}
+
+#[test]
+fn event_shortcut_expanded() {
+    let mut e = Engine::new();
+    e.shortcuts_mut().add(Shortcut::new("vn", "Việt Nam"));
+    e.shortcuts_mut().add(Shortcut::immediate("->", "→"));
+    let events = events_for(&mut e, "vn ");
+    assert_eq!(
+        events,
+        vec![Event::ShortcutExpanded {
+            trigger: "vn".into(),
+            replacement: "Việt Nam".into()
+        }]
+    );
+
+    let mut e2 = Engine::new();
+    e2.shortcuts_mut().add(Shortcut::immediate("->", "→"));
+    e2.set_events_enabled(true);
+    e2.on_key_ext(keys::MINUS, false, false, false);
+    e2.on_key_ext(keys::DOT, false, false, true);
+    assert_eq!(
+        e2.take_events(),
+        vec![Event::ShortcutExpanded {
+            trigger: "->".into(),
+            replacement: "→".into()
+        }]
+    );
+}
+
+#[test]
+fn event_auto_capitalize() {
+    let mut e = Engine::new();
+    e.set_auto_capitalize(true);
+    let events = events_for(&mut e, "ok. ban");
+    assert!(events.contains(&Event::AutoCapitalize { letter: 'B' }));
+}
+
+#[test]
+fn events_off_by_default() {
+    let mut e = Engine::new();
+    type_word(&mut e, "vieetj ");
+    assert!(e.take_events().is_empty());
+}
+
+// ============================================================
+// FOCUS CONTEXTS
+// ============================================================
+
+#[test]
+fn focus_keeps_backspace_after_space() {
+    let mut e = Engine::new();
+    e.focus(1);
+    type_word(&mut e, "vieetj ");
+    e.focus(2);
+    type_word(&mut e, "abc ");
+    e.focus(1);
+
+    // Deleting the space restores "việt" for editing, as if focus never moved
+    let r = e.on_key(keys::DELETE, false, false);
+    assert_eq!((r.action, r.backspace), (Action::Send as u8, 1));
+    assert_eq!(e.get_buffer_string(), "việt");
+}
+
+#[test]
+fn focus_keeps_esc_restore() {
+    let mut e = Engine::new();
+    e.set_esc_restore(true);
+    e.focus(1);
+    type_word(&mut e, "vieetj");
+    e.focus(2);
+    type_word(&mut e, "nam");
+    e.focus(1);
+    assert_eq!(type_word(&mut e, "\x1b"), "vieetj");
+}
+
+#[test]
+fn focus_settings_are_shared() {
+    let mut e = Engine::new();
+    e.focus(1);
+    e.set_method(1);
+    e.focus(2);
+    assert_eq!(type_word(&mut e, "a1"), "á");
+}
+
+// ============================================================
+// METHOD TABLES
+// ============================================================
+
+const TELEX_TABLE: &str = "
+name = Telex (table)
+sac = s
+huyen = f
+hoi = r
+nga = x
+nang = j
+circumflex = a:a e:e o:o
+horn = w:aou
+stroke = d
+remove = z
+stroke_style = delayed
+w_as_vowel = true
+";
+
+const VNI_TABLE: &str = "
+sac = 1
+huyen = 2
+hoi = 3
+nga = 4
+nang = 5
+circumflex = 6
+horn = 7
+breve = 8
+stroke = 9
+remove = 0
+stroke_style = anywhere
+";
+
+const VIQR_TABLE: &str = "
+sac = '
+huyen = `
+hoi = ?
+nga = ~
+nang = .
+circumflex = ^
+horn = +
+breve = (
+stroke = d
+";
+
+/// A table describing a built-in method types exactly like it
+fn assert_table_matches(table: &str, method: u8, inputs: &[&str]) {
+    for input in inputs {
+        let mut builtin = Engine::new();
+        builtin.set_method(method);
+        let mut custom = Engine::new();
+        custom.set_method_table(TableMethod::parse(table).unwrap());
+        assert_eq!(
+            type_word(&mut custom, input),
+            type_word(&mut builtin, input),
+            "table vs method {} for '{}'",
+            method,
+            input
+        );
+    }
+}
+
+#[test]
+fn table_telex_matches_builtin() {
+    assert_table_matches(
+        TELEX_TABLE,
+        id::TELEX,
+        &[
+            "vieetj nam",
+            "dduowngf",
+            "nhw",
+            "data",
+            "xepse",
+            "tafoo",
+            "did",
+            "dods",
+            "asz",
+            "chuwa",
+            "text",
+        ],
+    );
+}
+
+#[test]
+fn table_vni_matches_builtin() {
+    assert_table_matches(
+        VNI_TABLE,
+        id::VNI,
+        &["vie65t nam", "d9u7o7ng2", "a11", "di9", "a@", "a10"],
+    );
+}
+
+#[test]
+fn table_viqr_matches_builtin() {
+    assert_table_matches(
+        VIQR_TABLE,
+        id::VIQR,
+        &["Vie^.t Nam", "ddu+o+`ng", "a''", "ba.n.", "cha`o\\.", "dad"],
+    );
+}
+
+#[test]
+fn table_hash_key() {
+    let mut e = Engine::new();
+    let table = "sac = \\# # Shift+3 types sắc\nhorn = w:aou";
+    e.set_method_table(TableMethod::parse(table).unwrap());
+    assert_eq!(type_word(&mut e, "a# uwa#"), "á ứa");
+}
+
+#[test]
+fn table_method_needs_loaded_table() {
+    let mut e = Engine::new();
+    // Nothing to select yet: method and settings stay unchanged
+    assert!(!e.set_method(id::CUSTOM));
+    let config = EngineConfig::parse("method=3\nmodern_tone=false\n").unwrap();
+    assert!(!e.apply_config(&config));
+    assert_eq!(e.config(), EngineConfig::default());
+
+    e.set_method_table(TableMethod::parse("sac = q").unwrap());
+    e.set_method(id::TELEX);
+    assert!(e.apply_config(&config));
+    assert_eq!(e.config().method, id::CUSTOM);
+    assert_eq!(type_word(&mut e, "aq"), "á");
+}
+
+#[test]
+fn unknown_method_rejected() {
+    let mut e = Engine::new();
+    e.set_method(id::VNI);
+    assert!(!e.set_method(9));
+    assert_eq!(e.config().method, id::VNI);
+    assert_eq!(EngineConfig::parse("method=9"), None);
+}
+
+#[test]
+fn table_survives_method_switch() {
+    let mut e = Engine::new();
+    e.set_method_table(TableMethod::parse("sac = q\nhorn = w").unwrap());
+    assert_eq!(type_word(&mut e, "aq"), "á");
+    e.set_method(id::TELEX);
+    assert_eq!(type_word(&mut e, " aq"), " aq");
+    e.set_method(id::CUSTOM);
+    assert_eq!(type_word(&mut e, " aq"), " á");
+    assert_eq!(e.config().method, id::CUSTOM);
+}
+
+// ============================================================
+// BRACKET VOWELS ([ → ơ, ] → ư)
+// ============================================================
+
+#[test]
+fn bracket_vowels_alone() {
+    telex_brackets(&[("[", "ơ"), ("]", "ư"), ("{", "Ơ"), ("}", "Ư")]);
+}
+
+#[test]
+fn bracket_vowels_in_words() {
+    telex_brackets(&[
+        ("t[s", "tớ"),
+        ("h[j", "hợ"),
+        ("m]a", "mưa"),
+        ("ng][if", "người"),
+        ("Tr]owngf", "Trường"),
+        ("nh][ngx", "nhưỡng"),
+        ("gi]xa", "giữa"),
+        ("thu[r", "thuở"),
+    ]);
+}
+
+#[test]
+fn bracket_vowels_after_mark() {
+    // Mark typed before the vowel moves onto it
+    telex_brackets(&[("thuj[", "thuợ")]);
+}
+
+#[test]
+fn bracket_vowels_invalid_stay_punctuation() {
+    telex_brackets(&[("a[", "a["), ("qu]", "qu]"), ("l]]", "lư]"), ("t[ ", "tơ ")]);
+}
+
+#[test]
+fn bracket_vowels_off_by_default() {
+    telex(&[("t[s", "t[s"), ("]", "]")]);
+}
+
+#[test]
+fn bracket_vowels_telex_only() {
+    let mut e = Engine::new();
+    e.set_bracket_vowels(true);
+    e.set_method(id::VNI);
+    assert_eq!(type_word(&mut e, "t[1"), "t[1");
+}
+
+#[test]
+fn bracket_vowels_esc_restore() {
+    let mut e = Engine::new();
+    e.set_bracket_vowels(true);
+    e.set_esc_restore(true);
+    assert_eq!(type_word(&mut e, "t[s\x1b"), "t[s");
+}
+
+#[test]
+fn bracket_vowels_auto_capitalize() {
+    let mut e = Engine::new();
+    e.set_bracket_vowels(true);
+    e.set_auto_capitalize(true);
+    assert_eq!(type_word(&mut e, "a. ]a"), "a. Ưa");
+}
+
+// ============================================================
+// TELEX + VNI
+// ============================================================
+
+#[test]
+fn telex_vni_either_scheme() {
+    telex_vni(&[
+        ("vieetj nam", "việt nam"),
+        ("vie65t nam", "việt nam"),
+        ("dduowngf", "đường"),
+        ("d9u7o7ng2", "đường"),
+    ]);
+}
+
+#[test]
+fn telex_vni_mixed_in_word() {
+    telex_vni(&[("vie6tj", "việt"), ("tieen1g", "tiếng"), ("nhw4", "nhữ")]);
+}
+
+#[test]
+fn telex_vni_stroke_follows_key() {
+    telex_vni(&[("did", "đi"), ("di9", "đi")]);
+}
+
+#[test]
+fn telex_vni_revert_and_remove() {
+    telex_vni(&[("ass", "as"), ("a11", "a1"), ("asz", "a"), ("a10", "a")]);
+}
+
+#[test]
+fn telex_vni_symbols_and_digits() {
+    // Shift+digit is still a symbol, plain digits without a vowel stay digits
+    telex_vni(&[("a@", "a@"), ("a1!", "á!"), ("2024 ", "2024 ")]);
+}
+
+// ============================================================
+// KEY BINDINGS
+// ============================================================
+
+#[test]
+fn key_bindings_programmer_telex() {
+    // f, j, z stay letters
+    telex_bindings(
+        "huyen:q nang: remove:",
+        &[
+            ("hoaq", "hoà"),
+            ("dduowngq", "đường"),
+            ("aqq", "aq"),
+            ("fix", "fix"),
+            ("jazz", "jazz"),
+        ],
+    );
+}
+
+#[test]
+fn key_bindings_tones_off_letters() {
+    // w and aa are plain
+    telex_bindings(
+        "horn:] circumflex:6",
+        &[
+            ("vie6tj", "việt"),
+            ("du]o]ngf", "dường"),
+            ("a]", "ă"),
+            ("tu]]", "tu]"),
+            ("aw", "aw"),
+            ("aa", "aa"),
+        ],
+    );
+}
+
+#[test]
+fn key_bindings_stroke_on_digit() {
+    // Finds d anywhere, dd is plain
+    telex_bindings("stroke:9", &[("di9", "đi"), ("dd", "dd")]);
+}
+
+#[test]
+fn key_bindings_reject_shared_key() {
+    let mut e = Engine::new();
+    // x still types ngã
+    assert!(!e.set_key_bindings(KeyBindings::parse("sac:x").unwrap()));
+    assert!(e.key_bindings().is_empty());
+    // Swapping two functions is fine
+    assert!(e.set_key_bindings(KeyBindings::parse("sac:x nga:s").unwrap()));
+    assert_eq!(type_word(&mut e, "ax as"), "á ã");
+}
+
+#[test]
+fn key_bindings_survive_method_switch() {
+    let mut e = Engine::new();
+    assert!(e.set_key_bindings(KeyBindings::parse("huyen:q").unwrap()));
+    e.set_method(id::VNI);
+    assert_eq!(type_word(&mut e, "aq a2"), "à a2");
+    e.set_method(id::TELEX);
+    assert_eq!(type_word(&mut e, " af"), " af");
+}
+
+#[test]
+fn key_bindings_config_roundtrip() {
+    let mut e = Engine::new();
+    let config = EngineConfig::parse("key_bindings=huyen:q nang:\n").unwrap();
+    e.apply_config(&config);
+    assert_eq!(e.config(), config);
+    assert_eq!(type_word(&mut e, "aq"), "à");
+
+    // Clashing bindings are dropped
+    let clash = EngineConfig::parse("key_bindings=sac:x\n").unwrap();
+    e.apply_config(&clash);
+    assert!(e.key_bindings().is_empty());
+
+    // Stroke on a shifted symbol is rejected, not silently dead
+    assert_eq!(EngineConfig::parse("key_bindings=stroke:@\n"), None);
+}
+
+#[test]
+fn key_bindings_shifted_symbol() {
+    // Marks fire on shifted symbols, strokes on unshifted ones
+    telex_bindings(
+        "sac:@ stroke:2",
+        &[("a@", "á"), ("d2i", "đi"), ("a2", "a2")],
+    );
+}
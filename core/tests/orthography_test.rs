@@ -0,0 +1,150 @@
+//! Orthography Tests - Central Highlands letters and finals
+
+mod common;
+use common::{telex, type_word};
+use gonhanh_core::data::{keys, orthography};
+use gonhanh_core::engine::{Action, Engine};
+use gonhanh_core::input::id;
+
+fn highlands(method: u8, cases: &[(&str, &str)]) {
+    for (input, expected) in cases {
+        let mut e = Engine::new();
+        e.set_method(method);
+        e.set_orthography(orthography::id::HIGHLANDS);
+        assert_eq!(
+            type_word(&mut e, input),
+            *expected,
+            "[{}] '{}'",
+            method,
+            input
+        );
+    }
+}
+
+// ============================================================
+// LETTERS
+// ============================================================
+
+const TELEX_LETTERS: &[(&str, &str)] = &[
+    ("ev", "ĕ"),
+    ("ov", "ŏ"),
+    ("uv", "ŭ"),
+    ("owv", "ơ\u{306}"),
+    ("cca", "ča"),
+    ("nnu", "ñu"),
+    ("Ev", "Ĕ"),
+    ("CCa", "Ča"),
+    // Breve reaches the last vowel over the final
+    ("ekv", "ĕk"),
+    // ă and đ keep their Telex keys
+    ("ddawk", "đăk"),
+    ("ccawk", "čăk"),
+];
+
+const VNI_LETTERS: &[(&str, &str)] = &[
+    ("e8", "ĕ"),
+    ("o78", "ơ\u{306}"),
+    ("u8", "ŭ"),
+    ("a8", "ă"),
+    ("ca9", "ča"),
+    ("nu9", "ñu"),
+    // 9 strokes d first
+    ("dan9", "đan"),
+];
+
+/// Typing the key again undoes it
+const UNDO: &[(&str, &str)] = &[("evv", "ev"), ("evvv", "evv"), ("ccc", "cc"), ("nnn", "nn")];
+
+/// Marks without precomposed forms are combining
+const MARKS: &[(&str, &str)] = &[
+    ("evs", "ĕ\u{301}"),
+    ("ovx", "ŏ\u{303}"),
+    ("owvnf", "ơ\u{306}\u{300}n"),
+    // Backspace deletes the letter with its combining marks
+    ("owvs<", ""),
+    ("evs<", ""),
+];
+
+// ============================================================
+// FINALS
+// ============================================================
+
+const FINALS: &[(&str, &str)] = &[
+    ("bals", "bál"),
+    ("mahs", "máh"),
+    ("anns", "áñ"),
+    ("Kroong", "Krông"),
+    ("xin chaof", "xin chào"),
+];
+
+#[test]
+fn telex_letters() {
+    highlands(id::TELEX, TELEX_LETTERS);
+}
+
+#[test]
+fn vni_letters() {
+    highlands(id::VNI, VNI_LETTERS);
+}
+
+#[test]
+fn telex_vni_letters() {
+    highlands(id::TELEX_VNI, &[("ev", "ĕ"), ("e8", "ĕ"), ("cca", "ča")]);
+}
+
+#[test]
+fn undo() {
+    highlands(id::TELEX, UNDO);
+    highlands(id::VNI, &[("e88", "e8"), ("ca99", "ca9")]);
+}
+
+#[test]
+fn marks() {
+    highlands(id::TELEX, MARKS);
+    highlands(id::VNI, &[("e81", "ĕ\u{301}")]);
+}
+
+#[test]
+fn finals() {
+    highlands(id::TELEX, FINALS);
+}
+
+#[test]
+fn standard_unchanged() {
+    telex(&[("ev", "ev"), ("cca", "cca"), ("bals", "bals")]);
+}
+
+#[test]
+fn esc_restores_keys() {
+    let mut e = Engine::new();
+    e.set_esc_restore(true);
+    e.set_orthography(orthography::id::HIGHLANDS);
+    assert_eq!(type_word(&mut e, "ccev\x1b"), "ccev");
+}
+
+#[test]
+fn restore_word_combining() {
+    let mut e = Engine::new();
+    e.set_orthography(orthography::id::HIGHLANDS);
+    e.restore_word("Ơ\u{306}ñĕ\u{301}");
+    assert_eq!(e.get_buffer_string(), "Ơ\u{306}ñĕ\u{301}");
+}
+
+#[test]
+fn backspace_counts_code_points() {
+    // Hosts delete one code point per backspace: ơ̆ takes two
+    let mut e = Engine::new();
+    e.set_orthography(orthography::id::HIGHLANDS);
+    type_word(&mut e, "owv");
+    let r = e.on_key(keys::S, false, false);
+    assert_eq!(r.backspace, 2);
+    assert_eq!(r.output().collect::<String>(), "ơ\u{306}\u{301}");
+    let r = e.on_key(keys::DELETE, false, false);
+    assert_eq!((r.action, r.backspace, r.count), (Action::Send as u8, 3, 0));
+
+    // Words picked up for editing are counted the same way
+    e.restore_word("ŏ\u{301}");
+    let r = e.on_key(keys::F, false, false);
+    assert_eq!(r.backspace, 2);
+    assert_eq!(r.output().collect::<String>(), "ŏ\u{300}");
+}
@@ -0,0 +1,169 @@
+//! Text Tests - utilities on existing text (diacritics, slugs, case, tone style)
+
+use gonhanh_core::data::charset::{self, id};
+use gonhanh_core::engine::config::EngineConfig;
+use gonhanh_core::engine::convert::convert;
+use gonhanh_core::input;
+use gonhanh_core::text::case::{self, change_case, to_sentence};
+use gonhanh_core::text::slug::{slugify, strip_diacritics, SlugOptions};
+use gonhanh_core::text::tone_style::normalize_tone_style;
+
+// ============================================================
+// DIACRITICS AND SLUGS
+// ============================================================
+
+const STRIP: &[(&str, &str)] = &[
+    (
+        "Cộng hoà xã hội chủ nghĩa Việt Nam",
+        "Cong hoa xa hoi chu nghia Viet Nam",
+    ),
+    ("ĐẠI HỌC QUỐC GIA", "DAI HOC QUOC GIA"),
+    ("khuỷu tay, giường", "khuyu tay, giuong"),
+    ("Đắk Lắk", "Dak Lak"),
+    // Non-Vietnamese text is kept
+    ("email@gõ.vn 🇻🇳", "email@go.vn 🇻🇳"),
+];
+
+const SLUGS: &[(&str, &str)] = &[
+    ("Hồ Chí Minh", "ho-chi-minh"),
+    ("Việt Nam", "viet-nam"),
+    ("Bài 1: Giới thiệu (phần 2)", "bai-1-gioi-thieu-phan-2"),
+    ("Đường Lê Lợi, Q.1", "duong-le-loi-q-1"),
+    ("--Ăn uống--", "an-uong"),
+];
+
+#[test]
+fn strip() {
+    for (text, expected) in STRIP {
+        assert_eq!(strip_diacritics(text), *expected, "'{}'", text);
+    }
+}
+
+#[test]
+fn strip_decomposed() {
+    let nfd = charset::encode("Nguyễn Đình Chiểu", id::NFD);
+    assert_eq!(strip_diacritics(&nfd), "Nguyen Dinh Chieu");
+}
+
+#[test]
+fn slugs() {
+    for (text, expected) in SLUGS {
+        assert_eq!(
+            slugify(text, &SlugOptions::default()),
+            *expected,
+            "'{}'",
+            text
+        );
+    }
+}
+
+#[test]
+fn slug_options() {
+    let filename = SlugOptions {
+        separator: '_',
+        lowercase: false,
+        max_len: 20,
+    };
+    assert_eq!(
+        slugify("Báo cáo tháng Mười Hai 2024.docx", &filename),
+        "Bao_cao_thang_Muoi"
+    );
+}
+
+// ============================================================
+// CASE
+// ============================================================
+
+#[test]
+fn case_vietnamese_letters() {
+    let text = "Ừ, đường ĐI khúc khuỷu";
+    let cases = [
+        (case::id::LOWER, "ừ, đường đi khúc khuỷu"),
+        (case::id::UPPER, "Ừ, ĐƯỜNG ĐI KHÚC KHUỶU"),
+        (case::id::TITLE, "Ừ, Đường Đi Khúc Khuỷu"),
+        (case::id::SENTENCE, "Ừ, đường đi khúc khuỷu"),
+        (case::id::TOGGLE, "ừ, ĐƯỜNG đi KHÚC KHUỶU"),
+    ];
+    for (case_id, expected) in cases {
+        assert_eq!(
+            change_case(text, case_id).as_deref(),
+            Some(expected),
+            "{}",
+            case::NAMES[case_id as usize]
+        );
+    }
+    // Decomposed marks follow their letter
+    let nfd = charset::encode("ướt", id::NFD);
+    assert_eq!(
+        change_case(&nfd, case::id::UPPER),
+        Some(charset::encode("ƯỚT", id::NFD))
+    );
+}
+
+#[test]
+fn sentence_matches_auto_capitalize() {
+    // Sentence case and the engine's auto-capitalize agree on boundaries
+    let options = EngineConfig {
+        auto_capitalize: true,
+        ..EngineConfig::default()
+    };
+    let typed = convert(
+        "Xin chaof! \"tooi ddeens. (vaf ddi) nhes? ok",
+        input::id::TELEX,
+        &options,
+    );
+    assert_eq!(typed, "Xin chào! \"Tôi đến. (Và đi) nhé? Ok");
+    assert_eq!(to_sentence(&typed.to_lowercase()), typed);
+}
+
+// ============================================================
+// TONE STYLE
+// ============================================================
+
+/// (traditional, modern)
+const TONE_STYLES: &[(&str, &str)] = &[
+    ("Hòa bình", "Hoà bình"),
+    ("Thủy lợi", "Thuỷ lợi"),
+    ("khỏe khoắn", "khoẻ khoắn"),
+    ("THÚY", "THUÝ"),
+    ("lòe loẹt", "loè loẹt"),
+    ("hủy bỏ, tùy ý", "huỷ bỏ, tuỳ ý"),
+];
+
+/// Placement is the same in both styles
+const SAME_IN_BOTH: &[&str] = &[
+    "hoàn toàn",
+    "quả quýt",
+    "già giặn",
+    "người Việt",
+    "khuyết tật",
+    "thuở xưa",
+];
+
+#[test]
+fn tone_style_both_ways() {
+    for (traditional, modern) in TONE_STYLES {
+        assert_eq!(normalize_tone_style(traditional, true), *modern);
+        assert_eq!(normalize_tone_style(modern, false), *traditional);
+        // Already in style: unchanged
+        assert_eq!(normalize_tone_style(modern, true), *modern);
+        assert_eq!(normalize_tone_style(traditional, false), *traditional);
+    }
+    for text in SAME_IN_BOTH {
+        assert_eq!(normalize_tone_style(text, true), *text);
+        assert_eq!(normalize_tone_style(text, false), *text);
+    }
+}
+
+#[test]
+fn tone_style_mixed_document() {
+    let text = "Hòa bình và hoà hợp: email hoà@vn.com, café, Thủy-Tùng 2024.";
+    assert_eq!(
+        normalize_tone_style(text, true),
+        "Hoà bình và hoà hợp: email hoà@vn.com, café, Thuỷ-Tùng 2024."
+    );
+    assert_eq!(
+        normalize_tone_style(text, false),
+        "Hòa bình và hòa hợp: email hòa@vn.com, café, Thủy-Tùng 2024."
+    );
+}
@@ -0,0 +1,164 @@
+//! VIQR Tests - Punctuation modifiers typed after the letter
+
+mod common;
+use common::{type_word, viqr};
+use gonhanh_core::engine::Engine;
+
+// ============================================================
+// BASIC MODIFIERS
+// ============================================================
+
+const VIQR_MARKS: &[(&str, &str)] = &[
+    ("a'", "á"),
+    ("a`", "à"),
+    ("a?", "ả"),
+    ("a~", "ã"),
+    ("a.", "ạ"),
+    ("A'", "Á"),
+];
+
+const VIQR_TONES: &[(&str, &str)] = &[
+    ("a(", "ă"),
+    ("a^", "â"),
+    ("e^", "ê"),
+    ("o^", "ô"),
+    ("o+", "ơ"),
+    ("u+", "ư"),
+    ("dd", "đ"),
+    ("DD", "Đ"),
+];
+
+const VIQR_COMBINED: &[(&str, &str)] = &[
+    ("a^'", "ấ"),
+    ("a('", "ắ"),
+    ("o+~", "ỡ"),
+    ("e^.", "ệ"),
+    ("u+?", "ử"),
+];
+
+// ============================================================
+// WORDS & SENTENCES
+// ============================================================
+
+const VIQR_WORDS: &[(&str, &str)] = &[
+    ("Vie^.t Nam", "Việt Nam"),
+    ("tie^'ng Vie^.t", "tiếng Việt"),
+    ("ddu+o+`ng", "đường"),
+    ("nu+o+'c", "nước"),
+    ("kho^ng", "không"),
+    ("ha(ng ha'i", "hăng hái"),
+    ("ngu+o+`i", "người"),
+    ("to^i yeu Vie^.t Nam", "tôi yeu Việt Nam"),
+];
+
+// Mark typed after the final consonant moves to the right vowel
+const VIQR_DELAYED: &[(&str, &str)] = &[
+    ("vie^t.", "việt"),
+    ("toa'n", "toán"),
+    ("hoa`", "hoà"),
+    ("tru+o+`ng", "trường"),
+];
+
+// ============================================================
+// SWITCH, UNDO & PUNCTUATION
+// ============================================================
+
+const VIQR_SWITCH: &[(&str, &str)] = &[
+    // Another mark replaces the previous one
+    ("a'`", "à"),
+    // ` and ~ share a key
+    ("a`~", "ã"),
+    ("a~`", "à"),
+    // Circumflex → horn
+    ("o^+", "ơ"),
+];
+
+const VIQR_UNDO: &[(&str, &str)] = &[
+    // Same modifier twice gives the literal symbol
+    ("a''", "a'"),
+    ("a..", "a."),
+    ("a^^", "a^"),
+    ("ddd", "dd"),
+    ("a..b", "a.b"),
+];
+
+const VIQR_ESCAPE: &[(&str, &str)] = &[
+    // A mark already on the word is not applied again
+    ("ba.n.", "bạn."),
+    // \ keeps the next symbol literal
+    ("cha`o\\.", "chào."),
+    ("Vie^.t\\?", "Việt?"),
+    ("cha`o\\. To^i", "chào. Tôi"),
+];
+
+const VIQR_PUNCTUATION: &[(&str, &str)] = &[
+    // Modifiers don't apply without a vowel or outside a word
+    ("b'", "b'"),
+    ("'a", "'a"),
+    ("t.", "t."),
+    // Non-modifier punctuation still breaks the word
+    ("a,", "a,"),
+    ("ba,n", "ba,n"),
+    // Plain digits are not tones
+    ("a6", "a6"),
+    ("a9", "a9"),
+    // "dad" stays English (stroke is only dd)
+    ("dad", "dad"),
+];
+
+// ============================================================
+// TESTS
+// ============================================================
+
+#[test]
+fn viqr_marks() {
+    viqr(VIQR_MARKS);
+}
+
+#[test]
+fn viqr_tones() {
+    viqr(VIQR_TONES);
+}
+
+#[test]
+fn viqr_combined() {
+    viqr(VIQR_COMBINED);
+}
+
+#[test]
+fn viqr_words() {
+    viqr(VIQR_WORDS);
+}
+
+#[test]
+fn viqr_delayed() {
+    viqr(VIQR_DELAYED);
+}
+
+#[test]
+fn viqr_switch() {
+    viqr(VIQR_SWITCH);
+}
+
+#[test]
+fn viqr_undo() {
+    viqr(VIQR_UNDO);
+}
+
+#[test]
+fn viqr_punctuation() {
+    viqr(VIQR_PUNCTUATION);
+}
+
+#[test]
+fn viqr_escape() {
+    viqr(VIQR_ESCAPE);
+}
+
+#[test]
+fn viqr_esc_restores_keystrokes() {
+    let mut e = Engine::new();
+    e.set_method(2);
+    e.set_esc_restore(true);
+    assert_eq!(type_word(&mut e, "nu+o+'c\x1b"), "nu+o+'c");
+}
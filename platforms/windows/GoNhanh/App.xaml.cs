@@ -106,6 +106,9 @@ public partial class App : System.Windows.Application
     {
         if (!_settings.IsEnabled) return;
 
+        // Keep per-window typing state (backspace-after-space, ESC restore)
+        RustBridge.Focus(AppDetector.ForegroundWindow);
+
         var result = RustBridge.ProcessKey(e.VirtualKeyCode, e.Shift, e.CapsLock);
 
         if (result.Action == ImeAction.Send && result.Count > 0)
@@ -31,6 +31,11 @@ public static class AppDetector
         "chrome", "msedge", "firefox", "brave", "opera", "vivaldi", "arc"
     };
 
+    /// <summary>
+    /// Current foreground window handle
+    /// </summary>
+    public static IntPtr ForegroundWindow => GetForegroundWindow();
+
     // Cache to avoid repeated process lookups
     private static string? _cachedProcessName;
     private static IntPtr _cachedWindow;
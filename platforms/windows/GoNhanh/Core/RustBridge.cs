@@ -5,14 +5,22 @@ namespace GoNhanh.Core;
 
 /// <summary>
 /// P/Invoke bridge to Rust core library (gonhanh_core.dll)
-/// FFI contract matches core/src/lib.rs exports
+/// FFI contract matches core/include/gonhanh.h
 /// </summary>
 public static class RustBridge
 {
     private const string DllName = "gonhanh_core.dll";
 
+    /// <summary>
+    /// ABI version these declarations match (GONHANH_ABI_VERSION in gonhanh.h)
+    /// </summary>
+    private const uint AbiVersion = 1;
+
     #region Native Imports
 
+    [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
+    private static extern uint ime_abi_version();
+
     [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
     private static extern void ime_init();
 
@@ -22,6 +30,9 @@ public static class RustBridge
     [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
     private static extern void ime_free(IntPtr result);
 
+    [DllImport(DllName, CallingConvention = CallingConvention.Cdecl, CharSet = CharSet.Unicode)]
+    private static extern long ime_result_utf16(IntPtr result, [Out] char[]? output, long maxLen);
+
     [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
     private static extern void ime_method(byte method);
 
@@ -38,6 +49,9 @@ public static class RustBridge
         [MarshalAs(UnmanagedType.U1)] bool ctrl,
         [MarshalAs(UnmanagedType.U1)] bool shift);
 
+    [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
+    private static extern IntPtr ime_key_platform(byte keyNamespace, uint code, byte mods);
+
     [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
     private static extern void ime_skip_w_shortcut([MarshalAs(UnmanagedType.U1)] bool skip);
 
@@ -56,6 +70,9 @@ public static class RustBridge
     [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
     private static extern void ime_clear_all();
 
+    [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
+    private static extern void ime_focus(ulong contextId);
+
     [DllImport(DllName, CallingConvention = CallingConvention.Cdecl)]
     private static extern int ime_get_buffer(IntPtr outPtr, int maxLen);
 
@@ -77,9 +94,17 @@ public static class RustBridge
 
     /// <summary>
     /// Initialize the IME engine. Call once at startup.
+    /// Throws if the DLL was built for a different ABI (mismatched signatures
+    /// would corrupt memory instead of failing).
     /// </summary>
     public static void Initialize()
     {
+        uint dllVersion = ime_abi_version();
+        if (dllVersion != AbiVersion)
+        {
+            throw new InvalidOperationException(
+                $"{DllName} ABI version {dllVersion} does not match expected {AbiVersion}");
+        }
         ime_init();
     }
 
@@ -117,44 +142,28 @@ public static class RustBridge
 
     /// <summary>
     /// Process a keystroke and get the result
-    /// Translates Windows Virtual Key codes to macOS keycodes for the Rust core
+    /// Windows Virtual Key codes are translated to core keycodes by the Rust core
     /// </summary>
     /// <param name="keycode">Windows Virtual Key code</param>
     /// <param name="shift">Shift key pressed</param>
     /// <param name="capslock">CapsLock is on</param>
     public static ImeResult ProcessKey(ushort keycode, bool shift, bool capslock)
     {
-        // Translate Windows VK to macOS keycode (Rust core uses macOS keycodes)
-        ushort macKeycode = TranslateToMacKeycode(keycode);
-        if (macKeycode == 0xFFFF) // Unknown key
-        {
-            return ImeResult.Empty;
-        }
-
-        try
-        {
-            // Call ime_key_ext with correct parameter order: (key, caps, ctrl, shift)
-            IntPtr ptr = ime_key_ext(macKeycode, capslock, false, shift);
+        return ProcessKeyDebug(keycode, shift, capslock).result;
+    }
 
-            if (ptr == IntPtr.Zero)
-            {
-                return ImeResult.Empty;
-            }
+    /// <summary>
+    /// Read result text as UTF-16 (core handles surrogate pairs for emoji)
+    /// </summary>
+    private static string GetResultText(IntPtr ptr)
+    {
+        long len = ime_result_utf16(ptr, null, 0);
+        if (len <= 0)
+            return string.Empty;
 
-            try
-            {
-                var native = Marshal.PtrToStructure<NativeResult>(ptr);
-                return ImeResult.FromNative(native);
-            }
-            finally
-            {
-                ime_free(ptr);
-            }
-        }
-        catch
-        {
-            return ImeResult.Empty;
-        }
+        var buffer = new char[len + 1];
+        ime_result_utf16(ptr, buffer, buffer.Length);
+        return new string(buffer, 0, (int)len);
     }
 
     /// <summary>
@@ -162,15 +171,11 @@ public static class RustBridge
     /// </summary>
     public static (ImeResult result, bool ptrWasNull) ProcessKeyDebug(ushort keycode, bool shift, bool capslock)
     {
-        ushort macKeycode = TranslateToMacKeycode(keycode);
-        if (macKeycode == 0xFFFF)
-        {
-            return (ImeResult.Empty, true);
-        }
+        byte mods = (byte)((capslock ? MOD_CAPS : 0) | (shift ? MOD_SHIFT : 0));
 
         try
         {
-            IntPtr ptr = ime_key_ext(macKeycode, capslock, false, shift);
+            IntPtr ptr = ime_key_platform(NAMESPACE_WIN_VK, keycode, mods);
 
             if (ptr == IntPtr.Zero)
             {
@@ -180,7 +185,7 @@ public static class RustBridge
             try
             {
                 var native = Marshal.PtrToStructure<NativeResult>(ptr);
-                return (ImeResult.FromNative(native), false);
+                return (ImeResult.FromNative(native, GetResultText(ptr)), false);
             }
             finally
             {
@@ -244,7 +249,7 @@ public static class RustBridge
 
     /// <summary>
     /// Clear buffer and word history
-    /// Call when cursor position changes (mouse click, arrow keys, focus change)
+    /// Call when cursor position changes (mouse click, arrow keys)
     /// This prevents accidental restore from stale history
     /// </summary>
     public static void ClearAll()
@@ -252,6 +257,15 @@ public static class RustBridge
         ime_clear_all();
     }
 
+    /// <summary>
+    /// Switch engine state to the given window (no-op if unchanged)
+    /// Each window keeps its own buffer and word history
+    /// </summary>
+    public static void Focus(IntPtr hwnd)
+    {
+        ime_focus((ulong)hwnd.ToInt64());
+    }
+
     /// <summary>
     /// Get the full composed buffer as UTF-32 codepoints
     /// Used for "Select All + Replace" injection method where the entire buffer content is needed
@@ -372,129 +386,12 @@ public static class RustBridge
 
     #endregion
 
-    #region Keycode Translation (Windows VK -> macOS)
-
-    // macOS virtual keycodes (from core/src/data/keys.rs)
-    private const ushort MAC_A = 0x00;
-    private const ushort MAC_S = 0x01;
-    private const ushort MAC_D = 0x02;
-    private const ushort MAC_F = 0x03;
-    private const ushort MAC_H = 0x04;
-    private const ushort MAC_G = 0x05;
-    private const ushort MAC_Z = 0x06;
-    private const ushort MAC_X = 0x07;
-    private const ushort MAC_C = 0x08;
-    private const ushort MAC_V = 0x09;
-    private const ushort MAC_B = 0x0B;
-    private const ushort MAC_Q = 0x0C;
-    private const ushort MAC_W = 0x0D;
-    private const ushort MAC_E = 0x0E;
-    private const ushort MAC_R = 0x0F;
-    private const ushort MAC_Y = 0x10;
-    private const ushort MAC_T = 0x11;
-    private const ushort MAC_O = 0x1F;
-    private const ushort MAC_U = 0x20;
-    private const ushort MAC_I = 0x22;
-    private const ushort MAC_P = 0x23;
-    private const ushort MAC_L = 0x25;
-    private const ushort MAC_J = 0x26;
-    private const ushort MAC_K = 0x28;
-    private const ushort MAC_N = 0x2D;
-    private const ushort MAC_M = 0x2E;
-    private const ushort MAC_N1 = 0x12;
-    private const ushort MAC_N2 = 0x13;
-    private const ushort MAC_N3 = 0x14;
-    private const ushort MAC_N4 = 0x15;
-    private const ushort MAC_N5 = 0x17;
-    private const ushort MAC_N6 = 0x16;
-    private const ushort MAC_N7 = 0x1A;
-    private const ushort MAC_N8 = 0x1C;
-    private const ushort MAC_N9 = 0x19;
-    private const ushort MAC_N0 = 0x1D;
-    private const ushort MAC_SPACE = 0x31;
-    private const ushort MAC_DELETE = 0x33;
-    private const ushort MAC_TAB = 0x30;
-    private const ushort MAC_RETURN = 0x24;
-    private const ushort MAC_ESC = 0x35;
-    private const ushort MAC_LBRACKET = 0x21;
-    private const ushort MAC_RBRACKET = 0x1E;
-    private const ushort MAC_DOT = 0x2F;       // Period/dot key
-    private const ushort MAC_COMMA = 0x2B;     // Comma key
-    private const ushort MAC_SLASH = 0x2C;     // Forward slash key (? with shift)
-    private const ushort MAC_SEMICOLON = 0x29; // Semicolon key (: with shift)
-    private const ushort MAC_QUOTE = 0x27;     // Quote key
-    private const ushort MAC_MINUS = 0x1B;     // Minus key
-    private const ushort MAC_EQUAL = 0x18;     // Equal key
+    #region Key Event Constants
 
-    /// <summary>
-    /// Translate Windows Virtual Key code to macOS keycode
-    /// Returns 0xFFFF if key is not mapped
-    /// </summary>
-    private static ushort TranslateToMacKeycode(ushort windowsVK)
-    {
-        return windowsVK switch
-        {
-            // Letters A-Z
-            0x41 => MAC_A,  // VK_A
-            0x42 => MAC_B,  // VK_B
-            0x43 => MAC_C,  // VK_C
-            0x44 => MAC_D,  // VK_D
-            0x45 => MAC_E,  // VK_E
-            0x46 => MAC_F,  // VK_F
-            0x47 => MAC_G,  // VK_G
-            0x48 => MAC_H,  // VK_H
-            0x49 => MAC_I,  // VK_I
-            0x4A => MAC_J,  // VK_J
-            0x4B => MAC_K,  // VK_K
-            0x4C => MAC_L,  // VK_L
-            0x4D => MAC_M,  // VK_M
-            0x4E => MAC_N,  // VK_N
-            0x4F => MAC_O,  // VK_O
-            0x50 => MAC_P,  // VK_P
-            0x51 => MAC_Q,  // VK_Q
-            0x52 => MAC_R,  // VK_R
-            0x53 => MAC_S,  // VK_S
-            0x54 => MAC_T,  // VK_T
-            0x55 => MAC_U,  // VK_U
-            0x56 => MAC_V,  // VK_V
-            0x57 => MAC_W,  // VK_W
-            0x58 => MAC_X,  // VK_X
-            0x59 => MAC_Y,  // VK_Y
-            0x5A => MAC_Z,  // VK_Z
-
-            // Numbers 0-9
-            0x30 => MAC_N0,  // VK_0
-            0x31 => MAC_N1,  // VK_1
-            0x32 => MAC_N2,  // VK_2
-            0x33 => MAC_N3,  // VK_3
-            0x34 => MAC_N4,  // VK_4
-            0x35 => MAC_N5,  // VK_5
-            0x36 => MAC_N6,  // VK_6
-            0x37 => MAC_N7,  // VK_7
-            0x38 => MAC_N8,  // VK_8
-            0x39 => MAC_N9,  // VK_9
-
-            // Special keys
-            0x08 => MAC_DELETE,   // VK_BACK (Backspace)
-            0x09 => MAC_TAB,      // VK_TAB
-            0x0D => MAC_RETURN,   // VK_RETURN (Enter)
-            0x1B => MAC_ESC,      // VK_ESCAPE
-            0x20 => MAC_SPACE,    // VK_SPACE
-            0xDB => MAC_LBRACKET, // VK_OEM_4 ([{)
-            0xDD => MAC_RBRACKET, // VK_OEM_6 (]})
-
-            // Punctuation keys (needed for auto-capitalize: . ! ?)
-            0xBE => MAC_DOT,       // VK_OEM_PERIOD (.)
-            0xBC => MAC_COMMA,     // VK_OEM_COMMA (,)
-            0xBF => MAC_SLASH,     // VK_OEM_2 (/ or ? with shift)
-            0xBA => MAC_SEMICOLON, // VK_OEM_1 (; or : with shift)
-            0xDE => MAC_QUOTE,     // VK_OEM_7 (' or " with shift)
-            0xBD => MAC_MINUS,     // VK_OEM_MINUS (-)
-            0xBB => MAC_EQUAL,     // VK_OEM_PLUS (=)
-
-            _ => 0xFFFF  // Unknown key
-        };
-    }
+    // Keycode namespace and modifier bits for ime_key_platform (core/src/data/keymap.rs)
+    private const byte NAMESPACE_WIN_VK = 1;
+    private const byte MOD_CAPS = 0x01;
+    private const byte MOD_SHIFT = 0x04;
 
     #endregion
 }
@@ -540,44 +437,30 @@ public readonly struct ImeResult
     public readonly ImeAction Action;
     public readonly byte Backspace;
     public readonly byte Count;
-    private readonly uint[] _chars;
+    private readonly string _text;
 
-    public static readonly ImeResult Empty = new(ImeAction.None, 0, 0, Array.Empty<uint>());
+    public static readonly ImeResult Empty = new(ImeAction.None, 0, 0, string.Empty);
 
-    private ImeResult(ImeAction action, byte backspace, byte count, uint[] chars)
+    private ImeResult(ImeAction action, byte backspace, byte count, string text)
     {
         Action = action;
         Backspace = backspace;
         Count = count;
-        _chars = chars;
+        _text = text;
     }
 
-    internal static ImeResult FromNative(NativeResult native)
+    internal static ImeResult FromNative(NativeResult native, string text)
     {
         return new ImeResult(
             (ImeAction)native.action,
             native.backspace,
             native.count,
-            native.chars ?? Array.Empty<uint>()
+            text
         );
     }
 
     /// <summary>
-    /// Get the result text as a string
+    /// Get the result text as a string (UTF-16 from the core)
     /// </summary>
-    public string GetText()
-    {
-        if (Count == 0 || _chars == null)
-            return string.Empty;
-
-        var sb = new StringBuilder(Count);
-        for (int i = 0; i < Count && i < _chars.Length; i++)
-        {
-            if (_chars[i] > 0)
-            {
-                sb.Append(char.ConvertFromUtf32((int)_chars[i]));
-            }
-        }
-        return sb.ToString();
-    }
+    public string GetText() => _text ?? string.Empty;
 }